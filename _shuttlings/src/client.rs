@@ -0,0 +1,447 @@
+//! The settings of the clients that a validation run sends its requests with
+
+use std::{
+    net::{IpAddr, SocketAddr},
+    path::Path,
+    str::FromStr,
+    time::Duration,
+};
+
+use reqwest::{
+    header::{self, HeaderMap, HeaderValue},
+    redirect::Policy,
+    Url,
+};
+
+use crate::{testing, UNIX_SCHEME};
+
+/// How many redirects a request follows before its test fails
+pub const DEFAULT_REDIRECTS: usize = 3;
+
+/// How the certificates of https servers are checked
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum TlsVerification {
+    /// Only trust the usual root certificates
+    #[default]
+    Default,
+    /// Also trust the certificates in this PEM bundle, e.g. a self-signed one
+    CaCert(Vec<u8>),
+    /// Accept any certificate. Only use this on a network you trust.
+    Insecure,
+}
+
+impl TlsVerification {
+    /// Trust the certificates in the PEM file at `path`, which has to have at least one
+    pub fn ca_cert(path: &Path) -> Result<Self, String> {
+        let pem = std::fs::read(path)
+            .map_err(|e| format!("Failed to read certificate file {}: {e}", path.display()))?;
+        match reqwest::Certificate::from_pem_bundle(&pem) {
+            Ok(certs) if !certs.is_empty() => Ok(Self::CaCert(pem)),
+            _ => Err(format!("{} has no PEM certificates", path.display())),
+        }
+    }
+}
+
+/// Which proxy the requests go through
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Proxy {
+    /// The one in the `HTTP_PROXY`, `HTTPS_PROXY` or `ALL_PROXY` environment variables,
+    /// unless the host is in `NO_PROXY`
+    #[default]
+    FromEnv,
+    /// This proxy for every request, whatever the environment says
+    Url(String),
+    /// No proxy, whatever the environment says
+    Disabled,
+}
+
+/// Which HTTP versions the requests use. Websockets always connect with an HTTP/1.1 upgrade.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HttpVersion {
+    /// Only HTTP/1.1, which is what the hosted validation uses
+    #[default]
+    Http1,
+    /// HTTP/2 if an https server offers it, and HTTP/1.1 otherwise
+    Auto,
+    /// Only HTTP/2, which is h2c for http servers
+    Http2,
+}
+
+impl FromStr for HttpVersion {
+    type Err = String;
+
+    /// Parse `auto`, `1` or `2`
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "1" => Ok(Self::Http1),
+            "2" => Ok(Self::Http2),
+            _ => Err(format!("`{s}` is not an HTTP version, use auto, 1 or 2")),
+        }
+    }
+}
+
+/// Which redirects the requests of a run follow
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Redirects {
+    /// At most this many for each request, after which its test fails.
+    /// A response that arrived through a redirect is a warning, and a failure in a strict run.
+    Follow(usize),
+    /// None, so that the tests get the redirect responses
+    Disabled,
+}
+
+impl Default for Redirects {
+    fn default() -> Self {
+        Self::Follow(DEFAULT_REDIRECTS)
+    }
+}
+
+impl std::fmt::Display for Redirects {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Follow(max) => write!(f, "{max}"),
+            Self::Disabled => f.write_str("none"),
+        }
+    }
+}
+
+impl FromStr for Redirects {
+    type Err = String;
+
+    /// Parse a number of redirects, or `none`
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "none" => Ok(Self::Disabled),
+            _ => s
+                .parse()
+                .map(Self::Follow)
+                .map_err(|_| format!("`{s}` is not a number of redirects or none")),
+        }
+    }
+}
+
+/// A host and port whose connections go to another address, like curl's `--resolve`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolve {
+    pub host: String,
+    pub port: u16,
+    pub addr: IpAddr,
+}
+
+impl FromStr for Resolve {
+    type Err = String;
+
+    /// Parse `HOST:PORT:ADDR` like curl, where an IPv6 address may be in brackets
+    fn from_str(s: &str) -> Result<Self, String> {
+        let invalid = || format!("`{s}` is not HOST:PORT:ADDR, e.g. example.com:443:127.0.0.1");
+        let mut parts = s.splitn(3, ':');
+        let (Some(host), Some(port), Some(addr)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+        let addr = addr.trim_start_matches('[').trim_end_matches(']');
+        match (port.parse(), addr.parse()) {
+            (Ok(port), Ok(addr)) if !host.is_empty() => Ok(Self {
+                host: host.to_ascii_lowercase(),
+                port,
+                addr,
+            }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// The entries of `resolve` for the port of `url`
+pub fn resolve_for(url: &str, resolve: &[Resolve]) -> Vec<Resolve> {
+    let port = Url::parse(url).ok().and_then(|u| u.port_or_known_default());
+    resolve
+        .iter()
+        .filter(|r| Some(r.port) == port)
+        .cloned()
+        .collect()
+}
+
+/// The address that `url` connects to instead of its host, if `resolve` has one
+pub fn resolved(url: &str, resolve: &[Resolve]) -> Option<SocketAddr> {
+    let url = Url::parse(url).ok()?;
+    let port = url.port_or_known_default()?;
+    resolve
+        .iter()
+        .find(|r| Some(r.host.as_str()) == url.host_str() && r.port == port)
+        .map(|r| SocketAddr::new(r.addr, r.port))
+}
+
+/// Parse a base URL given by the user. `http://` is added if it has no scheme, and trailing
+/// slashes are removed so that challenge paths can be appended to it, after any path prefix.
+/// A Unix socket is given by its path, like `unix:///run/app.sock`, see [`UNIX_SCHEME`].
+pub fn normalize_url(input: &str) -> Result<String, String> {
+    let input = input.trim();
+    if let Some(path) = input.strip_prefix(UNIX_SCHEME) {
+        return unix_socket_url(input, path);
+    }
+    let with_scheme = if input.contains("://") {
+        input.to_owned()
+    } else {
+        format!("http://{input}")
+    };
+    let url = Url::parse(&with_scheme).map_err(|e| format!("`{input}` is not a valid URL: {e}"))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(format!(
+            "`{input}` has the unsupported scheme `{}`, use http, https or unix",
+            url.scheme()
+        ));
+    }
+    if url.query().is_some() || url.fragment().is_some() {
+        return Err(format!("`{input}` must not have a query or fragment"));
+    }
+    Ok(url.as_str().trim_end_matches('/').to_owned())
+}
+
+/// A `unix://` URL, whose path is the socket
+#[cfg(unix)]
+fn unix_socket_url(input: &str, path: &str) -> Result<String, String> {
+    if !path.starts_with('/') {
+        return Err(format!(
+            "`{input}` must have an absolute path, like unix:///run/app.sock"
+        ));
+    }
+    Ok(input.to_owned())
+}
+
+#[cfg(not(unix))]
+fn unix_socket_url(input: &str, _path: &str) -> Result<String, String> {
+    Err(format!(
+        "`{input}` is a Unix socket, which this platform doesn't have"
+    ))
+}
+
+/// A builder of a client with these settings, which connects to `unix_socket` if it is set.
+/// Fails if the URL of `proxy` is not a valid proxy URL.
+pub fn builder(
+    tls: &TlsVerification,
+    proxy: &Proxy,
+    http_version: HttpVersion,
+    redirects: Redirects,
+    unix_socket: Option<&Path>,
+    resolve: &[Resolve],
+) -> Result<reqwest::ClientBuilder, String> {
+    let builder = reqwest::ClientBuilder::new()
+        .connect_timeout(Duration::from_secs(3))
+        .redirect(match redirects {
+            Redirects::Follow(max) => Policy::limited(max),
+            Redirects::Disabled => Policy::none(),
+        })
+        .referer(false)
+        .timeout(Duration::from_secs(60))
+        .default_headers(HeaderMap::from_iter([(
+            header::ACCEPT_ENCODING,
+            HeaderValue::from_static(testing::ACCEPT_ENCODING),
+        )]));
+    // reqwest reads the proxy environment variables by default
+    let builder = match proxy {
+        Proxy::FromEnv => builder,
+        Proxy::Url(url) => builder.proxy(
+            reqwest::Proxy::all(url)
+                .map_err(|e| format!("`{url}` is not a valid proxy URL: {e}"))?,
+        ),
+        Proxy::Disabled => builder.no_proxy(),
+    };
+    let builder = match http_version {
+        HttpVersion::Http1 => builder.http1_only(),
+        HttpVersion::Auto => builder,
+        HttpVersion::Http2 => builder.http2_prior_knowledge(),
+    };
+    let builder = resolve.iter().fold(builder, |b, r| {
+        b.resolve(&r.host, SocketAddr::new(r.addr, r.port))
+    });
+    // which replaces the proxy, since the connections don't go over TCP
+    #[cfg(unix)]
+    let builder = match unix_socket {
+        Some(path) => builder.unix_socket(path),
+        None => builder,
+    };
+    // URLs only name Unix sockets on Unix
+    #[cfg(not(unix))]
+    let _ = unix_socket;
+    Ok(match tls {
+        TlsVerification::Default => builder,
+        TlsVerification::CaCert(pem) => reqwest::Certificate::from_pem_bundle(pem)
+            .unwrap_or_default()
+            .into_iter()
+            .fold(builder, |b, cert| b.add_root_certificate(cert)),
+        TlsVerification::Insecure => builder.danger_accept_invalid_certs(true),
+    })
+}
+
+/// Whether sending the request again can succeed. A connection that was dropped may have been dropped
+/// after the server handled the request, so only requests that can be handled twice are sent again then.
+pub fn is_transient(e: &reqwest::Error, method: &reqwest::Method) -> bool {
+    if e.is_connect() {
+        return true;
+    }
+    if !method.is_idempotent() {
+        return false;
+    }
+    let mut source = std::error::Error::source(e);
+    while let Some(err) = source {
+        if let Some(io) = err.downcast_ref::<std::io::Error>() {
+            return matches!(
+                io.kind(),
+                std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::BrokenPipe
+            );
+        }
+        source = err.source();
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A self-signed certificate for localhost and 127.0.0.1
+    const SELF_SIGNED: &str = "\
+-----BEGIN CERTIFICATE-----
+MIIBlzCCAT6gAwIBAgIUQ/xu7TO8vXvfUsRwb/spU2j6xfYwCgYIKoZIzj0EAwIw
+FDESMBAGA1UEAwwJbG9jYWxob3N0MCAXDTI2MTAxNjEyMTUyNFoYDzIxMjYwOTIy
+MTIxNTI0WjAUMRIwEAYDVQQDDAlsb2NhbGhvc3QwWTATBgcqhkjOPQIBBggqhkjO
+PQMBBwNCAAQp0vMT9v69stGt/2Oo13/PWwGeLM9R4h2sc7MxcEbW0Q1Gd3T5tCih
+7nRQIxzqNq5/QD1K2b5dhn3wqg9sEJNpo2wwajAdBgNVHQ4EFgQUEgjvmKQWMdkR
+LHUQL7IO8fGnVU8wHwYDVR0jBBgwFoAUEgjvmKQWMdkRLHUQL7IO8fGnVU8wGgYD
+VR0RBBMwEYIJbG9jYWxob3N0hwR/AAABMAwGA1UdEwEB/wQCMAAwCgYIKoZIzj0E
+AwIDRwAwRAIgWMmxNjU0qzrrt1N8LCCpIeQbRPXN7422pq8CrDGCapsCICHq4zLs
+WHm/lbTkE+5zLeTML3U0c8KdeAMg/S5YPXKz
+-----END CERTIFICATE-----
+";
+
+    #[test]
+    fn ca_cert_needs_a_pem_certificate() {
+        let path = std::env::temp_dir().join(format!("shuttlings-{}.pem", std::process::id()));
+        std::fs::write(&path, SELF_SIGNED).unwrap();
+        assert_eq!(
+            TlsVerification::ca_cert(&path),
+            Ok(TlsVerification::CaCert(SELF_SIGNED.into()))
+        );
+        std::fs::write(&path, "not a certificate").unwrap();
+        assert!(TlsVerification::ca_cert(&path)
+            .unwrap_err()
+            .ends_with("has no PEM certificates"));
+        std::fs::remove_file(&path).unwrap();
+        assert!(TlsVerification::ca_cert(&path)
+            .unwrap_err()
+            .starts_with("Failed to read"));
+    }
+
+    #[test]
+    fn base_urls_are_normalized() {
+        assert_eq!(
+            normalize_url("127.0.0.1:8000").unwrap(),
+            "http://127.0.0.1:8000"
+        );
+        assert_eq!(
+            normalize_url("localhost:8000/").unwrap(),
+            "http://localhost:8000"
+        );
+        assert_eq!(
+            normalize_url(" https://example.com/myapp// ").unwrap(),
+            "https://example.com/myapp"
+        );
+        assert_eq!(
+            normalize_url("HTTP://Example.com").unwrap(),
+            "http://example.com"
+        );
+        assert!(normalize_url("ftp://example.com")
+            .unwrap_err()
+            .contains("unsupported scheme `ftp`"));
+        assert!(normalize_url("http://example.com/?a=1").is_err());
+        assert!(normalize_url("http://").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unix_socket_urls_keep_their_path() {
+        assert_eq!(
+            normalize_url("unix:///run/app.sock").unwrap(),
+            "unix:///run/app.sock"
+        );
+        assert!(normalize_url("unix://app.sock")
+            .unwrap_err()
+            .contains("absolute path"));
+    }
+
+    #[test]
+    fn http_versions_are_auto_1_or_2() {
+        assert_eq!("auto".parse(), Ok(HttpVersion::Auto));
+        assert_eq!("1".parse(), Ok(HttpVersion::Http1));
+        assert_eq!("2".parse(), Ok(HttpVersion::Http2));
+        assert!("3".parse::<HttpVersion>().is_err());
+    }
+
+    #[test]
+    fn invalid_proxy_urls_fail_the_builder() {
+        let builder = |proxy: &Proxy| {
+            let redirects = Redirects::default();
+            builder(
+                &TlsVerification::Default,
+                proxy,
+                HttpVersion::Http1,
+                redirects,
+                None,
+                &[],
+            )
+        };
+        assert!(builder(&Proxy::Url("http://127.0.0.1:3128".into())).is_ok());
+        assert!(builder(&Proxy::Url("http://[::1".into()))
+            .unwrap_err()
+            .contains("is not a valid proxy URL"));
+    }
+
+    #[test]
+    fn redirects_are_a_number_or_none() {
+        assert_eq!("5".parse(), Ok(Redirects::Follow(5)));
+        assert_eq!("none".parse(), Ok(Redirects::Disabled));
+        assert!("-1".parse::<Redirects>().is_err());
+        assert!("all".parse::<Redirects>().is_err());
+        assert_eq!(Redirects::default().to_string(), "3");
+    }
+
+    #[test]
+    fn resolves_are_parsed_like_curl() {
+        let resolve = |host: &str, port, addr: &str| Resolve {
+            host: host.to_owned(),
+            port,
+            addr: addr.parse().unwrap(),
+        };
+        assert_eq!(
+            "MyApp.example.com:443:127.0.0.2".parse(),
+            Ok(resolve("myapp.example.com", 443, "127.0.0.2"))
+        );
+        assert_eq!(
+            "example.com:80:[::1]".parse(),
+            Ok(resolve("example.com", 80, "::1"))
+        );
+        assert!("example.com:443".parse::<Resolve>().is_err());
+        assert!("example.com:https:127.0.0.1".parse::<Resolve>().is_err());
+        assert_eq!(
+            resolve_for("https://example.com", &[resolve("example.com", 443, "::1")]).len(),
+            1
+        );
+        assert!(
+            resolve_for("http://example.com", &[resolve("example.com", 443, "::1")]).is_empty()
+        );
+        assert_eq!(
+            resolved(
+                "https://example.com/4/strength",
+                &[resolve("example.com", 443, "::1")]
+            ),
+            Some("[::1]:443".parse().unwrap())
+        );
+        assert_eq!(
+            resolved("https://other.com", &[resolve("example.com", 443, "::1")]),
+            None
+        );
+    }
+}
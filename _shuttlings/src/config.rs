@@ -0,0 +1,136 @@
+//! The options of a validation run that the validators of every year have
+
+use std::{sync::Arc, time::Duration};
+
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    client::{HttpVersion, Proxy, Redirects, Resolve, TlsVerification},
+    har::Har,
+    testing::{Strictness, DEFAULT_MAX_RESPONSE_SIZE},
+    transport::Transport,
+};
+
+/// How many times a request is retried after a connection error
+pub const DEFAULT_RETRIES: u32 = 3;
+
+/// Options for a validation run, with the `extras` that only the challenges of one year have
+#[derive(Debug, Clone)]
+pub struct ValidationConfig<E> {
+    /// Timeout for the whole challenge
+    pub timeout: Timeout,
+    /// Only validate this task of the challenge
+    pub task: Option<i32>,
+    /// How many times a request is retried after a connection error before the test fails
+    pub retries: u32,
+    /// How many bytes of a response body are read before the test fails, so that an endless body can't exhaust the memory
+    pub max_response_size: usize,
+    /// Record every request and response of the run, and every websocket handshake, in this HAR file
+    pub har: Option<Har>,
+    /// Send the requests through this instead of the client of each challenge,
+    /// e.g. a [`crate::har::Replay`] of a recording. No preflight request is sent then.
+    pub transport: Option<Arc<dyn Transport>>,
+    /// After the tests pass, send malformed requests to the endpoints they used,
+    /// and warn about the server errors and dropped connections they cause, see [`crate::probe`]
+    pub probe: bool,
+    /// Seed of the data that the run generates, e.g. the malformed requests of [`Self::probe`],
+    /// so that a run with the same seed generates the same data. A random one if not set.
+    pub seed: Option<u64>,
+    /// How closely the responses have to follow the challenge text, e.g. whether a wrong content type
+    /// fails its test, see [`crate::testing::check_content_type`]
+    pub strictness: Strictness,
+    /// Validate the remaining tasks of the challenge after a task fails
+    pub keep_going: bool,
+    /// Stops the validation when cancelled, which ends the run in [`crate::SubmissionState::Error`]
+    pub cancel: CancellationToken,
+    /// Multiplies the pauses between the requests of the challenges whose timing matters,
+    /// and the elapsed times they expect
+    pub time_scale: f64,
+    /// Send one request to the base URL before validating, and end the run in
+    /// [`crate::RunOutcome::ConnectionFailed`] without running any test if it can't be sent
+    pub preflight: bool,
+    /// How the certificates of https servers are checked
+    pub tls: TlsVerification,
+    /// Which proxy the requests go through
+    pub proxy: Proxy,
+    /// Which HTTP versions the requests use
+    pub http_version: HttpVersion,
+    /// Which redirects the requests follow
+    pub redirects: Redirects,
+    /// Connect to these addresses instead of looking up their hosts, like curl's `--resolve`.
+    /// Only the ones for the port of the URL are used, since the client can't tell ports apart.
+    pub resolve: Vec<Resolve>,
+    /// Forward at most one [`crate::SubmissionUpdate::Save`] per this window, for a receiver that
+    /// writes the submission on each one, see `debounce::SaveDebouncer`
+    pub save_debounce: Option<Duration>,
+    /// The options that only the challenges of this validator have
+    pub extras: E,
+}
+
+impl<E: Default> Default for ValidationConfig<E> {
+    fn default() -> Self {
+        Self {
+            timeout: Timeout::Scaled(1.0),
+            task: None,
+            retries: DEFAULT_RETRIES,
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+            har: None,
+            transport: None,
+            probe: false,
+            seed: None,
+            strictness: Strictness::Default,
+            keep_going: false,
+            cancel: CancellationToken::new(),
+            time_scale: 1.0,
+            preflight: true,
+            tls: TlsVerification::Default,
+            proxy: Proxy::FromEnv,
+            http_version: HttpVersion::Http1,
+            redirects: Redirects::default(),
+            resolve: Vec::new(),
+            save_debounce: None,
+            extras: E::default(),
+        }
+    }
+}
+
+/// How long a challenge may take before its validation is stopped
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Timeout {
+    /// The challenge's own timeout, multiplied by this factor
+    Scaled(f64),
+    /// The same timeout for every challenge
+    Fixed(Duration),
+    /// Let the validation run until it completes
+    Disabled,
+}
+
+impl Timeout {
+    /// The timeout for a challenge whose own timeout is `own`, or `None` if it may run until it completes
+    pub fn for_challenge(&self, own: Duration) -> Option<Duration> {
+        match *self {
+            Timeout::Scaled(factor) => Some(own.mul_f64(factor)),
+            Timeout::Fixed(timeout) => Some(timeout),
+            Timeout::Disabled => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timeout_is_scaled_per_challenge() {
+        let own = Duration::from_secs(60);
+        assert_eq!(
+            Timeout::Scaled(1.5).for_challenge(own),
+            Some(Duration::from_secs(90))
+        );
+        assert_eq!(
+            Timeout::Fixed(Duration::from_secs(5)).for_challenge(own),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(Timeout::Disabled.for_challenge(own), None);
+    }
+}
@@ -0,0 +1,164 @@
+//! Fewer [`SubmissionUpdate::Save`] updates for receivers that write to a database on each one.
+//! The validators send one after nearly every task, which can be many in quick succession.
+
+use std::time::Duration;
+
+use tokio::{
+    sync::mpsc::{self, Sender},
+    task::JoinHandle,
+    time::{sleep_until, Instant},
+};
+
+use crate::{SubmissionState, SubmissionUpdate};
+
+/// Forwards the updates sent to its [`SaveDebouncer::sender`] to another channel as they come,
+/// except for a `Save` within the window after the last one, which is held until the window ends.
+/// The saves held in a window are forwarded as one, and right after a `Done` or `Error` state.
+#[derive(Debug)]
+pub struct SaveDebouncer {
+    tx: Sender<SubmissionUpdate>,
+    forwarder: JoinHandle<()>,
+}
+
+impl SaveDebouncer {
+    /// Forward to `tx` with at most one `Save` per `window`.
+    /// Must be called in a tokio runtime, which the forwarding runs in.
+    pub fn new(tx: Sender<SubmissionUpdate>, window: Duration) -> Self {
+        let (debounced_tx, rx) = mpsc::channel(tx.max_capacity());
+        Self {
+            tx: debounced_tx,
+            forwarder: tokio::spawn(forward(rx, tx, window)),
+        }
+    }
+
+    /// The sender to send the updates with, which is closed once the forwarded ones can't be sent
+    pub fn sender(&self) -> Sender<SubmissionUpdate> {
+        self.tx.clone()
+    }
+
+    /// Wait until every update is forwarded, including a held `Save`.
+    /// The senders from [`SaveDebouncer::sender`] have to be dropped first.
+    pub async fn finish(self) {
+        drop(self.tx);
+        let _ = self.forwarder.await;
+    }
+}
+
+async fn forward(
+    mut rx: mpsc::Receiver<SubmissionUpdate>,
+    tx: Sender<SubmissionUpdate>,
+    window: Duration,
+) {
+    // when the last save was forwarded, and whether one is held until the window after it ends
+    let mut last_save: Option<Instant> = None;
+    let mut held = false;
+    loop {
+        let window_end = last_save.map(|s| s + window).filter(|_| held);
+        let flush = tokio::select! {
+            update = rx.recv() => match update {
+                Some(SubmissionUpdate::Save)
+                    if last_save.is_some_and(|s| s.elapsed() < window) =>
+                {
+                    held = true;
+                    false
+                }
+                Some(SubmissionUpdate::Save) => true,
+                Some(update) => {
+                    let finished = matches!(
+                        update,
+                        SubmissionUpdate::State(SubmissionState::Done | SubmissionState::Error)
+                    );
+                    if tx.send(update).await.is_err() {
+                        return;
+                    }
+                    finished && held
+                }
+                None => break,
+            },
+            // the future is created even when the branch is disabled
+            _ = sleep_until(window_end.unwrap_or_else(Instant::now)), if window_end.is_some() => true,
+        };
+        if flush {
+            if tx.send(SubmissionUpdate::Save).await.is_err() {
+                return;
+            }
+            last_save = Some(Instant::now());
+            held = false;
+        }
+    }
+    if held {
+        let _ = tx.send(SubmissionUpdate::Save).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_secs(1);
+
+    fn log(line: &str) -> SubmissionUpdate {
+        line.to_owned().into()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn saves_within_the_window_are_forwarded_as_one() {
+        let started = Instant::now();
+        let (tx, mut rx) = mpsc::channel(32);
+        let debouncer = SaveDebouncer::new(tx, WINDOW);
+        let updates = debouncer.sender();
+        for update in [
+            SubmissionUpdate::Save,
+            log("Task 1: completed"),
+            SubmissionUpdate::Save,
+            log("Task 2: completed"),
+            SubmissionUpdate::Save,
+        ] {
+            updates.send(update).await.unwrap();
+        }
+        assert_eq!(rx.recv().await, Some(SubmissionUpdate::Save));
+        // the other updates are not held with the saves
+        assert_eq!(rx.recv().await, Some(log("Task 1: completed")));
+        assert_eq!(rx.recv().await, Some(log("Task 2: completed")));
+        assert_eq!(started.elapsed(), Duration::ZERO);
+        assert_eq!(rx.recv().await, Some(SubmissionUpdate::Save));
+        assert_eq!(started.elapsed(), WINDOW);
+
+        drop(updates);
+        debouncer.finish().await;
+        assert_eq!(rx.recv().await, None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn held_saves_are_flushed_when_the_submission_ends() {
+        let started = Instant::now();
+        let (tx, mut rx) = mpsc::channel(32);
+        let debouncer = SaveDebouncer::new(tx, WINDOW);
+        let updates = debouncer.sender();
+        for update in [
+            SubmissionUpdate::Save,
+            SubmissionUpdate::Save,
+            SubmissionState::Done.into(),
+            SubmissionUpdate::Save,
+        ] {
+            updates.send(update).await.unwrap();
+        }
+        drop(updates);
+        debouncer.finish().await;
+        let mut forwarded = Vec::new();
+        while let Some(update) = rx.recv().await {
+            forwarded.push(update);
+        }
+        assert_eq!(
+            forwarded,
+            [
+                SubmissionUpdate::Save,
+                SubmissionState::Done.into(),
+                SubmissionUpdate::Save,
+                // the last one is flushed when the updates end
+                SubmissionUpdate::Save,
+            ]
+        );
+        assert_eq!(started.elapsed(), Duration::ZERO);
+    }
+}
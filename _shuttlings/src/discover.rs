@@ -0,0 +1,124 @@
+//! Finding the challenge server on a common local port, for `--discover`
+
+use reqwest::{redirect, StatusCode};
+use tokio::{task::JoinSet, time::Duration};
+
+/// The ports that `cargo shuttle run` and the usual frameworks serve on, in the order they are preferred
+pub const PORTS: &[u16] = &[
+    8000, 8001, 8002, 8003, 8004, 8005, 8006, 8007, 8008, 8009, 8010, 3000,
+];
+/// How long a port has to answer a probe
+const PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A request that a challenge server answers with `status`, and other servers most likely don't,
+/// e.g. `/-1/error` with the error of challenge -1
+#[derive(Debug, Clone, Copy)]
+pub struct Signature {
+    pub path: &'static str,
+    pub status: StatusCode,
+}
+
+/// The server to validate instead of `url`, which can't be connected to because of `reason`:
+/// the one server on [`PORTS`] of localhost that answers like a challenge server.
+/// If no server or several servers answer, the error says so, with the ones to choose from.
+pub async fn discover(url: &str, reason: &str, signature: Signature) -> Result<String, String> {
+    let candidates = candidates(PORTS, signature).await;
+    match candidates.as_slice() {
+        [found] => Ok(found.clone()),
+        [] => Err(format!(
+            "Could not connect to {url} ({reason}), and no challenge server answered on localhost ports 8000-8010 or 3000"
+        )),
+        _ => Err(format!(
+            "Could not connect to {url} ({reason}), and several servers answered like a challenge server, \
+            choose one with --url: {}",
+            candidates.join(", ")
+        )),
+    }
+}
+
+/// The base URLs of the servers on `ports` of localhost that answer like a challenge server, in the order of `ports`
+async fn candidates(ports: &[u16], signature: Signature) -> Vec<String> {
+    // the servers are local, and a redirect could be the answer of the signature
+    let client = reqwest::Client::builder()
+        .no_proxy()
+        .redirect(redirect::Policy::none())
+        .http1_only()
+        .build()
+        .expect("a client without TLS settings to build");
+    let mut probes = JoinSet::new();
+    for (i, port) in ports.iter().enumerate() {
+        let (client, url) = (client.clone(), format!("http://127.0.0.1:{port}"));
+        probes.spawn(async move {
+            let found = answers_like_a_challenge_server(&client, &url, signature).await;
+            (i, found.then_some(url))
+        });
+    }
+    let mut probed = probes.join_all().await;
+    probed.sort();
+    probed.into_iter().filter_map(|(_, url)| url).collect()
+}
+
+/// Whether the server at `url` answers `/` with a success, or the request of `signature` like a challenge server
+async fn answers_like_a_challenge_server(
+    client: &reqwest::Client,
+    url: &str,
+    signature: Signature,
+) -> bool {
+    let status = |path: &str| {
+        let req = client.get(format!("{url}{path}")).timeout(PROBE_TIMEOUT);
+        async move { req.send().await.map(|res| res.status()).ok() }
+    };
+    match status("/").await {
+        Some(status) if status.is_success() => true,
+        Some(_) => status(signature.path).await == Some(signature.status),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    const SIGNATURE: Signature = Signature {
+        path: "/-1/error",
+        status: StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    /// A server that answers each request with `status`, and its port
+    async fn serve(status: &'static str) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = [0; 4096];
+                let _ = stream.read(&mut request).await;
+                let response =
+                    format!("HTTP/1.1 {status}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n");
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        port
+    }
+
+    #[tokio::test]
+    async fn only_servers_that_answer_like_a_challenge_server_are_candidates() {
+        let challenge = serve("200 OK").await;
+        let other = serve("404 Not Found").await;
+        // a port that nothing listens on anymore
+        let closed = {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            listener.local_addr().unwrap().port()
+        };
+        assert_eq!(
+            candidates(&[other, closed, challenge], SIGNATURE).await,
+            [format!("http://127.0.0.1:{challenge}")]
+        );
+        let again = serve("200 OK").await;
+        assert_eq!(candidates(&[again, challenge], SIGNATURE).await.len(), 2);
+    }
+}
@@ -0,0 +1,482 @@
+//! Checks of the environment that a validation runs in, each with advice when it finds a problem:
+//! whether the host resolves, how long connecting and a round trip take, whether the clocks agree,
+//! and whether the latency endangers the timing-sensitive challenges.
+
+use std::{
+    net::{IpAddr, SocketAddr},
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+use serde::Serialize;
+use tokio::{
+    net::{lookup_host, TcpStream},
+    time::{timeout, Instant},
+};
+
+use crate::{
+    testing::{failure_reason, high_latency, HIGH_LATENCY},
+    Catalog, UNIX_BASE_URL, UNIX_SCHEME,
+};
+
+/// How long a lookup, connection or request of a check may take
+pub const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+/// How many round trips the latency is measured over, like the withdrawals of a rate limited sequence
+const ROUND_TRIPS: usize = 5;
+/// How far the clock of the server may be off, beyond the second that a `Date` header is rounded to
+const MAX_CLOCK_SKEW: Duration = Duration::from_secs(5);
+
+/// How a check went
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Ok,
+    /// The check does not apply, e.g. a TLS handshake with an http server, or an earlier check failed
+    Skipped,
+    /// A problem that can make some tests fail
+    Warning,
+    /// A problem that makes the validation fail
+    Failed,
+}
+
+/// What a check found
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    /// What was checked, e.g. `DNS resolution`
+    pub check: &'static str,
+    pub status: Status,
+    pub detail: String,
+    /// What to do about a problem
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub advice: Option<String>,
+}
+
+impl Finding {
+    fn new(check: &'static str, status: Status, detail: impl Into<String>) -> Self {
+        Self {
+            check,
+            status,
+            detail: detail.into(),
+            advice: None,
+        }
+    }
+
+    fn advise(self, advice: impl Into<String>) -> Self {
+        Self {
+            advice: Some(advice.into()),
+            ..self
+        }
+    }
+}
+
+/// The server that the checks connect to, and what the validator tells about it
+#[derive(Debug)]
+pub struct Target<'a> {
+    /// The base URL of the server, which only goes in the `Host` header for a Unix socket
+    pub url: &'a str,
+    /// The address that the host connects to instead of looking it up, like curl's `--resolve`
+    pub resolved: Option<SocketAddr>,
+    /// Whether the connections go to a Unix socket, which has no host to resolve or connect to
+    pub unix_socket: bool,
+    /// A client with the settings of the validation, which has no connections yet
+    pub client: reqwest::Client,
+    /// The challenges that high latency endangers
+    pub timing_sensitive: &'a [String],
+    /// What makes the timing-sensitive challenges more tolerant of latency
+    pub latency_advice: &'a str,
+}
+
+/// How a validator connects to a server, see [`Examine::connect`]
+#[derive(Debug)]
+pub struct Connection {
+    /// A client with the settings of the validation, which has no connections yet
+    pub client: reqwest::Client,
+    /// The address that the host connects to instead of looking it up, like curl's `--resolve`
+    pub resolved: Option<SocketAddr>,
+}
+
+/// A validator whose environment the checks can examine, see [`doctor`]
+pub trait Examine: Catalog {
+    /// The options of a run, which the checks connect with
+    type Config;
+
+    /// What makes the timing-sensitive challenges more tolerant of latency
+    const LATENCY_ADVICE: &'static str;
+
+    /// The challenges that high latency endangers
+    fn timing_sensitive() -> Vec<Self::Challenge>;
+
+    /// How a run connects to `base_url` with `config`, or to `unix_socket` if it is set.
+    /// Fails if `config` can't build a client, e.g. with an invalid proxy URL.
+    fn connect(
+        base_url: &str,
+        unix_socket: Option<&Path>,
+        config: &Self::Config,
+    ) -> Result<Connection, String>;
+}
+
+/// Check the environment of validating the server at `url` with `config`,
+/// with a client of its own so that the connection is made by the checks
+pub async fn doctor<K: Examine>(url: &str, config: &K::Config) -> Vec<Finding> {
+    let unix_socket = url.strip_prefix(UNIX_SCHEME).map(Path::new);
+    let base_url = match unix_socket {
+        Some(_) => UNIX_BASE_URL,
+        None => url,
+    };
+    let Connection { client, resolved } = match K::connect(base_url, unix_socket, config) {
+        Ok(connection) => connection,
+        Err(reason) => return vec![Finding::new("Client", Status::Failed, reason)],
+    };
+    let timing_sensitive = K::timing_sensitive()
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    diagnose(&Target {
+        url: base_url,
+        resolved,
+        unix_socket: unix_socket.is_some(),
+        client,
+        timing_sensitive: &timing_sensitive,
+        latency_advice: K::LATENCY_ADVICE,
+    })
+    .await
+}
+
+/// Run the checks against `target`, in order. The checks that need a connection are skipped without one.
+pub async fn diagnose(target: &Target<'_>) -> Vec<Finding> {
+    let Ok(url) = reqwest::Url::parse(target.url) else {
+        return vec![Finding::new(
+            "URL",
+            Status::Failed,
+            format!("`{}` is not a valid URL", target.url),
+        )];
+    };
+    let host = url.host_str().unwrap_or_default();
+    let port = url.port_or_known_default().unwrap_or(80);
+    let (dns, addr) = resolve(target, host, port).await;
+    let mut findings = vec![dns];
+    let (tcp, connect_time) = match addr {
+        Some(addr) => connect(addr, port).await,
+        None if target.unix_socket => {
+            let detail = "the connections go to a Unix socket";
+            (Finding::new("TCP connect", Status::Skipped, detail), None)
+        }
+        None => {
+            let detail = "the host could not be resolved";
+            (Finding::new("TCP connect", Status::Skipped, detail), None)
+        }
+    };
+    let connected = tcp.status == Status::Ok || target.unix_socket;
+    findings.push(tcp);
+    let https = url.scheme() == "https";
+    if !connected {
+        for check in ["TLS handshake", "HTTP round trip", "Clock skew", "Latency"] {
+            let detail = "there is no connection to the server";
+            findings.push(Finding::new(check, Status::Skipped, detail));
+        }
+        return findings;
+    }
+
+    // the first request makes the connection, and the others reuse it
+    let started = Instant::now();
+    let first = target
+        .client
+        .get(target.url)
+        .timeout(CHECK_TIMEOUT)
+        .send()
+        .await;
+    let first_time = started.elapsed();
+    let res = match first {
+        Ok(res) => res,
+        Err(e) => {
+            let reason = failure_reason(&e);
+            if https && e.is_connect() {
+                findings.push(
+                    Finding::new(
+                        "TLS handshake",
+                        Status::Failed,
+                        format!("the handshake with {host} failed: {reason}"),
+                    )
+                    .advise(
+                        "If the server has a self-signed certificate, trust it with --cacert, \
+                        or skip the check with --insecure on a network you trust.",
+                    ),
+                );
+            } else {
+                let (status, detail) = match https {
+                    true => (Status::Ok, "the TLS handshake succeeded"),
+                    false => (Status::Skipped, "the server is http"),
+                };
+                findings.push(Finding::new("TLS handshake", status, detail));
+            }
+            findings.push(
+                Finding::new(
+                    "HTTP round trip",
+                    Status::Failed,
+                    format!("GET / failed: {reason}"),
+                )
+                .advise("Check that the server answers HTTP requests, e.g. with curl."),
+            );
+            for check in ["Clock skew", "Latency"] {
+                let detail = "the server did not answer";
+                findings.push(Finding::new(check, Status::Skipped, detail));
+            }
+            return findings;
+        }
+    };
+    let (status, date) = (
+        res.status(),
+        res.headers().get(reqwest::header::DATE).cloned(),
+    );
+    let received = SystemTime::now();
+    let _ = res.bytes().await;
+    let mut round_trips = Vec::new();
+    for _ in 0..ROUND_TRIPS {
+        let started = Instant::now();
+        let Ok(res) = target
+            .client
+            .get(target.url)
+            .timeout(CHECK_TIMEOUT)
+            .send()
+            .await
+        else {
+            break;
+        };
+        let _ = res.bytes().await;
+        round_trips.push(started.elapsed());
+    }
+    let round_trip = average(&round_trips).unwrap_or(first_time);
+
+    findings.push(match https {
+        // what the first request took longer than the others, without connecting
+        true => {
+            let handshake = first_time
+                .saturating_sub(round_trip)
+                .saturating_sub(connect_time.unwrap_or_default());
+            let detail = format!("the handshake took about {} ms", handshake.as_millis());
+            Finding::new("TLS handshake", Status::Ok, detail)
+        }
+        false => Finding::new("TLS handshake", Status::Skipped, "the server is http"),
+    });
+    findings.push(Finding::new(
+        "HTTP round trip",
+        Status::Ok,
+        format!(
+            "GET / answered {status} in {} ms, and in {} ms with a new connection",
+            round_trip.as_millis(),
+            first_time.as_millis()
+        ),
+    ));
+    findings.push(clock_skew(
+        date.as_ref().and_then(|d| d.to_str().ok()),
+        received,
+    ));
+    findings.push(latency(target, &round_trips));
+    findings
+}
+
+/// The finding of looking up `host`, and the address to connect to if there is one
+async fn resolve(target: &Target<'_>, host: &str, port: u16) -> (Finding, Option<SocketAddr>) {
+    const CHECK: &str = "DNS resolution";
+    if target.unix_socket {
+        let detail = "the connections go to a Unix socket";
+        return (Finding::new(CHECK, Status::Skipped, detail), None);
+    }
+    if let Some(addr) = target.resolved {
+        let detail = format!("{host} is resolved to {addr} by --resolve");
+        return (Finding::new(CHECK, Status::Skipped, detail), Some(addr));
+    }
+    if let Ok(ip) = host
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse::<IpAddr>()
+    {
+        let detail = format!("{host} is an IP address");
+        return (
+            Finding::new(CHECK, Status::Skipped, detail),
+            Some(SocketAddr::new(ip, port)),
+        );
+    }
+    let started = Instant::now();
+    let found = match timeout(CHECK_TIMEOUT, lookup_host((host, port))).await {
+        Ok(Ok(mut addrs)) => addrs.next().ok_or_else(|| "it has no addresses".to_owned()),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(format!("no answer within {CHECK_TIMEOUT:?}")),
+    };
+    match found {
+        Ok(addr) => {
+            let detail = format!(
+                "{host} resolved to {} in {} ms",
+                addr.ip(),
+                started.elapsed().as_millis()
+            );
+            (Finding::new(CHECK, Status::Ok, detail), Some(addr))
+        }
+        Err(reason) => {
+            let finding = Finding::new(
+                CHECK,
+                Status::Failed,
+                format!("{host} could not be resolved: {reason}"),
+            )
+            .advise(format!(
+                "Check the host of --url. If its DNS record is not set up yet, \
+                connect to the address of the server with --resolve {host}:{port}:ADDR."
+            ));
+            (finding, None)
+        }
+    }
+}
+
+/// The finding of connecting to `addr`, and how long it took if it succeeded
+async fn connect(addr: SocketAddr, port: u16) -> (Finding, Option<Duration>) {
+    const CHECK: &str = "TCP connect";
+    let started = Instant::now();
+    let reason = match timeout(CHECK_TIMEOUT, TcpStream::connect(addr)).await {
+        Ok(Ok(_)) => {
+            let took = started.elapsed();
+            let detail = format!("connected to {addr} in {} ms", took.as_millis());
+            return (Finding::new(CHECK, Status::Ok, detail), Some(took));
+        }
+        Ok(Err(e)) if e.raw_os_error().is_some() => e.kind().to_string(),
+        Ok(Err(e)) => e.to_string(),
+        Err(_) => format!("no connection within {CHECK_TIMEOUT:?}"),
+    };
+    let finding = Finding::new(
+        CHECK,
+        Status::Failed,
+        format!("could not connect to {addr}: {reason}"),
+    )
+    .advise(format!(
+        "Check that the server is running and listens on port {port}. \
+        `cargo shuttle run` serves on port 8000, and --discover looks for the server on the common local ports."
+    ));
+    (finding, None)
+}
+
+/// The finding of comparing the `Date` header of a response with the time it was `received`
+fn clock_skew(date: Option<&str>, received: SystemTime) -> Finding {
+    const CHECK: &str = "Clock skew";
+    let Some(date) = date else {
+        return Finding::new(CHECK, Status::Skipped, "the response has no Date header");
+    };
+    let Ok(date) = chrono::DateTime::parse_from_rfc2822(date) else {
+        let detail = format!("the Date header {date:?} is not an HTTP date");
+        return Finding::new(CHECK, Status::Skipped, detail);
+    };
+    let received = chrono::DateTime::<chrono::Utc>::from(received);
+    let skew = (received - date.to_utc())
+        .abs()
+        .to_std()
+        .unwrap_or_default();
+    // the header has no fractions of a second
+    let skew = skew.saturating_sub(Duration::from_secs(1));
+    let detail = format!("the clocks differ by {} s", skew.as_secs());
+    if skew <= MAX_CLOCK_SKEW {
+        return Finding::new(CHECK, Status::Ok, detail);
+    }
+    Finding::new(CHECK, Status::Warning, detail).advise(
+        "Synchronize the clocks of this machine and the server, e.g. with NTP. \
+        The tests that check times, like the expiry of a token, can fail otherwise.",
+    )
+}
+
+/// The finding of whether the `round_trips` are fast enough for the timing-sensitive challenges
+fn latency(target: &Target<'_>, round_trips: &[Duration]) -> Finding {
+    const CHECK: &str = "Latency";
+    let Some(average) = average(round_trips) else {
+        return Finding::new(CHECK, Status::Skipped, "the round trips failed");
+    };
+    let challenges = target.timing_sensitive.join(", ");
+    let average = average.as_millis();
+    if !high_latency(round_trips) {
+        let detail = format!(
+            "round trips take {average} ms on average, \
+            which the timing-sensitive challenges ({challenges}) allow"
+        );
+        return Finding::new(CHECK, Status::Ok, detail);
+    }
+    let detail = format!(
+        "round trips take {average} ms on average, more than the {} ms \
+        that the timing-sensitive challenges ({challenges}) allow",
+        HIGH_LATENCY.as_millis()
+    );
+    Finding::new(CHECK, Status::Warning, detail).advise(target.latency_advice)
+}
+
+fn average(durations: &[Duration]) -> Option<Duration> {
+    let count = u32::try_from(durations.len()).ok().filter(|&n| n > 0)?;
+    Some(durations.iter().sum::<Duration>() / count)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{routing::get, Router};
+
+    use super::*;
+
+    fn target<'a>(url: &'a str, timing_sensitive: &'a [String]) -> Target<'a> {
+        Target {
+            url,
+            resolved: None,
+            unix_socket: false,
+            client: reqwest::Client::builder().no_proxy().build().unwrap(),
+            timing_sensitive,
+            latency_advice: "Validate closer to the server.",
+        }
+    }
+
+    fn statuses(findings: &[Finding]) -> Vec<(&str, Status)> {
+        findings.iter().map(|f| (f.check, f.status)).collect()
+    }
+
+    #[tokio::test]
+    async fn a_local_server_passes_the_checks() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let app = Router::new().route("/", get(|| async { "Hello, bird!" }));
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let timing_sensitive = ["9".to_owned(), "12".to_owned()];
+        let findings = diagnose(&target(&url, &timing_sensitive)).await;
+        assert_eq!(
+            statuses(&findings),
+            [
+                ("DNS resolution", Status::Skipped),
+                ("TCP connect", Status::Ok),
+                ("TLS handshake", Status::Skipped),
+                ("HTTP round trip", Status::Ok),
+                ("Clock skew", Status::Ok),
+                ("Latency", Status::Ok),
+            ]
+        );
+        assert!(findings[5]
+            .detail
+            .ends_with("timing-sensitive challenges (9, 12) allow"));
+        assert!(findings.iter().all(|f| f.advice.is_none()));
+    }
+
+    #[tokio::test]
+    async fn no_server_fails_with_advice() {
+        let port = {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            listener.local_addr().unwrap().port()
+        };
+        let url = format!("http://127.0.0.1:{port}");
+        let findings = diagnose(&target(&url, &[])).await;
+        assert_eq!(findings[1].status, Status::Failed);
+        assert!(findings[1].advice.as_ref().unwrap().contains("--discover"));
+        assert!(findings[2..].iter().all(|f| f.status == Status::Skipped));
+    }
+
+    #[test]
+    fn skewed_clocks_are_warnings() {
+        let received = SystemTime::UNIX_EPOCH + Duration::from_secs(784_887_151);
+        let date = |date| clock_skew(Some(date), received);
+        assert_eq!(date("Tue, 15 Nov 1994 08:12:31 GMT").status, Status::Ok);
+        assert_eq!(date("Tue, 15 Nov 1994 08:12:36 GMT").status, Status::Ok);
+        let skewed = date("Tue, 15 Nov 1994 08:11:31 GMT");
+        assert_eq!(skewed.status, Status::Warning);
+        assert_eq!(skewed.detail, "the clocks differ by 59 s");
+        assert_eq!(date("yesterday").status, Status::Skipped);
+    }
+}
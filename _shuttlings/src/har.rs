@@ -0,0 +1,502 @@
+//! Recording of the HTTP traffic of a run in a [HAR 1.2](http://www.softwareishard.com/blog/har-12-spec/) file,
+//! and [`Replay`] of the responses of a recording
+
+use std::{
+    collections::{HashMap, VecDeque},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use base64::{engine::general_purpose, Engine};
+use chrono::{DateTime, SecondsFormat, Utc};
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    StatusCode, Version,
+};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+/// How much of each body is recorded
+pub const MAX_BODY_LEN: usize = 64 * 1024;
+
+/// A HAR file that each recorded entry is added to. The file is rewritten after each entry,
+/// so that it is complete even if the run is cut short, e.g. by a timeout.
+#[derive(Debug, Clone)]
+pub struct Har {
+    inner: Arc<Mutex<Log>>,
+}
+
+#[derive(Debug)]
+struct Log {
+    path: PathBuf,
+    creator: Value,
+    entries: Vec<Value>,
+}
+
+impl Log {
+    fn write(&self) -> std::io::Result<()> {
+        let har = json!({
+            "log": {
+                "version": "1.2",
+                "creator": self.creator,
+                "entries": self.entries,
+            }
+        });
+        std::fs::write(&self.path, serde_json::to_vec_pretty(&har)?)
+    }
+}
+
+impl Har {
+    /// Create the file at `path` with no entries yet.
+    /// `creator` and `version` name the program that records the traffic.
+    pub fn create(path: impl Into<PathBuf>, creator: &str, version: &str) -> std::io::Result<Self> {
+        let log = Log {
+            path: path.into(),
+            creator: json!({"name": creator, "version": version}),
+            entries: Vec::new(),
+        };
+        log.write()?;
+        Ok(Self {
+            inner: Arc::new(Mutex::new(log)),
+        })
+    }
+
+    /// Add the entry that `pending` started, with the `response` it got or the error it ended in
+    pub fn record(&self, pending: Pending, response: Result<Response<'_>, String>) {
+        let time = pending.timer.elapsed();
+        let mut entry = json!({
+            "startedDateTime": pending.started.to_rfc3339_opts(SecondsFormat::Millis, true),
+            "time": millis(time),
+            "request": pending.request,
+            "cache": {},
+            "timings": {"send": 0, "wait": millis(time), "receive": 0},
+        });
+        entry["response"] = match response {
+            Ok(response) => response.to_json(),
+            Err(e) => {
+                entry["_error"] = e.into();
+                json!({
+                    "status": 0,
+                    "statusText": "",
+                    "httpVersion": "",
+                    "cookies": [],
+                    "headers": [],
+                    "content": {"size": 0, "mimeType": ""},
+                    "redirectURL": "",
+                    "headersSize": -1,
+                    "bodySize": -1,
+                })
+            }
+        };
+        if pending.websocket {
+            entry["_resourceType"] = "websocket".into();
+        }
+        let mut log = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        log.entries.push(entry);
+        if let Err(e) = log.write() {
+            warn!(path = %log.path.display(), error = %e, "Failed to write HAR file");
+        }
+    }
+}
+
+/// An entry whose request was sent, but whose response is not there yet
+#[derive(Debug)]
+pub struct Pending {
+    started: DateTime<Utc>,
+    timer: Instant,
+    request: Value,
+    websocket: bool,
+}
+
+impl Pending {
+    /// Start an entry for `request`, before it is sent
+    pub fn request(request: &reqwest::Request) -> Self {
+        let mut json = json!({
+            "method": request.method().as_str(),
+            "url": request.url().as_str(),
+            "httpVersion": version(request.version()),
+            "cookies": [],
+            "headers": headers(request.headers()),
+            "queryString": request
+                .url()
+                .query_pairs()
+                .map(|(name, value)| json!({"name": name, "value": value}))
+                .collect::<Vec<_>>(),
+            "headersSize": -1,
+            "bodySize": -1,
+        });
+        if let Some(body) = request.body() {
+            let mime_type = request
+                .headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default();
+            // a streamed body can't be looked at without sending it
+            json["postData"] = match body.as_bytes() {
+                Some(bytes) => {
+                    json["bodySize"] = bytes.len().into();
+                    // the recorded body may be truncated, so a replay matches on this instead
+                    json["_bodySha256"] = sha256(bytes).into();
+                    content(mime_type, bytes)
+                }
+                None => json!({"mimeType": mime_type, "text": "", "comment": "streamed body"}),
+            };
+        }
+        Self::start(json, false)
+    }
+
+    /// Start an entry for the handshake of a websocket connection to `url`
+    pub fn websocket(url: &str) -> Self {
+        let request = json!({
+            "method": "GET",
+            "url": url,
+            "httpVersion": "HTTP/1.1",
+            "cookies": [],
+            "headers": [],
+            "queryString": [],
+            "headersSize": -1,
+            "bodySize": 0,
+        });
+        Self::start(request, true)
+    }
+
+    fn start(request: Value, websocket: bool) -> Self {
+        Self {
+            started: Utc::now(),
+            timer: Instant::now(),
+            request,
+            websocket,
+        }
+    }
+}
+
+/// A response to record
+#[derive(Debug)]
+pub struct Response<'a> {
+    pub status: StatusCode,
+    pub version: Version,
+    pub headers: &'a HeaderMap,
+    pub body: &'a [u8],
+}
+
+impl Response<'_> {
+    fn to_json(&self) -> Value {
+        let mime_type = self
+            .headers
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+        json!({
+            "status": self.status.as_u16(),
+            "statusText": self.status.canonical_reason().unwrap_or_default(),
+            "httpVersion": version(self.version),
+            "cookies": [],
+            "headers": headers(self.headers),
+            "content": content(mime_type, self.body),
+            "redirectURL": self
+                .headers
+                .get(reqwest::header::LOCATION)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default(),
+            "headersSize": -1,
+            "bodySize": self.body.len(),
+        })
+    }
+}
+
+/// The responses of a HAR file, which are served instead of sending the requests.
+/// A request gets the response of a recorded one with the same method, path, query and body.
+/// Requests that were recorded more than once get their responses in the recorded order,
+/// and the last one after that.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    responses: Arc<Mutex<HashMap<Key, VecDeque<Recorded>>>>,
+}
+
+/// The method, path with query, and SHA-256 of the body of a request.
+/// Streamed bodies were not recorded, so those requests have no hash, like the ones without a body.
+type Key = (String, String, Option<String>);
+
+/// A response of the recording, or the error the request ended in
+type Recorded = Result<(StatusCode, Version, HeaderMap, Vec<u8>), String>;
+
+impl Replay {
+    /// Read the entries of the HAR file at `path`. Websocket handshakes are left out, since they can't be replayed.
+    pub fn open(path: &Path) -> std::io::Result<Self> {
+        let invalid = |e: String| std::io::Error::new(std::io::ErrorKind::InvalidData, e);
+        let har: Value = serde_json::from_slice(&std::fs::read(path)?)?;
+        let entries = har["log"]["entries"]
+            .as_array()
+            .ok_or_else(|| invalid("no log.entries".into()))?;
+        let mut responses = HashMap::<_, VecDeque<_>>::new();
+        for (i, entry) in entries.iter().enumerate() {
+            if entry["_resourceType"] == "websocket" {
+                continue;
+            }
+            let (key, recorded) =
+                recorded(entry).ok_or_else(|| invalid(format!("entry {i} is invalid")))?;
+            responses.entry(key).or_default().push_back(recorded);
+        }
+        Ok(Self {
+            responses: Arc::new(Mutex::new(responses)),
+        })
+    }
+
+    /// The recorded response to `request`, or why there is none
+    pub fn respond(&self, request: &reqwest::Request) -> Result<reqwest::Response, String> {
+        let key = key(request);
+        let mut responses = self.responses.lock().unwrap_or_else(|e| e.into_inner());
+        let recorded = match responses.get_mut(&key) {
+            Some(queue) if queue.len() > 1 => queue.pop_front(),
+            Some(queue) => queue.front().cloned(),
+            None => None,
+        };
+        let (status, version, headers, body) = match recorded {
+            Some(Ok(response)) => response,
+            Some(Err(e)) => return Err(format!("{e} (recorded)")),
+            None => {
+                let (method, path, hash) = key;
+                return Err(match hash {
+                    Some(hash) => {
+                        format!(
+                            "{method} {path} with a body of SHA-256 {hash} is not in the recording"
+                        )
+                    }
+                    None => format!("{method} {path} is not in the recording"),
+                });
+            }
+        };
+        let mut response = http::Response::builder().status(status).version(version);
+        if let Some(h) = response.headers_mut() {
+            *h = headers;
+        }
+        Ok(response
+            .body(body)
+            .expect("the parts of a recorded response to be valid")
+            .into())
+    }
+}
+
+fn key(request: &reqwest::Request) -> Key {
+    let hash = request.body().and_then(|b| b.as_bytes()).map(sha256);
+    (request.method().to_string(), path(request.url()), hash)
+}
+
+/// The path and query of `url`, which are matched without the base URL of the recording
+fn path(url: &reqwest::Url) -> String {
+    match url.query() {
+        Some(query) => format!("{}?{query}", url.path()),
+        None => url.path().to_owned(),
+    }
+}
+
+/// The key and response of a recorded entry, or `None` if it is not a valid one
+fn recorded(entry: &Value) -> Option<(Key, Recorded)> {
+    let request = &entry["request"];
+    let url = reqwest::Url::parse(request["url"].as_str()?).ok()?;
+    let hash = request["_bodySha256"].as_str().map(str::to_owned);
+    let key = (request["method"].as_str()?.to_owned(), path(&url), hash);
+    if let Some(e) = entry["_error"].as_str() {
+        return Some((key, Err(e.to_owned())));
+    }
+    let response = &entry["response"];
+    let status = StatusCode::from_u16(response["status"].as_u64()?.try_into().ok()?).ok()?;
+    let version = match response["httpVersion"].as_str()? {
+        "HTTP/0.9" => Version::HTTP_09,
+        "HTTP/1.0" => Version::HTTP_10,
+        "HTTP/2" => Version::HTTP_2,
+        "HTTP/3" => Version::HTTP_3,
+        _ => Version::HTTP_11,
+    };
+    let mut headers = HeaderMap::new();
+    for header in response["headers"].as_array()? {
+        // the recorded content is decompressed
+        if header["name"]
+            .as_str()?
+            .eq_ignore_ascii_case(reqwest::header::CONTENT_ENCODING.as_str())
+        {
+            continue;
+        }
+        headers.append(
+            HeaderName::from_bytes(header["name"].as_str()?.as_bytes()).ok()?,
+            HeaderValue::from_str(header["value"].as_str()?).ok()?,
+        );
+    }
+    let content = &response["content"];
+    let text = content["text"].as_str().unwrap_or_default();
+    let body = match content["encoding"].as_str() {
+        Some("base64") => general_purpose::STANDARD.decode(text).ok()?,
+        _ => text.as_bytes().to_vec(),
+    };
+    Some((key, Ok((status, version, headers, body))))
+}
+
+fn sha256(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+fn millis(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+fn version(v: Version) -> &'static str {
+    match v {
+        Version::HTTP_09 => "HTTP/0.9",
+        Version::HTTP_10 => "HTTP/1.0",
+        Version::HTTP_2 => "HTTP/2",
+        Version::HTTP_3 => "HTTP/3",
+        _ => "HTTP/1.1",
+    }
+}
+
+fn headers(headers: &HeaderMap) -> Vec<Value> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            json!({"name": name.as_str(), "value": String::from_utf8_lossy(value.as_bytes())})
+        })
+        .collect()
+}
+
+/// The `content` of a response or the `postData` of a request, with at most [`MAX_BODY_LEN`] of `body`
+fn content(mime_type: &str, body: &[u8]) -> Value {
+    let recorded = &body[..body.len().min(MAX_BODY_LEN)];
+    let mut content = match std::str::from_utf8(recorded) {
+        Ok(text) => json!({"mimeType": mime_type, "text": text}),
+        Err(_) => json!({
+            "mimeType": mime_type,
+            "text": general_purpose::STANDARD.encode(recorded),
+            "encoding": "base64",
+        }),
+    };
+    content["size"] = body.len().into();
+    if recorded.len() < body.len() {
+        content["comment"] = format!("truncated to {MAX_BODY_LEN} bytes").into();
+    }
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_are_written_as_they_are_recorded() {
+        let path = std::env::temp_dir().join(format!("shuttlings-{}.har", std::process::id()));
+        let har = Har::create(&path, "validator", "1.0").unwrap();
+        let read = || -> Value { serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap() };
+        assert_eq!(read()["log"]["entries"], json!([]));
+
+        let client = reqwest::Client::new();
+        let request = client
+            .post("http://localhost:8000/4/strength?x=1")
+            .header("content-type", "application/json")
+            .body("[1]")
+            .build()
+            .unwrap();
+        let headers = HeaderMap::from_iter([(
+            reqwest::header::CONTENT_TYPE,
+            "application/octet-stream".parse().unwrap(),
+        )]);
+        let body = vec![0xff; MAX_BODY_LEN + 1];
+        har.record(
+            Pending::request(&request),
+            Ok(Response {
+                status: StatusCode::OK,
+                version: Version::HTTP_11,
+                headers: &headers,
+                body: &body,
+            }),
+        );
+        har.record(
+            Pending::websocket("ws://localhost:8000/19/ws"),
+            Err("refused".into()),
+        );
+
+        let entries = read()["log"]["entries"].clone();
+        std::fs::remove_file(&path).unwrap();
+        let request = &entries[0]["request"];
+        assert_eq!(request["method"], "POST");
+        assert_eq!(request["queryString"], json!([{"name": "x", "value": "1"}]));
+        assert_eq!(request["postData"]["text"], "[1]");
+        let content = &entries[0]["response"]["content"];
+        assert_eq!(content["encoding"], "base64");
+        assert_eq!(content["size"], MAX_BODY_LEN + 1);
+        assert_eq!(content["comment"], "truncated to 65536 bytes");
+        assert_eq!(entries[1]["_resourceType"], "websocket");
+        assert_eq!(entries[1]["_error"], "refused");
+        assert_eq!(entries[1]["response"]["status"], 0);
+    }
+
+    #[tokio::test]
+    async fn recorded_responses_are_replayed_in_order() {
+        let path =
+            std::env::temp_dir().join(format!("shuttlings-replay-{}.har", std::process::id()));
+        let har = Har::create(&path, "validator", "1.0").unwrap();
+        let client = reqwest::Client::new();
+        let views = client
+            .get("http://localhost:8000/19/views")
+            .build()
+            .unwrap();
+        for (n, body) in [(1, "0"), (2, "10")] {
+            let headers = HeaderMap::from_iter([(
+                reqwest::header::CONTENT_LENGTH,
+                n.to_string().parse().unwrap(),
+            )]);
+            har.record(
+                Pending::request(&views),
+                Ok(Response {
+                    status: StatusCode::OK,
+                    version: Version::HTTP_11,
+                    headers: &headers,
+                    body: body.as_bytes(),
+                }),
+            );
+        }
+        let post = |body: &str| {
+            client
+                .post("http://localhost:8000/1/cube")
+                .body(body.to_owned())
+                .build()
+                .unwrap()
+        };
+        har.record(
+            Pending::request(&post("[1]")),
+            Err("connection refused".into()),
+        );
+
+        let replay = Replay::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        // the base URL of a replay may differ from the recorded one
+        let views = client
+            .get("http://127.0.0.1:8080/19/views")
+            .build()
+            .unwrap();
+        for expected in ["0", "10", "10"] {
+            let res = replay.respond(&views).unwrap();
+            assert_eq!(res.status(), StatusCode::OK);
+            assert_eq!(res.text().await.unwrap(), expected);
+        }
+        assert_eq!(
+            replay.respond(&post("[1]")).unwrap_err(),
+            "connection refused (recorded)"
+        );
+        assert!(replay
+            .respond(&post("[2]"))
+            .unwrap_err()
+            .starts_with("POST /1/cube with a body of SHA-256 "));
+        let other = client
+            .get("http://localhost:8000/19/reset?x=1")
+            .build()
+            .unwrap();
+        assert_eq!(
+            replay.respond(&other).unwrap_err(),
+            "GET /19/reset?x=1 is not in the recording"
+        );
+    }
+}
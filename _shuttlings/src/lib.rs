@@ -1,4 +1,46 @@
-#[derive(Debug)]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "testing")]
+pub mod client;
+#[cfg(feature = "testing")]
+pub mod config;
+#[cfg(feature = "debounce")]
+pub mod debounce;
+#[cfg(feature = "discover")]
+pub mod discover;
+#[cfg(feature = "testing")]
+pub mod doctor;
+#[cfg(feature = "testing")]
+pub mod har;
+#[cfg(feature = "output")]
+pub mod output;
+#[cfg(feature = "testing")]
+pub mod probe;
+#[cfg(feature = "report")]
+pub mod report;
+#[cfg(feature = "serve")]
+pub mod serve;
+#[cfg(feature = "report")]
+pub mod tap;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "testing")]
+pub mod tracker;
+#[cfg(feature = "testing")]
+pub mod transport;
+#[cfg(feature = "update")]
+pub mod update;
+#[cfg(feature = "report")]
+pub mod webhook;
+
+/// The scheme of a base URL that is a Unix socket, e.g. `unix:///run/app.sock`
+pub const UNIX_SCHEME: &str = "unix://";
+/// Where the requests to a Unix socket are sent, whose host only goes in the `Host` header
+pub const UNIX_BASE_URL: &str = "http://localhost";
+
+/// Serialized as its name in snake case, e.g. `"running"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SubmissionState {
     Waiting,
     Running,
@@ -11,17 +53,90 @@ impl std::fmt::Display for SubmissionState {
     }
 }
 
-#[derive(Debug)]
+/// Serialized as a JSON object with a `type` tag and the data of the update next to it:
+///
+/// ```json
+/// {"type": "state", "state": "running"}
+/// {"type": "task_completed", "last_core": true, "bonus": 100}
+/// {"type": "test_result", "task": 1, "test": 2, "passed": false, "duration_ms": 5, "detail": "expected 200 OK, got 404 Not Found"}
+/// {"type": "timing", "task": 1, "elapsed_ms": 12300}
+/// {"type": "log_line", "level": "error", "line": "Task 1: test #2 failed"}
+/// {"type": "save"}
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(into = "WireUpdate", from = "WireUpdate")]
 pub enum SubmissionUpdate {
     /// State update
     State(SubmissionState),
     /// bool is true if this task was the last core task, int is amount of bonus points
     TaskCompleted(bool, i32),
+    /// Outcome of a single test of a task
+    TestResult(TestResult),
+    /// A task has finished, `elapsed_ms` after the run started.
+    /// Sent after the updates of the task and before the messages of its failure, if it failed.
+    Timing { task: i32, elapsed_ms: u64 },
     /// Append line to log
-    LogLine(String),
-    /// Save changes to db
+    LogLine { level: LogLevel, message: String },
+    /// Save changes to db. It carries no data, the receiver persists what it has gathered so far.
     Save,
 }
+
+/// The tagged representation of [`SubmissionUpdate`]
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum WireUpdate {
+    State {
+        state: SubmissionState,
+    },
+    TaskCompleted {
+        last_core: bool,
+        bonus: i32,
+    },
+    TestResult(TestResult),
+    Timing {
+        task: i32,
+        elapsed_ms: u64,
+    },
+    LogLine {
+        /// Lines from before there were levels are [`LogLevel::Info`]
+        #[serde(default)]
+        level: LogLevel,
+        line: String,
+    },
+    Save,
+}
+impl From<SubmissionUpdate> for WireUpdate {
+    fn from(value: SubmissionUpdate) -> Self {
+        match value {
+            SubmissionUpdate::State(state) => Self::State { state },
+            SubmissionUpdate::TaskCompleted(last_core, bonus) => {
+                Self::TaskCompleted { last_core, bonus }
+            }
+            SubmissionUpdate::TestResult(result) => Self::TestResult(result),
+            SubmissionUpdate::Timing { task, elapsed_ms } => Self::Timing { task, elapsed_ms },
+            SubmissionUpdate::LogLine { level, message } => Self::LogLine {
+                level,
+                line: message,
+            },
+            SubmissionUpdate::Save => Self::Save,
+        }
+    }
+}
+impl From<WireUpdate> for SubmissionUpdate {
+    fn from(value: WireUpdate) -> Self {
+        match value {
+            WireUpdate::State { state } => Self::State(state),
+            WireUpdate::TaskCompleted { last_core, bonus } => Self::TaskCompleted(last_core, bonus),
+            WireUpdate::TestResult(result) => Self::TestResult(result),
+            WireUpdate::Timing { task, elapsed_ms } => Self::Timing { task, elapsed_ms },
+            WireUpdate::LogLine { level, line } => Self::LogLine {
+                level,
+                message: line,
+            },
+            WireUpdate::Save => Self::Save,
+        }
+    }
+}
 impl From<SubmissionState> for SubmissionUpdate {
     fn from(value: SubmissionState) -> Self {
         Self::State(value)
@@ -32,8 +147,1265 @@ impl From<(bool, i32)> for SubmissionUpdate {
         Self::TaskCompleted(b, i)
     }
 }
+/// An [`LogLevel::Info`] line
 impl From<String> for SubmissionUpdate {
     fn from(value: String) -> Self {
-        Self::LogLine(value)
+        (LogLevel::Info, value).into()
+    }
+}
+impl From<(LogLevel, String)> for SubmissionUpdate {
+    fn from((level, message): (LogLevel, String)) -> Self {
+        Self::LogLine { level, message }
+    }
+}
+impl From<TestResult> for SubmissionUpdate {
+    fn from(value: TestResult) -> Self {
+        Self::TestResult(value)
+    }
+}
+
+/// How much a [`SubmissionUpdate::LogLine`] matters, serialized as its name in snake case, e.g. `"warn"`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel {
+    /// A note, that quiet output leaves out
+    #[default]
+    Info,
+    /// Something that may be wrong, but does not fail the submission
+    Warn,
+    /// Why the submission failed, or could not be validated
+    Error,
+}
+
+/// How a validation run ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunOutcome {
+    /// All core tasks that were validated passed. Bonus tasks may have failed.
+    Completed,
+    /// A test of a core task, or of the one task that was validated, failed
+    Failed {
+        task: i32,
+        test: i32,
+    },
+    TimedOut,
+    /// The server could not be connected to, before the first test or by a test that then failed
+    ConnectionFailed,
+    /// The challenge or task can't be validated by this version
+    Unsupported,
+    /// The run was cancelled through the config of the validator, or nobody receives the updates anymore
+    Cancelled,
+    /// The validator itself failed, e.g. it panicked or its config has an invalid proxy URL
+    InternalError,
+}
+
+impl RunOutcome {
+    /// Whether the validation could not be finished, which ends the run in [`SubmissionState::Error`]
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self,
+            RunOutcome::TimedOut
+                | RunOutcome::ConnectionFailed
+                | RunOutcome::Cancelled
+                | RunOutcome::InternalError
+        )
+    }
+}
+
+/// How a submission ended, with the report of its updates
+#[derive(Debug, Clone)]
+pub struct SubmissionOutcome {
+    /// `Done`, or `Error` if the validation could not be finished, see [`RunOutcome::is_error`].
+    /// A challenge that isn't supported is never started, and is `Done` right away.
+    pub state: SubmissionState,
+    pub report: SubmissionReport,
+    /// The test that failed the submission, if one did
+    pub failed: Option<TestId>,
+    /// Why the submission ended, e.g. [`RunOutcome::TimedOut`] for one that ended in `Error`
+    pub reason: RunOutcome,
+}
+
+impl SubmissionOutcome {
+    /// The outcome of the run of `challenge` that ended in `reason`, after the updates of `report`
+    pub fn new(challenge: impl ToString, reason: RunOutcome, report: SubmissionReport) -> Self {
+        let failed = match reason {
+            RunOutcome::Failed { task, test } => Some(TestId {
+                challenge: challenge.to_string(),
+                ..TestId::from((task, test))
+            }),
+            _ => None,
+        };
+        Self {
+            state: report.state(),
+            report,
+            failed,
+            reason,
+        }
+    }
+}
+
+/// Outcome of a single test of a task
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestResult {
+    pub task: i32,
+    pub test: i32,
+    pub passed: bool,
+    /// Milliseconds spent on the requests of the test, from sending them until their body was read,
+    /// or on the whole test if it sent none
+    pub duration_ms: u64,
+    /// Why the test failed, if known
+    pub detail: Option<String>,
+}
+
+/// Identifies a test of a challenge. Displayed as `challenge.task.test`, e.g. `7.2.1`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TestId {
+    pub challenge: String,
+    pub task: u8,
+    pub test: u8,
+}
+
+impl std::fmt::Display for TestId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.challenge.is_empty() {
+            write!(f, "{}.", self.challenge)?;
+        }
+        write!(f, "{}.{}", self.task, self.test)
+    }
+}
+
+/// Task and test number, for validators that still number tests with a tuple.
+/// The challenge is left empty for the caller to fill in.
+impl From<(i32, i32)> for TestId {
+    fn from((task, test): (i32, i32)) -> Self {
+        Self {
+            challenge: String::new(),
+            task: task.clamp(0, u8::MAX.into()) as u8,
+            test: test.clamp(0, u8::MAX.into()) as u8,
+        }
+    }
+}
+
+/// Folds the updates of one submission into its progress so far
+#[derive(Debug, Clone, Default)]
+pub struct SubmissionReport {
+    state: Option<SubmissionState>,
+    tasks_completed: usize,
+    bonus_points: i32,
+    core_done: bool,
+    tests: Vec<TestResult>,
+    log: Vec<String>,
+    /// When the last task that finished did so, in milliseconds after the run started
+    elapsed_ms: u64,
+}
+
+impl SubmissionReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fold in the next update.
+    /// The state only moves forward, so a late `Running` or a second `Done` or `Error` is ignored.
+    pub fn apply(&mut self, update: &SubmissionUpdate) {
+        match update {
+            SubmissionUpdate::State(state) => {
+                if self.state.map_or(0, state_rank) < state_rank(*state) {
+                    self.state = Some(*state);
+                }
+            }
+            SubmissionUpdate::TaskCompleted(last_core, bonus) => {
+                self.tasks_completed += 1;
+                self.bonus_points += bonus;
+                self.core_done |= last_core;
+            }
+            SubmissionUpdate::TestResult(result) => self.tests.push(result.clone()),
+            SubmissionUpdate::LogLine { message, .. } => self.log.push(message.clone()),
+            SubmissionUpdate::Timing { elapsed_ms, .. } => {
+                self.elapsed_ms = self.elapsed_ms.max(*elapsed_ms)
+            }
+            SubmissionUpdate::Save => (),
+        }
+    }
+
+    /// The latest state, `Waiting` until the submission starts
+    pub fn state(&self) -> SubmissionState {
+        self.state.unwrap_or(SubmissionState::Waiting)
+    }
+
+    /// Whether the submission is `Done` or ended with an `Error`
+    pub fn finished(&self) -> bool {
+        matches!(
+            self.state,
+            Some(SubmissionState::Done | SubmissionState::Error)
+        )
+    }
+
+    pub fn tasks_completed(&self) -> usize {
+        self.tasks_completed
+    }
+
+    pub fn bonus_points(&self) -> i32 {
+        self.bonus_points
+    }
+
+    /// Whether the last core task was completed
+    pub fn core_done(&self) -> bool {
+        self.core_done
+    }
+
+    pub fn tests(&self) -> &[TestResult] {
+        &self.tests
+    }
+
+    pub fn log(&self) -> &[String] {
+        &self.log
+    }
+
+    /// Time from the start of the run until the last task that finished did so
+    pub fn elapsed(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.elapsed_ms)
+    }
+
+    /// The first test that failed
+    pub fn first_failure(&self) -> Option<&TestResult> {
+        self.tests.iter().find(|t| !t.passed)
+    }
+
+    pub fn finalize(self) -> SubmissionSummary {
+        SubmissionSummary {
+            state: self.state(),
+            tasks_completed: self.tasks_completed,
+            bonus_points: self.bonus_points,
+            core_done: self.core_done,
+            tests: self.tests,
+            log: self.log,
+        }
+    }
+}
+
+/// A submission of a challenge, as a row of [`markdown_summary`]
+#[derive(Debug, Clone, Copy)]
+pub struct SummaryRow<'a> {
+    pub challenge: &'a str,
+    /// Number of tasks that were validated
+    pub tasks: usize,
+    pub report: &'a SubmissionReport,
+}
+
+/// A Markdown table with a row per submission: its status, the tasks completed,
+/// the bonus points, how long it took and the first test that failed
+pub fn markdown_summary(rows: &[SummaryRow]) -> String {
+    let mut md = String::from(
+        "| Challenge | Status | Tasks | Bonus | Duration | First failure |\n\
+        | --- | :---: | ---: | ---: | ---: | --- |\n",
+    );
+    for row in rows {
+        let report = row.report;
+        let failure = report.first_failure();
+        let status = match report.state() {
+            SubmissionState::Error => "⚠️",
+            _ if failure.is_some() => "❌",
+            SubmissionState::Done => "✅",
+            SubmissionState::Waiting | SubmissionState::Running => "⏳",
+        };
+        let failure = failure.map_or(String::new(), |t| {
+            let mut failure = format!("Task {} test #{}", t.task, t.test);
+            if let Some(detail) = &t.detail {
+                failure.push_str(&format!(" — {detail}"));
+            }
+            // keep the table intact
+            failure.replace('|', "\\|").replace('\n', " ")
+        });
+        md.push_str(&format!(
+            "| {} | {status} | {}/{} | {} | {:.1}s | {failure} |\n",
+            row.challenge,
+            report.tasks_completed(),
+            row.tasks,
+            report.bonus_points(),
+            report.elapsed().as_secs_f64(),
+        ));
+    }
+    md
+}
+
+/// A task of a run, as a row of [`csv_report`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvRow {
+    pub challenge: String,
+    pub task: i32,
+    /// `passed`, `failed` or `skipped`
+    pub status: &'static str,
+    pub bonus: i32,
+    /// Milliseconds spent on the tests of the task
+    pub duration_ms: u64,
+    /// The test that failed and why, e.g. `test #2: expected 5, got 6`
+    pub failed_test: Option<String>,
+    /// When the task finished, in RFC 3339
+    pub timestamp: Option<String>,
+}
+
+/// A CSV file with a header, the `rows`, and a last row with the totals,
+/// e.g. `total,,3/5 passed,250,1234,,` for spreadsheets that track progress
+pub fn csv_report(rows: &[CsvRow]) -> String {
+    let mut csv = String::from("challenge,task,status,bonus,duration_ms,failed_test,timestamp\n");
+    for row in rows {
+        let fields = [
+            row.challenge.clone(),
+            row.task.to_string(),
+            row.status.to_owned(),
+            row.bonus.to_string(),
+            row.duration_ms.to_string(),
+            row.failed_test.clone().unwrap_or_default(),
+            row.timestamp.clone().unwrap_or_default(),
+        ];
+        csv.push_str(&fields.map(|f| csv_field(&f)).join(","));
+        csv.push('\n');
+    }
+    let passed = rows.iter().filter(|r| r.status == "passed").count();
+    csv.push_str(&format!(
+        "total,,{passed}/{} passed,{},{},,\n",
+        rows.len(),
+        rows.iter().map(|r| r.bonus).sum::<i32>(),
+        rows.iter().map(|r| r.duration_ms).sum::<u64>(),
+    ));
+    csv
+}
+
+/// A field of [`csv_report`], quoted as RFC 4180 requires if it has a comma, a quote or a line break
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// The submissions of a challenge to two servers, as a challenge of [`compare_runs`]
+#[derive(Debug, Clone, Copy)]
+pub struct ComparedRun<'a> {
+    pub challenge: &'a str,
+    pub a: &'a SubmissionReport,
+    pub b: &'a SubmissionReport,
+}
+
+/// How the submissions of the same challenges to servers `a` and `b` differ, e.g. before and after
+/// a migration: the tests that passed on one but not the other, how much longer each challenge took
+/// on `b`, and the bonus points where they differ. The servers are named by their labels.
+pub fn compare_runs(a: &str, b: &str, runs: &[ComparedRun]) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut differences = 0;
+    for run in runs {
+        lines.push(format!("Challenge {}:", run.challenge));
+        // e.g. a timeout or a connection failure, which the last log line says
+        let errors = [(run.a, a), (run.b, b)].map(|(report, label)| {
+            (report.state() == SubmissionState::Error).then(|| {
+                let reason = report.log().last().map_or("", String::as_str);
+                format!("  Could not be validated on {label}: {reason}")
+            })
+        });
+        if errors[0].is_some() != errors[1].is_some() {
+            differences += 1;
+        }
+        lines.extend(errors.into_iter().flatten());
+        let mut ids: Vec<(i32, i32)> = run.a.tests().iter().map(|t| (t.task, t.test)).collect();
+        ids.extend(run.b.tests().iter().map(|t| (t.task, t.test)));
+        ids.sort();
+        ids.dedup();
+        for (task, test) in ids {
+            let is_test = |t: &&TestResult| (t.task, t.test) == (task, test);
+            let in_a = run.a.tests().iter().find(is_test);
+            let in_b = run.b.tests().iter().find(is_test);
+            if in_a.map(|t| t.passed) == in_b.map(|t| t.passed) {
+                continue;
+            }
+            differences += 1;
+            let outcome = |t: Option<&TestResult>, label: &str| match t {
+                Some(t) if t.passed => format!("passed on {label}"),
+                Some(TestResult {
+                    detail: Some(detail),
+                    ..
+                }) => format!("failed on {label} ({})", detail.replace('\n', " ")),
+                Some(_) => format!("failed on {label}"),
+                None => format!("did not run on {label}"),
+            };
+            lines.push(format!(
+                "  Task {task} test #{test} {}, but {}",
+                outcome(in_a, a),
+                outcome(in_b, b)
+            ));
+        }
+        if run.a.bonus_points() != run.b.bonus_points() {
+            differences += 1;
+            lines.push(format!(
+                "  Bonus points: {} on {a}, {} on {b}",
+                run.a.bonus_points(),
+                run.b.bonus_points()
+            ));
+        }
+        // in tenths of a second, so that the difference is that of the times shown
+        let tenths = |r: &SubmissionReport| (r.elapsed().as_millis() as i64 + 50) / 100;
+        let (took_a, took_b) = (tenths(run.a), tenths(run.b));
+        lines.push(format!(
+            "  Took {:.1}s on {a}, {:.1}s on {b} ({:+.1}s)",
+            took_a as f64 / 10.0,
+            took_b as f64 / 10.0,
+            (took_b - took_a) as f64 / 10.0
+        ));
+    }
+    let bonus_a: i32 = runs.iter().map(|run| run.a.bonus_points()).sum();
+    let bonus_b: i32 = runs.iter().map(|run| run.b.bonus_points()).sum();
+    if bonus_a != bonus_b {
+        lines.push(format!(
+            "Bonus points in total: {bonus_a} on {a}, {bonus_b} on {b}"
+        ));
+    }
+    lines.push(match differences {
+        0 => "The outcomes are the same.".to_owned(),
+        1 => "1 difference in the outcomes.".to_owned(),
+        n => format!("{n} differences in the outcomes."),
+    });
+    lines
+}
+
+/// How a challenge counts towards the score, see [`ScoreRow`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScoreStatus {
+    /// The core tasks passed
+    Completed,
+    /// A core task failed, or the validation could not be finished
+    Failed,
+    /// Left out with `--skip`, or not supported by the validator
+    Skipped,
+}
+
+impl ScoreStatus {
+    pub fn mark(self) -> &'static str {
+        match self {
+            ScoreStatus::Completed => "✅",
+            ScoreStatus::Failed => "🟥",
+            ScoreStatus::Skipped => "⏭ ",
+        }
+    }
+}
+
+/// The header of the score table, whose rows are [`ScoreRow::line`] and [`ScoreTotal::line`]
+pub const SCORE_HEADER: &str = "   Challenge  Result        Core  Bonus";
+
+/// A challenge as a row of the score table, with the points that the platform counts for it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScoreRow {
+    pub challenge: String,
+    pub status: ScoreStatus,
+    /// What was validated, e.g. `passed 2/3`
+    pub result: String,
+    /// The core points of the challenge if it was completed, see [`ScoreTotal::completed`]
+    pub core: i32,
+    /// The bonus points that count towards the score
+    pub bonus: i32,
+}
+
+impl ScoreRow {
+    pub fn line(&self) -> String {
+        format!(
+            "{} {:<10} {:<12} {:>5} {:>6}",
+            self.status.mark(),
+            self.challenge,
+            self.result,
+            self.core,
+            self.bonus
+        )
+    }
+}
+
+/// The score of a run as the platform counts it, where a challenge that was validated
+/// more than once counts with its best points
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScoreTotal {
+    /// The core points, one for each completed challenge that counts towards the score,
+    /// which the leaderboard ranks by before the bonus points
+    pub completed: i32,
+    pub bonus: i32,
+}
+
+impl ScoreTotal {
+    pub fn new(rows: &[ScoreRow]) -> Self {
+        let mut best: Vec<(&str, i32, i32)> = Vec::new();
+        for row in rows {
+            match best.iter_mut().find(|(c, ..)| *c == row.challenge) {
+                Some((_, core, bonus)) => {
+                    *core = (*core).max(row.core);
+                    *bonus = (*bonus).max(row.bonus);
+                }
+                None => best.push((&row.challenge, row.core, row.bonus)),
+            }
+        }
+        Self {
+            completed: best.iter().map(|(_, core, _)| core).sum(),
+            bonus: best.iter().map(|(.., bonus)| bonus).sum(),
+        }
+    }
+
+    /// The last row of the score table
+    pub fn line(&self) -> String {
+        format!(
+            "   {:<10} {:<12} {:>5} {:>6}",
+            "Total", "", self.completed, self.bonus
+        )
+    }
+}
+
+impl std::fmt::Display for ScoreTotal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Completed {} challenges and gathered a total of {} bonus points.",
+            self.completed, self.bonus
+        )
+    }
+}
+
+/// Order of the states, which a submission goes through at most once each
+fn state_rank(state: SubmissionState) -> u8 {
+    match state {
+        SubmissionState::Waiting => 1,
+        SubmissionState::Running => 2,
+        SubmissionState::Done | SubmissionState::Error => 3,
+    }
+}
+
+/// The final result of a submission, see [`SubmissionReport::finalize`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubmissionSummary {
+    pub state: SubmissionState,
+    pub tasks_completed: usize,
+    pub bonus_points: i32,
+    pub core_done: bool,
+    pub tests: Vec<TestResult>,
+    pub log: Vec<String>,
+}
+
+/// The results of a run of several submissions, e.g. to notify a webhook when it finished
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunSummary {
+    /// A line that sums up the run, which chat webhooks show as the message
+    pub text: String,
+    pub challenges: Vec<ChallengeSummary>,
+    pub bonus_points: i32,
+    pub duration_ms: u64,
+    pub failed_tests: Vec<TestId>,
+}
+
+/// The final result of the submission of a challenge in a [`RunSummary`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChallengeSummary {
+    pub challenge: String,
+    #[serde(flatten)]
+    pub summary: SubmissionSummary,
+}
+
+impl ChallengeSummary {
+    /// Whether the submission is done and none of its tests failed
+    pub fn passed(&self) -> bool {
+        self.summary.state == SubmissionState::Done && self.summary.tests.iter().all(|t| t.passed)
+    }
+}
+
+impl RunSummary {
+    pub fn new(challenges: Vec<ChallengeSummary>, duration: std::time::Duration) -> Self {
+        let bonus_points = challenges.iter().map(|c| c.summary.bonus_points).sum();
+        let failed_tests = challenges
+            .iter()
+            .flat_map(|c| {
+                c.summary
+                    .tests
+                    .iter()
+                    .filter(|t| !t.passed)
+                    .map(|t| TestId {
+                        challenge: c.challenge.clone(),
+                        ..(t.task, t.test).into()
+                    })
+            })
+            .collect();
+        let passed = challenges.iter().filter(|c| c.passed()).count();
+        let text = format!(
+            "Validated {} challenges: {passed} passed, {} failed, {bonus_points} bonus points in {:.1}s",
+            challenges.len(),
+            challenges.len() - passed,
+            duration.as_secs_f64(),
+        );
+        Self {
+            text,
+            challenges,
+            bonus_points,
+            duration_ms: duration.as_millis() as u64,
+            failed_tests,
+        }
+    }
+}
+
+/// The receiving end of the update channel was dropped, e.g. because the submission was cancelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UpdatesClosed;
+impl std::fmt::Display for UpdatesClosed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("the submission update channel is closed")
+    }
+}
+impl std::error::Error for UpdatesClosed {}
+
+/// Send an update about a submission.
+/// If nobody receives the updates anymore, this is logged at debug level and the validation should stop.
+pub async fn try_update(
+    tx: &tokio::sync::mpsc::Sender<SubmissionUpdate>,
+    update: impl Into<SubmissionUpdate>,
+) -> Result<(), UpdatesClosed> {
+    tx.send(update.into()).await.map_err(|e| {
+        tracing::debug!(update = ?e.0, "Dropped an update because the channel is closed");
+        UpdatesClosed
+    })
+}
+
+/// Validates the challenges of one year, so that a tool can run those of any year the same way
+pub trait Validator: Send + Sync {
+    /// The options of a run, e.g. its timeout
+    type Config: Default + Send + Sync;
+
+    /// The year of the challenges, e.g. 2024
+    fn year(&self) -> u16;
+
+    /// The numbers of the challenges that can be validated, e.g. `"-1"`
+    fn supported(&self) -> &[&str];
+
+    /// Validate challenge `number` against the server at `url`, sending the updates to `sink`
+    fn run(
+        &self,
+        url: String,
+        number: &str,
+        sink: impl SubmissionSink,
+        config: &Self::Config,
+    ) -> impl std::future::Future<Output = SubmissionOutcome> + Send;
+}
+
+/// The challenges of a validator, for what reports on them the same way every year
+pub trait Catalog {
+    /// How a challenge is numbered, e.g. `i32`
+    type Challenge: Clone + Default + PartialEq + std::fmt::Debug + std::fmt::Display + Serialize;
+
+    /// The event the challenges are from, e.g. `"CCH23"`
+    const EVENT: &'static str;
+    /// The name of the validator, e.g. `"cch23-validator"`
+    const NAME: &'static str;
+    /// The version of the validator
+    const VERSION: &'static str;
+
+    /// How many tasks `challenge` has, or `None` if it is not supported
+    fn task_count(challenge: &Self::Challenge) -> Option<i32>;
+
+    /// Task number and test number of each test of `challenge`, in order, or none if it is not supported
+    fn tests(challenge: &Self::Challenge) -> Vec<(i32, i32)>;
+
+    /// Whether completing `challenge` counts towards the score of the event
+    fn scored(challenge: &Self::Challenge) -> bool;
+}
+
+/// What a [`SubmissionSink`] returns once nobody receives its updates, the same as a channel
+pub type SinkClosed = UpdatesClosed;
+
+/// Where the updates of a submission go, e.g. a channel or a stream to a browser
+pub trait SubmissionSink: Send + Sync {
+    fn send(
+        &self,
+        update: SubmissionUpdate,
+    ) -> impl std::future::Future<Output = Result<(), SinkClosed>> + Send;
+}
+
+impl SubmissionSink for tokio::sync::mpsc::Sender<SubmissionUpdate> {
+    async fn send(&self, update: SubmissionUpdate) -> Result<(), SinkClosed> {
+        try_update(self, update).await
+    }
+}
+
+impl<S: SubmissionSink + ?Sized> SubmissionSink for &S {
+    async fn send(&self, update: SubmissionUpdate) -> Result<(), SinkClosed> {
+        S::send(self, update).await
+    }
+}
+
+/// Keeps the updates it is sent, e.g. to check them in a test
+#[derive(Debug, Default)]
+pub struct VecSink(std::sync::Mutex<Vec<SubmissionUpdate>>);
+
+impl VecSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn updates(&self) -> Vec<SubmissionUpdate> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+impl SubmissionSink for VecSink {
+    async fn send(&self, update: SubmissionUpdate) -> Result<(), SinkClosed> {
+        self.0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(update);
+        Ok(())
+    }
+}
+
+/// How many updates a channel to a [`SubmissionSink`] holds while the sink is busy
+const SINK_BUFFER: usize = 32;
+
+/// Run the validation `run` with a sender whose updates go to `sink`.
+/// Once the sink is closed, so is the sender, which stops the validation like a closed channel does.
+pub async fn with_sink<F: std::future::Future>(
+    sink: impl SubmissionSink,
+    run: impl FnOnce(tokio::sync::mpsc::Sender<SubmissionUpdate>) -> F,
+) -> F::Output {
+    let (tx, mut rx) = tokio::sync::mpsc::channel(SINK_BUFFER);
+    let forward = async move {
+        while let Some(update) = rx.recv().await {
+            if sink.send(update).await.is_err() {
+                break;
+            }
+        }
+    };
+    let (output, ()) = tokio::join!(run(tx), forward);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    /// Challenges -1 and 5 with two tasks of one test each, of which only 5 is scored
+    #[cfg(feature = "report")]
+    #[derive(Debug)]
+    pub(crate) struct Days;
+
+    #[cfg(feature = "report")]
+    impl Catalog for Days {
+        type Challenge = i32;
+
+        const EVENT: &'static str = "CCH23";
+        const NAME: &'static str = "cch23-validator";
+        const VERSION: &'static str = "0.1.0";
+
+        fn task_count(challenge: &i32) -> Option<i32> {
+            [-1, 5].contains(challenge).then_some(2)
+        }
+
+        fn tests(challenge: &i32) -> Vec<(i32, i32)> {
+            let tasks = Self::task_count(challenge).unwrap_or_default();
+            (1..=tasks).map(|task| (task, 1)).collect()
+        }
+
+        fn scored(challenge: &i32) -> bool {
+            *challenge == 5
+        }
+    }
+
+    fn round_trip(update: SubmissionUpdate, expected: serde_json::Value) {
+        let json = serde_json::to_value(&update).unwrap();
+        assert_eq!(json, expected);
+        assert_eq!(
+            serde_json::from_value::<SubmissionUpdate>(json).unwrap(),
+            update
+        );
+    }
+
+    #[test]
+    fn state_round_trip() {
+        for (state, name) in [
+            (SubmissionState::Waiting, "waiting"),
+            (SubmissionState::Running, "running"),
+            (SubmissionState::Done, "done"),
+            (SubmissionState::Error, "error"),
+        ] {
+            round_trip(state.into(), json!({"type": "state", "state": name}));
+        }
+    }
+
+    #[test]
+    fn task_completed_round_trip() {
+        round_trip(
+            (true, 100).into(),
+            json!({"type": "task_completed", "last_core": true, "bonus": 100}),
+        );
+    }
+
+    #[test]
+    fn test_result_round_trip() {
+        round_trip(
+            TestResult {
+                task: 1,
+                test: 2,
+                passed: false,
+                duration_ms: 5,
+                detail: Some("expected 200 OK, got 404 Not Found".to_owned()),
+            }
+            .into(),
+            json!({
+                "type": "test_result",
+                "task": 1,
+                "test": 2,
+                "passed": false,
+                "duration_ms": 5,
+                "detail": "expected 200 OK, got 404 Not Found",
+            }),
+        );
+    }
+
+    #[test]
+    fn timing_round_trip() {
+        round_trip(
+            SubmissionUpdate::Timing {
+                task: 1,
+                elapsed_ms: 12300,
+            },
+            json!({"type": "timing", "task": 1, "elapsed_ms": 12300}),
+        );
+    }
+
+    #[test]
+    fn log_line_round_trip() {
+        round_trip(
+            "Task 1: completed".to_owned().into(),
+            json!({"type": "log_line", "level": "info", "line": "Task 1: completed"}),
+        );
+        round_trip(
+            (LogLevel::Warn, "Warning: slow".to_owned()).into(),
+            json!({"type": "log_line", "level": "warn", "line": "Warning: slow"}),
+        );
+        // from before there were levels
+        let unleveled = json!({"type": "log_line", "line": "Task 1: completed"});
+        assert_eq!(
+            serde_json::from_value::<SubmissionUpdate>(unleveled).unwrap(),
+            "Task 1: completed".to_owned().into()
+        );
+    }
+
+    #[test]
+    fn save_round_trip() {
+        round_trip(SubmissionUpdate::Save, json!({"type": "save"}));
+    }
+
+    #[test]
+    fn state_display_is_unchanged() {
+        assert_eq!(SubmissionState::Running.to_string(), "Running");
+    }
+
+    fn report(updates: Vec<SubmissionUpdate>) -> SubmissionReport {
+        let mut report = SubmissionReport::new();
+        for update in &updates {
+            report.apply(update);
+        }
+        report
+    }
+
+    fn passed(task: i32, test: i32) -> SubmissionUpdate {
+        TestResult {
+            task,
+            test,
+            passed: true,
+            duration_ms: 1,
+            detail: None,
+        }
+        .into()
+    }
+
+    #[test]
+    fn report_folds_a_submission() {
+        let report = report(vec![
+            SubmissionState::Running.into(),
+            passed(1, 1),
+            (true, 0).into(),
+            SubmissionUpdate::Save,
+            passed(2, 1),
+            (false, 100).into(),
+            "Task 3: test #1 failed".to_owned().into(),
+            SubmissionState::Done.into(),
+        ]);
+        assert_eq!(report.state(), SubmissionState::Done);
+        assert!(report.finished());
+        assert_eq!(report.tasks_completed(), 2);
+        assert_eq!(report.bonus_points(), 100);
+        assert!(report.core_done());
+        assert_eq!(report.tests().len(), 2);
+        assert_eq!(report.log(), ["Task 3: test #1 failed"]);
+    }
+
+    #[test]
+    fn report_starts_waiting() {
+        let report = SubmissionReport::new();
+        assert_eq!(report.state(), SubmissionState::Waiting);
+        assert!(!report.finished());
+        assert!(!report.core_done());
+    }
+
+    #[test]
+    fn report_ignores_duplicate_done() {
+        let report = report(vec![
+            SubmissionState::Running.into(),
+            (true, 50).into(),
+            SubmissionState::Done.into(),
+            SubmissionState::Done.into(),
+        ]);
+        assert_eq!(report.state(), SubmissionState::Done);
+        assert_eq!(report.tasks_completed(), 1);
+        assert_eq!(report.bonus_points(), 50);
+    }
+
+    #[test]
+    fn report_state_only_moves_forward() {
+        let report = report(vec![
+            (true, 0).into(),
+            SubmissionState::Error.into(),
+            SubmissionState::Running.into(),
+            SubmissionState::Done.into(),
+            SubmissionState::Waiting.into(),
+            (false, 75).into(),
+        ]);
+        assert_eq!(report.state(), SubmissionState::Error);
+        // updates that arrive out of order still count
+        assert_eq!(report.tasks_completed(), 2);
+        assert_eq!(report.bonus_points(), 75);
+        assert!(report.core_done());
+    }
+
+    #[test]
+    fn report_finalizes_into_a_summary() {
+        let summary = report(vec![
+            SubmissionState::Running.into(),
+            TestResult {
+                task: 1,
+                test: 1,
+                passed: false,
+                duration_ms: 3,
+                detail: None,
+            }
+            .into(),
+            "Task 1: test #1 failed".to_owned().into(),
+            SubmissionState::Done.into(),
+        ])
+        .finalize();
+        assert_eq!(
+            summary,
+            SubmissionSummary {
+                state: SubmissionState::Done,
+                tasks_completed: 0,
+                bonus_points: 0,
+                core_done: false,
+                tests: vec![TestResult {
+                    task: 1,
+                    test: 1,
+                    passed: false,
+                    duration_ms: 3,
+                    detail: None,
+                }],
+                log: vec!["Task 1: test #1 failed".to_owned()],
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn sinks_get_the_updates_in_order() {
+        let sink = VecSink::new();
+        let output = with_sink(&sink, |tx| async move {
+            try_update(&tx, SubmissionState::Running).await.unwrap();
+            try_update(&tx, "Task 1: completed".to_owned())
+                .await
+                .unwrap();
+            try_update(&tx, SubmissionUpdate::Save).await.unwrap();
+            "done"
+        })
+        .await;
+        assert_eq!(output, "done");
+        assert_eq!(
+            sink.updates(),
+            [
+                SubmissionState::Running.into(),
+                "Task 1: completed".to_owned().into(),
+                SubmissionUpdate::Save,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn closed_sinks_close_the_sender() {
+        let (closed, rx) = tokio::sync::mpsc::channel(1);
+        drop(rx);
+        with_sink(closed, |tx| async move {
+            let _ = try_update(&tx, SubmissionUpdate::Save).await;
+            tx.closed().await;
+        })
+        .await;
+    }
+
+    #[test]
+    fn test_id_display() {
+        let id = TestId {
+            challenge: "7".to_owned(),
+            task: 2,
+            test: 1,
+        };
+        assert_eq!(id.to_string(), "7.2.1");
+        assert_eq!(TestId::from((2, 1)).to_string(), "2.1");
+    }
+
+    #[test]
+    fn test_id_from_tuple() {
+        let id = TestId::from((3, 300));
+        assert_eq!((id.challenge.as_str(), id.task, id.test), ("", 3, u8::MAX));
+    }
+
+    #[test]
+    fn markdown_summary_has_a_row_per_submission() {
+        let passed = report(vec![
+            SubmissionState::Running.into(),
+            passed(1, 1),
+            SubmissionUpdate::TaskCompleted(true, 0),
+            SubmissionUpdate::Timing {
+                task: 1,
+                elapsed_ms: 1250,
+            },
+            passed(2, 1),
+            SubmissionUpdate::TaskCompleted(false, 150),
+            SubmissionUpdate::Timing {
+                task: 2,
+                elapsed_ms: 2000,
+            },
+            SubmissionState::Done.into(),
+        ]);
+        let failed = report(vec![
+            SubmissionState::Running.into(),
+            TestResult {
+                task: 1,
+                test: 2,
+                passed: false,
+                duration_ms: 1,
+                detail: Some("expected \"a|b\", got \"\"".to_owned()),
+            }
+            .into(),
+            SubmissionState::Done.into(),
+        ]);
+        let timed_out = report(vec![
+            SubmissionState::Running.into(),
+            SubmissionState::Error.into(),
+        ]);
+        let rows = [("-1", 2, &passed), ("5", 3, &failed), ("9", 3, &timed_out)].map(
+            |(challenge, tasks, report)| SummaryRow {
+                challenge,
+                tasks,
+                report,
+            },
+        );
+        assert_eq!(
+            markdown_summary(&rows),
+            "\
+| Challenge | Status | Tasks | Bonus | Duration | First failure |
+| --- | :---: | ---: | ---: | ---: | --- |
+| -1 | ✅ | 2/2 | 150 | 2.0s |  |
+| 5 | ❌ | 0/3 | 0 | 0.0s | Task 1 test #2 — expected \"a\\|b\", got \"\" |
+| 9 | ⚠️ | 0/3 | 0 | 0.0s |  |
+"
+        );
+    }
+
+    #[test]
+    fn csv_reports_quote_fields_and_end_with_the_totals() {
+        let row = |task, status, bonus, failed_test: Option<&str>| CsvRow {
+            challenge: "-1".to_owned(),
+            task,
+            status,
+            bonus,
+            duration_ms: 120,
+            failed_test: failed_test.map(str::to_owned),
+            timestamp: Some("2024-12-01T10:00:00.000Z".to_owned()),
+        };
+        let rows = [
+            row(1, "passed", 0, None),
+            row(2, "failed", 0, Some("test #1: expected \"a,b\",\ngot \"\"")),
+            CsvRow {
+                timestamp: None,
+                ..row(3, "skipped", 0, None)
+            },
+        ];
+        assert_eq!(
+            csv_report(&rows),
+            "\
+challenge,task,status,bonus,duration_ms,failed_test,timestamp
+-1,1,passed,0,120,,2024-12-01T10:00:00.000Z
+-1,2,failed,0,120,\"test #1: expected \"\"a,b\"\",
+got \"\"\"\"\",2024-12-01T10:00:00.000Z
+-1,3,skipped,0,120,,
+total,,1/3 passed,0,360,,
+"
+        );
+    }
+
+    #[test]
+    fn compared_runs_show_where_the_outcomes_differ() {
+        let failed = |task, test, detail: &str| {
+            TestResult {
+                task,
+                test,
+                passed: false,
+                duration_ms: 1,
+                detail: Some(detail.to_owned()),
+            }
+            .into()
+        };
+        let timing = |elapsed_ms| SubmissionUpdate::Timing {
+            task: 1,
+            elapsed_ms,
+        };
+        let old = report(vec![
+            passed(1, 1),
+            passed(1, 2),
+            SubmissionUpdate::TaskCompleted(true, 0),
+            passed(2, 1),
+            SubmissionUpdate::TaskCompleted(false, 150),
+            timing(1200),
+        ]);
+        let new = report(vec![
+            passed(1, 1),
+            failed(1, 2, "expected 200, got 404"),
+            timing(1500),
+        ]);
+        let same = report(vec![passed(1, 1), timing(100)]);
+        let timed_out = report(vec![
+            SubmissionState::Running.into(),
+            (LogLevel::Error, "Timed out after 60s".to_owned()).into(),
+            SubmissionState::Error.into(),
+        ]);
+        let runs = [
+            ComparedRun {
+                challenge: "5",
+                a: &old,
+                b: &new,
+            },
+            ComparedRun {
+                challenge: "9",
+                a: &same,
+                b: &same,
+            },
+            ComparedRun {
+                challenge: "12",
+                a: &same,
+                b: &timed_out,
+            },
+        ];
+        assert_eq!(
+            compare_runs("axum", "actix", &runs),
+            [
+                "Challenge 5:",
+                "  Task 1 test #2 passed on axum, but failed on actix (expected 200, got 404)",
+                "  Task 2 test #1 passed on axum, but did not run on actix",
+                "  Bonus points: 150 on axum, 0 on actix",
+                "  Took 1.2s on axum, 1.5s on actix (+0.3s)",
+                "Challenge 9:",
+                "  Took 0.1s on axum, 0.1s on actix (+0.0s)",
+                "Challenge 12:",
+                "  Could not be validated on actix: Timed out after 60s",
+                "  Task 1 test #1 passed on axum, but did not run on actix",
+                "  Took 0.1s on axum, 0.0s on actix (-0.1s)",
+                "Bonus points in total: 150 on axum, 0 on actix",
+                "5 differences in the outcomes.",
+            ]
+        );
+        assert_eq!(
+            compare_runs("axum", "actix", &runs[1..2]).last().unwrap(),
+            "The outcomes are the same."
+        );
+    }
+
+    #[test]
+    fn score_counts_the_best_run_of_each_challenge() {
+        let row = |challenge: &str, status, core, bonus| ScoreRow {
+            challenge: challenge.to_owned(),
+            status,
+            result: String::new(),
+            core,
+            bonus,
+        };
+        let rows = [
+            row("5", ScoreStatus::Completed, 1, 0),
+            row("5", ScoreStatus::Completed, 1, 150),
+            row("9", ScoreStatus::Failed, 0, 0),
+            row("12", ScoreStatus::Skipped, 0, 0),
+        ];
+        let total = ScoreTotal::new(&rows);
+        assert_eq!(
+            total,
+            ScoreTotal {
+                completed: 1,
+                bonus: 150
+            }
+        );
+        assert_eq!(
+            total.to_string(),
+            "Completed 1 challenges and gathered a total of 150 bonus points."
+        );
+        assert_eq!(
+            [SCORE_HEADER.to_owned(), rows[1].line(), total.line()],
+            [
+                "   Challenge  Result        Core  Bonus",
+                "✅ 5                           1    150",
+                "   Total                       1    150",
+            ]
+        );
+    }
+
+    #[test]
+    fn run_summary_sums_up_the_challenges() {
+        let completed = report(vec![
+            SubmissionState::Running.into(),
+            passed(1, 1),
+            SubmissionUpdate::TaskCompleted(true, 100),
+            SubmissionState::Done.into(),
+        ]);
+        let failing = report(vec![
+            SubmissionState::Running.into(),
+            passed(1, 1),
+            TestResult {
+                task: 1,
+                test: 2,
+                passed: false,
+                duration_ms: 1,
+                detail: None,
+            }
+            .into(),
+            SubmissionState::Done.into(),
+        ]);
+        let summary = RunSummary::new(
+            vec![
+                ChallengeSummary {
+                    challenge: "5".to_owned(),
+                    summary: completed.finalize(),
+                },
+                ChallengeSummary {
+                    challenge: "9".to_owned(),
+                    summary: failing.finalize(),
+                },
+            ],
+            std::time::Duration::from_millis(1500),
+        );
+        assert_eq!(
+            summary.text,
+            "Validated 2 challenges: 1 passed, 1 failed, 100 bonus points in 1.5s"
+        );
+        assert_eq!(summary.bonus_points, 100);
+        assert_eq!(summary.duration_ms, 1500);
+        assert_eq!(summary.failed_tests.len(), 1);
+        assert_eq!(summary.failed_tests[0].to_string(), "9.1.2");
+
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["challenges"][0]["challenge"], "5");
+        assert_eq!(json["challenges"][0]["state"], "done");
+        assert_eq!(json["failed_tests"][0]["challenge"], "9");
     }
 }
@@ -0,0 +1,133 @@
+//! Printing what a run does to the terminal, with a progress line that stays below the output,
+//! and copying it to a file
+
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{self, IsTerminal, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use chrono::Local;
+
+/// How often the progress is printed when the output is not a terminal
+pub const PROGRESS_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Where progress is printed: the terminal, and optionally a file with a timestamp on every line
+pub struct Output {
+    stderr: bool,
+    file: Option<File>,
+    terminal: bool,
+    progress: Option<String>,
+    /// When the progress was last printed as a line of its own
+    progress_printed: Option<Instant>,
+}
+
+impl Output {
+    /// Prints to stderr instead of stdout if `stderr` is set
+    pub fn new(stderr: bool) -> Self {
+        let terminal = if stderr {
+            io::stderr().is_terminal()
+        } else {
+            io::stdout().is_terminal()
+        };
+        Self {
+            stderr,
+            file: None,
+            terminal,
+            progress: None,
+            progress_printed: None,
+        }
+    }
+
+    /// Also write to the file at `path`, creating its parent directories if needed
+    pub fn with_file(mut self, path: &Path) -> io::Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        self.file = Some(File::create(path)?);
+        Ok(self)
+    }
+
+    pub fn line(&mut self, line: impl Display) {
+        let line = line.to_string();
+        self.erase_progress();
+        if self.stderr {
+            anstream::eprintln!("{line}");
+        } else {
+            anstream::println!("{line}");
+        }
+        self.draw_progress();
+        self.log(&line);
+    }
+
+    /// Show the progress of the run.
+    /// On a terminal it stays below the other output until it is replaced or cleared,
+    /// otherwise it is printed as a line every [`PROGRESS_INTERVAL`].
+    pub fn progress(&mut self, progress: String) {
+        self.erase_progress();
+        self.progress = Some(progress);
+        self.refresh_progress();
+    }
+
+    /// Show the progress again if it is due
+    pub fn refresh_progress(&mut self) {
+        if self.terminal {
+            self.draw_progress();
+            return;
+        }
+        let Some(progress) = &self.progress else {
+            return;
+        };
+        if self
+            .progress_printed
+            .is_none_or(|t| t.elapsed() >= PROGRESS_INTERVAL)
+        {
+            self.progress_printed = Some(Instant::now());
+            self.line(format!("Progress: {progress}"));
+        }
+    }
+
+    pub fn clear_progress(&mut self) {
+        self.erase_progress();
+        self.progress = None;
+    }
+
+    fn draw_progress(&self) {
+        if let Some(progress) = self.progress.as_ref().filter(|_| self.terminal) {
+            self.write_terminal(progress);
+        }
+    }
+
+    fn erase_progress(&self) {
+        if self.progress.is_some() && self.terminal {
+            // carriage return and erase the line
+            self.write_terminal("\r\x1b[2K");
+        }
+    }
+
+    /// Write to the terminal without a newline, bypassing the color handling
+    fn write_terminal(&self, s: &str) {
+        let _ = if self.stderr {
+            let mut e = io::stderr();
+            e.write_all(s.as_bytes()).and_then(|_| e.flush())
+        } else {
+            let mut o = io::stdout();
+            o.write_all(s.as_bytes()).and_then(|_| o.flush())
+        };
+    }
+
+    /// Write a line only to the file
+    pub fn log(&mut self, line: &str) {
+        let Some(file) = &mut self.file else {
+            return;
+        };
+        let time = Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
+        let line = anstream::adapter::strip_str(line);
+        if let Err(e) = writeln!(file, "[{time}] {line}") {
+            anstream::eprintln!("Failed to write to the output file, no longer writing to it: {e}");
+            self.file = None;
+        }
+    }
+}
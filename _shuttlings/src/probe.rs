@@ -0,0 +1,241 @@
+//! Malformed requests that a robust server rejects with a 4xx, sent to the endpoints a run used
+//! after its tests passed. A server error or a dropped connection is only a warning.
+
+use std::{sync::Mutex, time::Duration};
+
+use reqwest::{Method, StatusCode};
+
+use crate::transport::TransportError;
+
+/// Most endpoints that are probed in a run, so that a challenge with many paths doesn't get thousands of probes
+pub const MAX_ENDPOINTS: usize = 16;
+/// How long the server has to answer a probe
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+const WRONG_CONTENT_TYPES: &[&str] = &[
+    "text/plain",
+    "application/xml",
+    "application/x-www-form-urlencoded",
+    "image/png",
+];
+
+/// How a probe is malformed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Malformation {
+    /// A JSON object that ends halfway
+    TruncatedJson,
+    /// A JSON object that says it is something else
+    WrongContentType,
+    /// The last segment of the path replaced with thousands of characters
+    LongPathParam,
+    /// A text body that is not valid UTF-8
+    InvalidUtf8,
+}
+
+impl std::fmt::Display for Malformation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::TruncatedJson => "truncated JSON",
+            Self::WrongContentType => "a wrong content type",
+            Self::LongPathParam => "a long path parameter",
+            Self::InvalidUtf8 => "an invalid UTF-8 body",
+        })
+    }
+}
+
+/// A malformed request to an endpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Probe {
+    pub malformation: Malformation,
+    pub method: Method,
+    pub path: String,
+    pub content_type: Option<&'static str>,
+    pub body: Vec<u8>,
+}
+
+impl Probe {
+    /// The request of the probe to the server at `base_url`
+    pub fn request(&self, client: &reqwest::Client, base_url: &str) -> reqwest::RequestBuilder {
+        let mut req = client
+            .request(self.method.clone(), format!("{base_url}{}", self.path))
+            .timeout(PROBE_TIMEOUT)
+            .body(self.body.clone());
+        if let Some(content_type) = self.content_type {
+            req = req.header(reqwest::header::CONTENT_TYPE, content_type);
+        }
+        req
+    }
+
+    /// What is wrong with how the server answered the probe, if anything.
+    /// A transport that doesn't know the request, like a replay, says nothing about the server.
+    pub fn warning(&self, res: Result<StatusCode, &TransportError>) -> Option<String> {
+        let problem = match res {
+            Ok(status) if status.is_server_error() => format!("got {status}"),
+            Ok(_) | Err(TransportError::Unknown(_)) => return None,
+            Err(TransportError::Http(e)) if e.is_timeout() => {
+                format!("got no response within {PROBE_TIMEOUT:?}")
+            }
+            Err(TransportError::Http(e)) => format!("dropped the connection: {e}"),
+        };
+        Some(format!(
+            "{} {} with {} {problem}",
+            self.method,
+            shortened(&self.path),
+            self.malformation
+        ))
+    }
+}
+
+/// The path, without most of a long path parameter
+fn shortened(path: &str) -> String {
+    match path.char_indices().nth(60) {
+        Some((i, _)) => format!("{}… ({} bytes)", &path[..i], path.len()),
+        None => path.to_owned(),
+    }
+}
+
+/// One probe of each malformation for the `method` requests to `path`, generated by `rng`
+pub fn probes(method: &Method, path: &str, rng: &mut fastrand::Rng) -> Vec<Probe> {
+    let probe = |malformation, path: String, content_type, body| Probe {
+        malformation,
+        method: method.clone(),
+        path,
+        content_type,
+        body,
+    };
+    let json = json_object(rng);
+    let truncated = json[..rng.usize(1..json.len())].to_vec();
+    let mut probes = vec![
+        probe(
+            Malformation::TruncatedJson,
+            path.to_owned(),
+            Some("application/json"),
+            truncated,
+        ),
+        probe(
+            Malformation::WrongContentType,
+            path.to_owned(),
+            rng.choice(WRONG_CONTENT_TYPES).copied(),
+            json,
+        ),
+    ];
+    // the root has no parameter to replace
+    if let Some((parent, _)) = path.rsplit_once('/').filter(|(_, last)| !last.is_empty()) {
+        let param: String = (0..rng.usize(4096..=16384))
+            .map(|_| rng.alphanumeric())
+            .collect();
+        probes.push(probe(
+            Malformation::LongPathParam,
+            format!("{parent}/{param}"),
+            None,
+            Vec::new(),
+        ));
+    }
+    let mut text: Vec<u8> = (0..rng.usize(16..256))
+        .map(|_| rng.alphanumeric() as u8)
+        .collect();
+    for _ in 0..rng.usize(1..8) {
+        // a continuation byte without a start, bytes that never appear, and a start without a continuation
+        let invalid = rng.choice([0x80, 0xfe, 0xff, 0xc3]).unwrap_or(0xff);
+        text.insert(rng.usize(..=text.len()), invalid);
+    }
+    probes.push(probe(
+        Malformation::InvalidUtf8,
+        path.to_owned(),
+        Some("text/plain; charset=utf-8"),
+        text,
+    ));
+    probes
+}
+
+/// A JSON object with a few random members
+fn json_object(rng: &mut fastrand::Rng) -> Vec<u8> {
+    let object: serde_json::Map<String, serde_json::Value> = (0..rng.usize(1..=4))
+        .map(|_| {
+            let key: String = (0..rng.usize(1..=8)).map(|_| rng.lowercase()).collect();
+            let value = match rng.u8(..3) {
+                0 => rng.i64(..).into(),
+                1 => (0..rng.usize(..=16))
+                    .map(|_| rng.alphanumeric())
+                    .collect::<String>()
+                    .into(),
+                _ => (0..rng.usize(..=4))
+                    .map(|_| rng.u32(..))
+                    .collect::<Vec<_>>()
+                    .into(),
+            };
+            (key, value)
+        })
+        .collect();
+    serde_json::to_vec(&object).expect("a JSON object to serialize")
+}
+
+/// The distinct endpoints that a run sent requests to, in the order of their first request
+#[derive(Debug, Default)]
+pub struct Endpoints(Mutex<Vec<(Method, String)>>);
+
+impl Endpoints {
+    /// Add the endpoint of `method` requests to `path`, unless it is known or there are [`MAX_ENDPOINTS`]
+    pub fn add(&self, method: &Method, path: &str) {
+        let mut endpoints = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        if endpoints.len() < MAX_ENDPOINTS
+            && !endpoints.iter().any(|(m, p)| m == method && p == path)
+        {
+            endpoints.push((method.clone(), path.to_owned()));
+        }
+    }
+
+    pub fn list(&self) -> Vec<(Method, String)> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn probes_are_malformed_the_same_way_for_a_seed() {
+        let generate_for =
+            |path, seed| probes(&Method::POST, path, &mut fastrand::Rng::with_seed(seed));
+        let generate = |seed| generate_for("/4/strength", seed);
+        let probes = generate(2023);
+        assert_eq!(probes, generate(2023));
+        assert_ne!(probes, generate(2024));
+
+        let malformations = probes.iter().map(|p| p.malformation).collect::<Vec<_>>();
+        assert_eq!(
+            malformations,
+            [
+                Malformation::TruncatedJson,
+                Malformation::WrongContentType,
+                Malformation::LongPathParam,
+                Malformation::InvalidUtf8,
+            ]
+        );
+        assert!(serde_json::from_slice::<serde_json::Value>(&probes[0].body).is_err());
+        assert!(serde_json::from_slice::<serde_json::Value>(&probes[1].body).is_ok());
+        assert_ne!(probes[1].content_type, Some("application/json"));
+        assert!(probes[2].path.starts_with("/4/") && probes[2].path.len() > 4096);
+        assert!(std::str::from_utf8(&probes[3].body).is_err());
+
+        // the root has no parameter
+        let root = generate_for("/", 0);
+        assert!(root
+            .iter()
+            .all(|p| p.malformation != Malformation::LongPathParam));
+    }
+
+    #[test]
+    fn only_server_errors_are_warnings() {
+        let probe = probes(&Method::POST, "/6", &mut fastrand::Rng::with_seed(1)).remove(0);
+        assert_eq!(probe.warning(Ok(StatusCode::BAD_REQUEST)), None);
+        assert_eq!(probe.warning(Ok(StatusCode::OK)), None);
+        assert_eq!(
+            probe.warning(Ok(StatusCode::INTERNAL_SERVER_ERROR)),
+            Some("POST /6 with truncated JSON got 500 Internal Server Error".to_owned())
+        );
+        let unknown = TransportError::Unknown("POST /6 is not in the recording".to_owned());
+        assert_eq!(probe.warning(Err(&unknown)), None);
+    }
+}
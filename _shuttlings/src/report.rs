@@ -0,0 +1,647 @@
+//! Machine-readable results of a run, whatever the challenges of its [`Catalog`] are numbered with
+
+use std::{marker::PhantomData, time::Instant};
+
+use chrono::{DateTime, SecondsFormat, TimeDelta, Utc};
+use serde::Serialize;
+
+use crate::{
+    csv_report, Catalog, CsvRow, ScoreRow, ScoreStatus, SubmissionState, SubmissionUpdate,
+};
+
+/// Machine-readable results of validating one or more challenges
+#[derive(Debug, Serialize)]
+#[serde(bound = "")]
+pub struct Report<K: Catalog> {
+    /// The version of the validator
+    pub version: &'static str,
+    pub challenges: Vec<ChallengeReport<K::Challenge>>,
+    /// Challenges that were left out with `--skip`
+    pub skipped: Vec<K::Challenge>,
+    /// Bonus points gathered over all challenges
+    pub bonus: i32,
+    /// Seconds spent validating
+    pub duration: f64,
+    /// How often each challenge that was validated more than once passed, see `--repeat`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub flakiness: Vec<Flakiness<K::Challenge>>,
+    #[serde(skip)]
+    catalog: PhantomData<K>,
+}
+
+impl<K: Catalog> Report<K> {
+    /// A row of the score table for each challenge that was validated, and for each one left out with `--skip`.
+    /// The challenges that are not scored get no points.
+    pub fn score_rows(&self) -> Vec<ScoreRow> {
+        let validated = self.challenges.iter().map(|c| {
+            let scored = K::scored(&c.challenge);
+            let status = if c.tasks.is_empty() && !c.error {
+                ScoreStatus::Skipped
+            } else if c.core_done {
+                ScoreStatus::Completed
+            } else {
+                ScoreStatus::Failed
+            };
+            ScoreRow {
+                challenge: c.challenge.to_string(),
+                status,
+                result: c.result(),
+                // the leaderboard ranks by these before the bonus points
+                core: i32::from(scored && c.core_done),
+                bonus: if scored { c.bonus } else { 0 },
+            }
+        });
+        let skipped = self.skipped.iter().map(|num| ScoreRow {
+            challenge: num.to_string(),
+            status: ScoreStatus::Skipped,
+            result: "skipped".to_owned(),
+            core: 0,
+            bonus: 0,
+        });
+        validated.chain(skipped).collect()
+    }
+
+    /// Render the report as CSV, with a row per task of each challenge and a last row with the totals
+    pub fn to_csv(&self) -> String {
+        let rows: Vec<CsvRow> = self
+            .challenges
+            .iter()
+            .flat_map(|c| {
+                c.tasks.iter().map(move |t| {
+                    let tests = c.tests.iter().filter(|r| r.task == t.task);
+                    CsvRow {
+                        challenge: c.challenge.to_string(),
+                        task: t.task,
+                        status: match t.status {
+                            TaskStatus::Passed => "passed",
+                            TaskStatus::Failed => "failed",
+                            TaskStatus::Skipped => "skipped",
+                        },
+                        bonus: t.bonus,
+                        duration_ms: tests
+                            .clone()
+                            .map(|r| (r.duration * 1000.0).round() as u64)
+                            .sum(),
+                        failed_test: tests.clone().find(|r| !r.passed).map(|r| match &r.detail {
+                            Some(detail) => format!("test #{}: {detail}", r.test),
+                            None => format!("test #{}", r.test),
+                        }),
+                        timestamp: t.elapsed.map(|elapsed| {
+                            let finished = c.started_at
+                                + TimeDelta::milliseconds((elapsed * 1000.0).round() as i64);
+                            finished.to_rfc3339_opts(SecondsFormat::Millis, true)
+                        }),
+                    }
+                })
+            })
+            .collect();
+        csv_report(&rows)
+    }
+
+    /// Render the report as JUnit XML, with a `<testsuite>` per challenge and a `<testcase>` per test
+    pub fn to_junit(&self) -> String {
+        let tests = self.challenges.iter().map(|c| c.tests.len()).sum::<usize>();
+        let failures = self.challenges.iter().map(|c| c.failures()).sum::<usize>();
+        let errors = self.challenges.iter().filter(|c| c.error).count();
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<testsuites name=\"{}\" tests=\"{tests}\" failures=\"{failures}\" errors=\"{errors}\" time=\"{:.3}\">\n",
+            K::NAME,
+            self.duration,
+        ));
+        for c in &self.challenges {
+            xml.push_str(&format!(
+                "  <testsuite name=\"Challenge {}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"0\" time=\"{:.3}\">\n",
+                escape_xml(&c.challenge.to_string()),
+                c.tests.len(),
+                c.failures(),
+                u8::from(c.error),
+                c.duration,
+            ));
+            for t in &c.tests {
+                xml.push_str(&format!(
+                    "    <testcase name=\"Task {}: test #{}\" classname=\"challenge {}\" time=\"{:.3}\"",
+                    t.task,
+                    t.test,
+                    escape_xml(&c.challenge.to_string()),
+                    t.duration,
+                ));
+                if t.passed {
+                    xml.push_str("/>\n");
+                } else {
+                    xml.push_str(&format!(
+                        ">\n      <failure message=\"Task {}: test #{} failed\">{}</failure>\n    </testcase>\n",
+                        t.task,
+                        t.test,
+                        escape_xml(t.detail.as_deref().unwrap_or_default()),
+                    ));
+                }
+            }
+            xml.push_str("  </testsuite>\n");
+        }
+        xml.push_str("</testsuites>\n");
+        xml
+    }
+
+    /// GitHub Actions workflow commands that annotate the run:
+    /// an error for every failed test or challenge that could not be validated,
+    /// and a notice for the bonus points of every challenge
+    pub fn to_github_annotations(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for c in &self.challenges {
+            let title = escape_github_property(&format!("{} day {}", K::EVENT, c.challenge));
+            for t in c.tests.iter().filter(|t| !t.passed) {
+                let mut message = format!("Task {} test #{} failed", t.task, t.test);
+                if let Some(detail) = &t.detail {
+                    message.push_str(&format!(" — {detail}"));
+                }
+                lines.push(format!(
+                    "::error title={title}::{}",
+                    escape_github_data(&message)
+                ));
+            }
+            if c.error {
+                lines.push(format!("::error title={title}::Could not be validated"));
+            }
+            if c.bonus > 0 {
+                lines.push(format!("::notice title={title}::Bonus points: {}", c.bonus));
+            }
+        }
+        lines
+    }
+
+    /// The tests that took longest, at most `slowest` of them, and how long each challenge took, see `--timings`
+    pub fn timing_lines(&self, slowest: usize) -> Vec<String> {
+        let mut tests: Vec<_> = self
+            .challenges
+            .iter()
+            .flat_map(|c| c.tests.iter().map(move |t| (c, t)))
+            .collect();
+        tests.sort_by(|(_, a), (_, b)| b.duration.total_cmp(&a.duration));
+        let mut lines = vec!["Slowest tests:".to_owned()];
+        for (c, t) in tests.into_iter().take(slowest) {
+            let id = format!("{}.{}.{}", c.challenge, t.task, t.test);
+            lines.push(format!("  {id:<10} {:>8.3}s", t.duration));
+        }
+        lines.push("Time per challenge:".to_owned());
+        for c in &self.challenges {
+            let total: f64 = c.tests.iter().map(|t| t.duration).sum();
+            lines.push(format!("  {:<10} {total:>8.3}s", c.challenge));
+        }
+        lines
+    }
+}
+
+/// How often a challenge passed over several runs
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Flakiness<C> {
+    pub challenge: C,
+    pub runs: usize,
+    pub passed: usize,
+    /// Task number and test number of the test that failed most often, if a test failed
+    pub most_common_failure: Option<(i32, i32)>,
+}
+
+impl<C: std::fmt::Display> std::fmt::Display for Flakiness<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Challenge {}: passed {}/{}",
+            self.challenge, self.passed, self.runs
+        )?;
+        if let Some((task, test)) = self.most_common_failure {
+            write!(f, "; most common failure: task {task} test #{test}")?;
+        }
+        Ok(())
+    }
+}
+
+fn flakiness<C: PartialEq + Clone>(challenges: &[ChallengeReport<C>]) -> Vec<Flakiness<C>> {
+    let mut numbers: Vec<&C> = Vec::new();
+    for c in challenges {
+        if !numbers.contains(&&c.challenge) {
+            numbers.push(&c.challenge);
+        }
+    }
+    numbers
+        .into_iter()
+        .filter_map(|number| {
+            let runs: Vec<_> = challenges
+                .iter()
+                .filter(|c| c.challenge == *number)
+                .collect();
+            if runs.len() < 2 {
+                return None;
+            }
+            let failures: Vec<_> = runs.iter().filter_map(|c| c.failed_test).collect();
+            // of the failures that happened equally often, the one that happened first
+            let most_common_failure = failures
+                .iter()
+                .rev()
+                .max_by_key(|f| failures.iter().filter(|g| g == f).count())
+                .copied();
+            Some(Flakiness {
+                challenge: number.clone(),
+                runs: runs.len(),
+                passed: runs.iter().filter(|c| c.passed()).count(),
+                most_common_failure,
+            })
+        })
+        .collect()
+}
+
+/// Escape the message of a workflow command, see
+/// <https://github.com/actions/toolkit/blob/main/packages/core/src/command.ts>
+fn escape_github_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a property of a workflow command, which can't have `:` or `,` either
+fn escape_github_property(s: &str) -> String {
+    escape_github_data(s)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChallengeReport<C> {
+    pub challenge: C,
+    pub tasks: Vec<TaskReport>,
+    pub tests: Vec<TestReport>,
+    /// Task number and test number of the test that failed
+    pub failed_test: Option<(i32, i32)>,
+    pub bonus: i32,
+    /// Seconds spent validating this challenge
+    pub duration: f64,
+    /// The validation could not be finished because of a timeout, a connection failure or an internal error
+    pub error: bool,
+    /// When the validation of the challenge started
+    pub started_at: DateTime<Utc>,
+    /// The core tasks passed, which completes the challenge
+    pub core_done: bool,
+    #[serde(skip)]
+    started: Option<Instant>,
+    /// Task number and seconds since the run started of every [`SubmissionUpdate::Timing`]
+    #[serde(skip)]
+    timings: Vec<(i32, f64)>,
+}
+
+impl<C> ChallengeReport<C> {
+    /// Whether every task that was validated passed
+    pub fn passed(&self) -> bool {
+        !self.error && self.tasks.iter().all(|t| t.status == TaskStatus::Passed)
+    }
+
+    /// The result as it is shown in summaries, e.g. `passed 2/3`
+    pub fn result(&self) -> String {
+        let passed = self
+            .tasks
+            .iter()
+            .filter(|t| t.status == TaskStatus::Passed)
+            .count();
+        if self.error {
+            "error".to_owned()
+        } else if self.tasks.is_empty() {
+            "unsupported".to_owned()
+        } else if self.passed() {
+            format!("passed {passed}/{}", self.tasks.len())
+        } else {
+            format!("failed {passed}/{}", self.tasks.len())
+        }
+    }
+
+    fn failures(&self) -> usize {
+        self.tests.iter().filter(|t| !t.passed).count()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct TaskReport {
+    pub task: i32,
+    pub status: TaskStatus,
+    pub bonus: i32,
+    /// Seconds after the start of the run that the task finished, if it was validated
+    pub elapsed: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TestReport {
+    pub task: i32,
+    pub test: i32,
+    pub passed: bool,
+    /// Seconds spent on the requests of this test, or on the whole test if it sent none
+    pub duration: f64,
+    /// Why the test failed, if known
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskStatus {
+    Passed,
+    Failed,
+    /// The task was not reached because an earlier task failed or the challenge timed out,
+    /// or it was skipped because a task it depends on failed
+    Skipped,
+}
+
+/// Collects the updates of challenges that are run one after another into a [`Report`]
+pub struct ReportBuilder<K: Catalog> {
+    numbers: Vec<K::Challenge>,
+    skipped: Vec<K::Challenge>,
+    task: Option<i32>,
+    started: Instant,
+    challenges: Vec<ChallengeReport<K::Challenge>>,
+}
+
+impl<K: Catalog> ReportBuilder<K> {
+    /// `numbers` are the challenges in the order they are run.
+    /// `task` is set if only that task of each challenge is validated.
+    pub fn new(numbers: Vec<K::Challenge>, skipped: Vec<K::Challenge>, task: Option<i32>) -> Self {
+        Self {
+            numbers,
+            skipped,
+            task,
+            started: Instant::now(),
+            challenges: Vec::new(),
+        }
+    }
+
+    pub fn update(&mut self, update: &SubmissionUpdate) {
+        match update {
+            SubmissionUpdate::State(SubmissionState::Running) => {
+                self.finish_challenge();
+                let challenge = self
+                    .numbers
+                    .get(self.challenges.len())
+                    .cloned()
+                    .unwrap_or_default();
+                self.challenges.push(ChallengeReport {
+                    challenge,
+                    tasks: Vec::new(),
+                    tests: Vec::new(),
+                    failed_test: None,
+                    bonus: 0,
+                    duration: 0.0,
+                    error: false,
+                    started_at: Utc::now(),
+                    core_done: false,
+                    started: Some(Instant::now()),
+                    timings: Vec::new(),
+                });
+            }
+            SubmissionUpdate::State(SubmissionState::Done) => self.finish_challenge(),
+            SubmissionUpdate::State(SubmissionState::Error) => {
+                if let Some(c) = self.challenges.last_mut() {
+                    c.error = true;
+                }
+                self.finish_challenge();
+            }
+            SubmissionUpdate::TaskCompleted(core_done, bonus) => {
+                let task = self.task;
+                if let Some(c) = self.challenges.last_mut() {
+                    // the tests of a task are reported before the task itself
+                    let last_test = c.tests.last().map(|t| t.task);
+                    c.tasks.push(TaskReport {
+                        task: last_test.or(task).unwrap_or(c.tasks.len() as i32 + 1),
+                        status: TaskStatus::Passed,
+                        bonus: *bonus,
+                        elapsed: None,
+                    });
+                    c.bonus += bonus;
+                    c.core_done |= core_done;
+                }
+            }
+            SubmissionUpdate::TestResult(t) => {
+                if let Some(c) = self.challenges.last_mut() {
+                    c.tests.push(TestReport {
+                        task: t.task,
+                        test: t.test,
+                        passed: t.passed,
+                        duration: t.duration_ms as f64 / 1000.0,
+                        detail: t.detail.clone(),
+                    });
+                    if !t.passed {
+                        c.failed_test = Some((t.task, t.test));
+                        c.tasks.push(TaskReport {
+                            task: t.task,
+                            status: TaskStatus::Failed,
+                            bonus: 0,
+                            elapsed: None,
+                        });
+                    }
+                }
+            }
+            SubmissionUpdate::Timing { task, elapsed_ms } => {
+                if let Some(c) = self.challenges.last_mut() {
+                    c.timings.push((*task, *elapsed_ms as f64 / 1000.0));
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// The challenge that is being, or was last, validated
+    pub fn last(&self) -> Option<&ChallengeReport<K::Challenge>> {
+        self.challenges.last()
+    }
+
+    pub fn finish(mut self) -> Report<K> {
+        self.finish_challenge();
+        Report {
+            version: K::VERSION,
+            bonus: self.challenges.iter().map(|c| c.bonus).sum(),
+            flakiness: flakiness(&self.challenges),
+            challenges: self.challenges,
+            skipped: self.skipped,
+            duration: self.started.elapsed().as_secs_f64(),
+            catalog: PhantomData,
+        }
+    }
+
+    fn finish_challenge(&mut self) {
+        let Some(c) = self.challenges.last_mut() else {
+            return;
+        };
+        let Some(started) = c.started.take() else {
+            return;
+        };
+        c.duration = started.elapsed().as_secs_f64();
+        let count = K::task_count(&c.challenge).unwrap_or_default();
+        let tasks = match self.task {
+            Some(task) => task..=task.min(count),
+            None => 1..=count,
+        };
+        let skipped: Vec<i32> = tasks
+            .filter(|task| c.tasks.iter().all(|t| t.task != *task))
+            .collect();
+        c.tasks.extend(skipped.into_iter().map(|task| TaskReport {
+            task,
+            status: TaskStatus::Skipped,
+            bonus: 0,
+            elapsed: None,
+        }));
+        c.tasks.sort_by_key(|t| t.task);
+        // a failed task only gets its report after its timing
+        for (task, elapsed) in c.timings.drain(..) {
+            if let Some(t) = c.tasks.iter_mut().find(|t| t.task == task) {
+                t.elapsed = Some(elapsed);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::Days, TestResult};
+
+    #[test]
+    fn repeated_runs_are_summarized() {
+        let result = |passed| {
+            SubmissionUpdate::TestResult(TestResult {
+                task: 1,
+                test: 1,
+                passed,
+                duration_ms: 0,
+                detail: None,
+            })
+        };
+        let mut report = ReportBuilder::<Days>::new(vec![-1; 3], Vec::new(), Some(1));
+        for passed in [false, true, false] {
+            report.update(&SubmissionState::Running.into());
+            report.update(&result(passed));
+            if passed {
+                report.update(&SubmissionUpdate::TaskCompleted(true, 0));
+            }
+            report.update(&SubmissionState::Done.into());
+        }
+        let flakiness = report.finish().flakiness;
+        assert_eq!(
+            flakiness,
+            [Flakiness {
+                challenge: -1,
+                runs: 3,
+                passed: 1,
+                most_common_failure: Some((1, 1)),
+            }]
+        );
+        assert_eq!(
+            flakiness[0].to_string(),
+            "Challenge -1: passed 1/3; most common failure: task 1 test #1"
+        );
+    }
+
+    #[test]
+    fn failures_become_github_annotations() {
+        let mut report = ReportBuilder::<Days>::new(vec![-1], Vec::new(), None);
+        for update in [
+            SubmissionState::Running.into(),
+            SubmissionUpdate::TestResult(TestResult {
+                task: 1,
+                test: 1,
+                passed: true,
+                duration_ms: 0,
+                detail: None,
+            }),
+            SubmissionUpdate::TaskCompleted(true, 50),
+            SubmissionUpdate::TestResult(TestResult {
+                task: 2,
+                test: 1,
+                passed: false,
+                duration_ms: 0,
+                detail: Some("expected \"100%\",\ngot \"\"".to_owned()),
+            }),
+            SubmissionState::Done.into(),
+        ] {
+            report.update(&update);
+        }
+        assert_eq!(
+            report.finish().to_github_annotations(),
+            [
+                "::error title=CCH23 day -1::Task 2 test #1 failed — expected \"100%25\",%0Agot \"\"",
+                "::notice title=CCH23 day -1::Bonus points: 50",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_render_as_csv() {
+        let mut report = ReportBuilder::<Days>::new(vec![-1], Vec::new(), None);
+        for update in [
+            SubmissionState::Running.into(),
+            SubmissionUpdate::TestResult(TestResult {
+                task: 1,
+                test: 1,
+                passed: true,
+                duration_ms: 40,
+                detail: None,
+            }),
+            SubmissionUpdate::TaskCompleted(true, 0),
+            SubmissionUpdate::TestResult(TestResult {
+                task: 2,
+                test: 1,
+                passed: false,
+                duration_ms: 2,
+                detail: Some("expected 500, got 200".to_owned()),
+            }),
+            SubmissionState::Done.into(),
+        ] {
+            report.update(&update);
+        }
+        assert_eq!(
+            report.finish().to_csv(),
+            "\
+challenge,task,status,bonus,duration_ms,failed_test,timestamp
+-1,1,passed,0,40,,
+-1,2,failed,0,2,\"test #1: expected 500, got 200\",
+total,,1/2 passed,0,42,,
+"
+        );
+    }
+
+    #[test]
+    fn timings_show_the_slowest_tests() {
+        let result = |task, duration_ms| {
+            SubmissionUpdate::TestResult(TestResult {
+                task,
+                test: 1,
+                passed: true,
+                duration_ms,
+                detail: None,
+            })
+        };
+        let mut report = ReportBuilder::<Days>::new(vec![-1, 5], Vec::new(), None);
+        for updates in [
+            [result(1, 20), result(2, 1500)],
+            [result(1, 300), result(2, 5)],
+        ] {
+            report.update(&SubmissionState::Running.into());
+            for update in &updates {
+                report.update(update);
+            }
+            report.update(&SubmissionState::Done.into());
+        }
+        assert_eq!(
+            report.finish().timing_lines(2),
+            [
+                "Slowest tests:",
+                "  -1.2.1        1.500s",
+                "  5.1.1         0.300s",
+                "Time per challenge:",
+                "  -1            1.520s",
+                "  5             0.305s",
+            ]
+        );
+    }
+}
@@ -0,0 +1,264 @@
+//! An HTTP API that validates submissions, for `--serve`
+
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    routing::{get, post},
+    Json, Router,
+};
+use dashmap::DashMap;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tokio::{
+    sync::{broadcast, mpsc::Sender, Semaphore},
+    time::{Duration, Instant},
+};
+use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
+use uuid::Uuid;
+
+use crate::{Catalog, RunOutcome, SubmissionReport, SubmissionUpdate, UNIX_SCHEME};
+
+/// How many updates a subscriber to the events of a submission can fall behind before it misses some
+const EVENT_BUFFER: usize = 256;
+/// How long a finished submission can still be looked up
+const SUBMISSION_TTL: Duration = Duration::from_secs(60 * 60);
+/// How many finished submissions are kept at most, the ones that finished first are dropped first
+const MAX_FINISHED: usize = 1000;
+
+/// A validator that the API runs the submissions of
+pub trait Service: Catalog<Challenge: DeserializeOwned + Send + Sync> + 'static {
+    /// The options that every submission is validated with
+    type Config: Send + Sync + 'static;
+
+    /// The base URL of the solution at `url` as runs take it, or why it is not a valid URL
+    fn normalize_url(url: &str) -> Result<String, String>;
+
+    /// Why `challenge` can't be validated, if it is not supported
+    fn unsupported(challenge: &Self::Challenge) -> Option<String>;
+
+    /// Validate `challenge` of the solution at `url` as submission `id`, sending the updates to `tx`
+    fn run(
+        url: String,
+        id: Uuid,
+        challenge: Self::Challenge,
+        config: &Self::Config,
+        tx: Sender<SubmissionUpdate>,
+    ) -> impl Future<Output = RunOutcome> + Send;
+}
+
+struct AppState<K: Service> {
+    submissions: Arc<DashMap<Uuid, Submission>>,
+    /// Caps the number of submissions that are validated at the same time
+    permits: Arc<Semaphore>,
+    config: Arc<K::Config>,
+}
+
+// not derived, which would need the validator itself to be `Clone`
+impl<K: Service> Clone for AppState<K> {
+    fn clone(&self) -> Self {
+        Self {
+            submissions: self.submissions.clone(),
+            permits: self.permits.clone(),
+            config: self.config.clone(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Submission {
+    report: SubmissionReport,
+    /// Every update so far, for subscribers that come after the validation started
+    updates: Vec<SubmissionUpdate>,
+    /// Sends the updates as they come, until the validation ends
+    live: Option<broadcast::Sender<SubmissionUpdate>>,
+    /// When the validation ended, which lets the submission be evicted
+    finished: Option<Instant>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ValidateRequest<C> {
+    /// The base URL of the solution
+    pub url: String,
+    pub challenge: C,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ValidateResponse {
+    /// Identifies the submission in `/submissions/{id}`
+    pub id: Uuid,
+}
+
+/// The routes of the API, which validates at most `concurrency` submissions at the same time
+pub fn router<K: Service>(config: K::Config, concurrency: usize) -> Router {
+    let state = AppState::<K> {
+        submissions: Arc::new(DashMap::new()),
+        permits: Arc::new(Semaphore::new(concurrency)),
+        config: Arc::new(config),
+    };
+    Router::new()
+        .route("/validate", post(validate::<K>))
+        .route("/submissions/{id}", get(submission::<K>))
+        .route("/submissions/{id}/events", get(events::<K>))
+        .with_state(state)
+}
+
+/// Serve the API on `addr` until Ctrl-C is pressed
+pub async fn serve<K: Service>(
+    addr: SocketAddr,
+    config: K::Config,
+    concurrency: usize,
+) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, router::<K>(config, concurrency))
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await
+}
+
+/// Start validating a submission, once there is room for it
+async fn validate<K: Service>(
+    State(state): State<AppState<K>>,
+    Json(request): Json<ValidateRequest<K::Challenge>>,
+) -> Response {
+    let url = match K::normalize_url(&request.url) {
+        // a socket of the host of the API is no solution of whoever sends the request
+        Ok(url) if url.starts_with(UNIX_SCHEME) => {
+            let message = "Unix sockets can't be validated through the API";
+            return (StatusCode::BAD_REQUEST, message).into_response();
+        }
+        Ok(url) => url,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    let number = request.challenge;
+    if let Some(message) = K::unsupported(&number) {
+        return (StatusCode::BAD_REQUEST, message).into_response();
+    }
+    let id = Uuid::new_v4();
+    let (live, _) = broadcast::channel(EVENT_BUFFER);
+    state.submissions.insert(
+        id,
+        Submission {
+            live: Some(live),
+            ..Default::default()
+        },
+    );
+    tokio::spawn(async move {
+        let Ok(_permit) = state.permits.acquire().await else {
+            return;
+        };
+        let (tx, mut rx) = tokio::sync::mpsc::channel(32);
+        let run = K::run(url, id, number, &state.config, tx);
+        let record = async {
+            while let Some(update) = rx.recv().await {
+                if let Some(mut s) = state.submissions.get_mut(&id) {
+                    s.report.apply(&update);
+                    s.updates.push(update.clone());
+                    if let Some(live) = &s.live {
+                        let _ = live.send(update);
+                    }
+                }
+            }
+        };
+        tokio::join!(run, record);
+        // ends the event streams
+        if let Some(mut s) = state.submissions.get_mut(&id) {
+            s.live = None;
+            s.finished = Some(Instant::now());
+        }
+        evict(&state.submissions);
+    });
+    (StatusCode::ACCEPTED, Json(ValidateResponse { id })).into_response()
+}
+
+/// The progress of a submission so far
+async fn submission<K: Service>(
+    State(state): State<AppState<K>>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    match state.submissions.get(&id) {
+        Some(s) => Json(s.report.clone().finalize()).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Server-sent events with every update of a submission, from the start until the validation ends
+async fn events<K: Service>(
+    State(state): State<AppState<K>>,
+    Path(id): Path<Uuid>,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, StatusCode> {
+    let (updates, live) = {
+        let s = state.submissions.get(&id).ok_or(StatusCode::NOT_FOUND)?;
+        // a receiver of a channel that is already closed if the validation ended
+        let live = s
+            .live
+            .as_ref()
+            .map_or_else(|| broadcast::channel(1).1, |live| live.subscribe());
+        (s.updates.clone(), live)
+    };
+    let stream = tokio_stream::iter(updates)
+        .chain(BroadcastStream::new(live).filter_map(Result::ok))
+        .map(|update| Event::default().json_data(update));
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// Drop the finished submissions that are older than [`SUBMISSION_TTL`],
+/// and those that finished first of the ones above [`MAX_FINISHED`]
+fn evict(submissions: &DashMap<Uuid, Submission>) {
+    submissions.retain(|_, s| s.finished.is_none_or(|f| f.elapsed() < SUBMISSION_TTL));
+    let mut finished: Vec<(Instant, Uuid)> = submissions
+        .iter()
+        .filter_map(|s| s.finished.map(|f| (f, *s.key())))
+        .collect();
+    let excess = finished.len().saturating_sub(MAX_FINISHED);
+    if excess > 0 {
+        finished.sort_unstable();
+        for (_, id) in &finished[..excess] {
+            submissions.remove(id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn finished_submissions_are_evicted() {
+        let submissions = DashMap::new();
+        let finish = |submissions: &DashMap<_, _>| {
+            let id = Uuid::new_v4();
+            let finished = Some(Instant::now());
+            submissions.insert(
+                id,
+                Submission {
+                    finished,
+                    ..Default::default()
+                },
+            );
+            id
+        };
+        let running = Uuid::new_v4();
+        submissions.insert(running, Submission::default());
+        let expired = finish(&submissions);
+        tokio::time::advance(SUBMISSION_TTL).await;
+        let mut kept = Vec::new();
+        for _ in 0..=MAX_FINISHED {
+            kept.push(finish(&submissions));
+            tokio::time::advance(Duration::from_millis(1)).await;
+        }
+        evict(&submissions);
+        assert!(submissions.contains_key(&running));
+        assert!(!submissions.contains_key(&expired));
+        // the one that finished first is over the cap
+        assert!(!submissions.contains_key(&kept[0]));
+        assert!(kept[1..].iter().all(|id| submissions.contains_key(id)));
+    }
+}
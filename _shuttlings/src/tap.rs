@@ -0,0 +1,169 @@
+//! Output in the Test Anything Protocol, see <https://testanything.org>
+
+use std::marker::PhantomData;
+
+use crate::{Catalog, SubmissionState, SubmissionUpdate};
+
+/// Turns the updates of challenges that are run one after another into TAP lines
+pub struct TapWriter<K: Catalog> {
+    numbers: Vec<K::Challenge>,
+    task: Option<i32>,
+    /// Index in `numbers` of the challenge that is being validated
+    current: Option<usize>,
+    /// Task number and test number of the tests of the current challenge that have a line
+    reported: Vec<(i32, i32)>,
+    /// Number of the last test line
+    count: usize,
+    catalog: PhantomData<K>,
+}
+
+impl<K: Catalog> TapWriter<K> {
+    /// `numbers` are the challenges in the order they are run.
+    /// `task` is set if only that task of each challenge is validated.
+    pub fn new(numbers: Vec<K::Challenge>, task: Option<i32>) -> Self {
+        Self {
+            numbers,
+            task,
+            current: None,
+            reported: Vec::new(),
+            count: 0,
+            catalog: PhantomData,
+        }
+    }
+
+    /// The plan line, with the number of tests of all challenges
+    pub fn plan(&self) -> String {
+        let tests: usize = self
+            .numbers
+            .iter()
+            .map(|n| tests::<K>(n, self.task).len())
+            .sum();
+        format!("1..{tests}")
+    }
+
+    /// The lines that an update adds
+    pub fn update(&mut self, update: &SubmissionUpdate) -> Vec<String> {
+        match update {
+            SubmissionUpdate::State(SubmissionState::Running) => {
+                self.current = Some(self.current.map_or(0, |c| c + 1));
+                self.reported.clear();
+                Vec::new()
+            }
+            SubmissionUpdate::TestResult(t) => {
+                let detail = t.detail.as_deref().filter(|_| !t.passed);
+                vec![self.test_line(t.task, t.test, t.passed, detail)]
+            }
+            // tests that a task passes without starting them only count as passed once the task does
+            SubmissionUpdate::TaskCompleted(..) => {
+                let task = self.reported.last().map(|(task, _)| *task).or(self.task);
+                self.unreported(task)
+                    .into_iter()
+                    .map(|(task, test)| self.test_line(task, test, true, None))
+                    .collect()
+            }
+            SubmissionUpdate::State(state @ (SubmissionState::Done | SubmissionState::Error)) => {
+                // a challenge that could not be finished fails at the first test that was not reached
+                let error = *state == SubmissionState::Error;
+                self.unreported(self.task)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, (task, test))| {
+                        if error && i == 0 {
+                            self.test_line(task, test, false, None)
+                        } else {
+                            self.test_line(task, test, true, Some("SKIP not validated"))
+                        }
+                    })
+                    .collect()
+            }
+            SubmissionUpdate::LogLine { message, .. } => {
+                message.lines().map(|l| format!("# {l}")).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn number(&self) -> K::Challenge {
+        self.current
+            .and_then(|c| self.numbers.get(c))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Tests of the current challenge that don't have a line yet, of `task` if it is set
+    fn unreported(&self, task: Option<i32>) -> Vec<(i32, i32)> {
+        tests::<K>(&self.number(), task)
+            .into_iter()
+            .filter(|t| !self.reported.contains(t))
+            .collect()
+    }
+
+    fn test_line(&mut self, task: i32, test: i32, ok: bool, directive: Option<&str>) -> String {
+        self.count += 1;
+        self.reported.push((task, test));
+        let mut line = format!(
+            "{}ok {} - challenge {} task {task} test {test}",
+            if ok { "" } else { "not " },
+            self.count,
+            self.number(),
+        );
+        if let Some(directive) = directive {
+            line.push_str(" # ");
+            line.push_str(&directive.replace('\n', " "));
+        }
+        line
+    }
+}
+
+/// Task number and test number of the tests of `challenge`, or only of `task` if it is set
+fn tests<K: Catalog>(challenge: &K::Challenge, task: Option<i32>) -> Vec<(i32, i32)> {
+    K::tests(challenge)
+        .into_iter()
+        .filter(|(t, _)| task.is_none_or(|task| *t == task))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::Days, LogLevel, TestResult};
+
+    #[test]
+    fn tap_has_a_line_per_test() {
+        let result = |task, passed, detail: Option<&str>| {
+            SubmissionUpdate::TestResult(TestResult {
+                task,
+                test: 1,
+                passed,
+                duration_ms: 0,
+                detail: detail.map(str::to_owned),
+            })
+        };
+        let mut tap = TapWriter::<Days>::new(vec![-1, -1], None);
+        let mut lines = vec![tap.plan()];
+        for update in [
+            SubmissionState::Running.into(),
+            result(1, true, None),
+            SubmissionUpdate::TaskCompleted(true, 0),
+            result(2, false, Some("expected 500, got 200")),
+            (LogLevel::Error, "Task 2: test #1 failed 🟥".to_owned()).into(),
+            SubmissionState::Done.into(),
+            // a run that could not be finished
+            SubmissionState::Running.into(),
+            SubmissionState::Error.into(),
+        ] {
+            lines.extend(tap.update(&update));
+        }
+        assert_eq!(
+            lines,
+            [
+                "1..4",
+                "ok 1 - challenge -1 task 1 test 1",
+                "not ok 2 - challenge -1 task 2 test 1 # expected 500, got 200",
+                "# Task 2: test #1 failed 🟥",
+                "not ok 3 - challenge -1 task 1 test 1",
+                "ok 4 - challenge -1 task 2 test 1 # SKIP not validated",
+            ]
+        );
+    }
+}
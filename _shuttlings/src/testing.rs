@@ -0,0 +1,1575 @@
+//! Testers that send requests to a solution and check its responses, to write integration tests with.
+//! This is what the validators use to check solutions, see the `testing` feature.
+//!
+//! ```no_run
+//! use serde_json::json;
+//! use shuttlings::testing::{JSONTester, StatusCode, TestError};
+//!
+//! # async fn example() -> Result<(), TestError> {
+//! let tester = JSONTester::new("http://localhost:8000/4/strength");
+//! tester
+//!     .test(&json!([{"name": "Dasher", "strength": 5}]), StatusCode::OK, &[], &json!(5))
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{io::Read, pin::Pin};
+
+use futures_util::{Sink, SinkExt, Stream, StreamExt};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+pub use reqwest::{header, Method, StatusCode};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    time::Duration,
+};
+pub use tokio_tungstenite::tungstenite::protocol::{frame::coding::CloseCode, CloseFrame};
+use tokio_tungstenite::{tungstenite::Message, WebSocketStream};
+
+use crate::{TestId, UpdatesClosed};
+
+/// Why a check of a response failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TestError {
+    /// The request could not be sent, or the response could not be read
+    Request(String),
+    /// The response was not the expected one
+    Mismatch { expected: String, actual: String },
+    /// The text of the response was not the expected one, where [`text_mismatch_detail`] says if only its whitespace differs
+    TextMismatch {
+        expected: String,
+        actual: String,
+        detail: String,
+    },
+    /// The JSON of the response was not the expected one, where [`json_differences`] says
+    JsonMismatch {
+        expected: String,
+        actual: String,
+        differences: Vec<String>,
+    },
+    /// The websocket connection was closed, with the close frame of the server if it sent one
+    Closed(Option<CloseFrame<'static>>),
+    /// The body of the response was longer than `limit` bytes, see [`read_body`]
+    TooLarge { limit: usize, prefix: String },
+    /// The body of the response was compressed with `encoding`, but could not be decompressed, see [`decompress`]
+    Decompression { encoding: String, error: String },
+}
+
+impl std::fmt::Display for TestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Request(e) => write!(f, "request failed: {e}"),
+            Self::Mismatch { expected, actual } => write!(f, "expected {expected}, got {actual}"),
+            Self::TextMismatch {
+                expected,
+                actual,
+                detail,
+            } => write!(f, "expected {expected}, got {actual}{detail}"),
+            Self::JsonMismatch { differences, .. } => write!(f, "{}", differences.join("; ")),
+            Self::Closed(None) => write!(f, "the connection was closed"),
+            Self::Closed(Some(frame)) => write!(f, "the connection was closed with {frame}"),
+            Self::TooLarge { limit, prefix } => write!(
+                f,
+                "response exceeded size limit of {limit} bytes, starting with {prefix:?}"
+            ),
+            Self::Decompression { encoding, error } => {
+                write!(
+                    f,
+                    "the {encoding}-compressed response could not be decompressed: {error}"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for TestError {}
+
+impl From<reqwest::Error> for TestError {
+    fn from(e: reqwest::Error) -> Self {
+        Self::Request(e.to_string())
+    }
+}
+
+impl From<tokio_tungstenite::tungstenite::Error> for TestError {
+    fn from(e: tokio_tungstenite::tungstenite::Error) -> Self {
+        use tokio_tungstenite::tungstenite::Error;
+
+        match e {
+            Error::ConnectionClosed | Error::AlreadyClosed => Self::Closed(None),
+            e => Self::Request(e.to_string()),
+        }
+    }
+}
+
+/// How many characters of an expected or actual value are shown when a test fails
+const MAX_VALUE_LEN: usize = 200;
+
+/// A test that failed, with the compared values if it failed because they did not match
+#[derive(Debug)]
+pub struct TestFailure {
+    /// The challenge is only known once [`TestFailure::in_challenge`] sets it
+    pub id: TestId,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+    /// Where compared JSON values differ, which says more than the truncated values
+    pub differences: Vec<String>,
+}
+
+impl std::fmt::Display for TestFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "test {} failed", self.id)?;
+        if !self.differences.is_empty() {
+            write!(f, ": {}", self.differences.join("; "))?;
+        } else if let (Some(expected), Some(actual)) = (&self.expected, &self.actual) {
+            write!(f, ": expected {expected}, got {actual}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for TestFailure {}
+
+impl TestFailure {
+    /// `test` got `actual` where it expected `expected`
+    pub fn mismatch(test: (i32, i32), expected: String, actual: String) -> Self {
+        Self {
+            expected: Some(truncate_value(expected)),
+            actual: Some(truncate_value(actual)),
+            ..test.into()
+        }
+    }
+
+    /// `test` failed a check of this module
+    pub fn failed(test: (i32, i32), e: TestError) -> Self {
+        match e {
+            TestError::Mismatch { expected, actual } => Self::mismatch(test, expected, actual),
+            // the detail is short, and says more than the truncated texts
+            TestError::TextMismatch {
+                expected,
+                actual,
+                detail,
+            } => {
+                let failure = Self::mismatch(test, expected, actual);
+                Self {
+                    actual: failure.actual.map(|actual| actual + &detail),
+                    ..failure
+                }
+            }
+            TestError::JsonMismatch {
+                expected,
+                actual,
+                differences,
+            } => Self {
+                differences,
+                ..Self::mismatch(test, expected, actual)
+            },
+            e @ TestError::TooLarge { .. } => Self {
+                differences: vec![e.to_string()],
+                ..test.into()
+            },
+            e @ TestError::Decompression { .. } => Self {
+                differences: vec![e.to_string()],
+                ..test.into()
+            },
+            TestError::Request(_) | TestError::Closed(_) => test.into(),
+        }
+    }
+
+    /// Sets the challenge of the failed test
+    pub fn in_challenge(mut self, number: impl ToString) -> Self {
+        self.id.challenge = number.to_string();
+        self
+    }
+}
+
+/// Lets a task stop at its next update once nobody receives them.
+/// The validators never report this failure.
+impl From<UpdatesClosed> for TestFailure {
+    fn from(_: UpdatesClosed) -> Self {
+        (0, 0).into()
+    }
+}
+
+impl From<(i32, i32)> for TestFailure {
+    fn from(test: (i32, i32)) -> Self {
+        Self {
+            id: test.into(),
+            expected: None,
+            actual: None,
+            differences: Vec::new(),
+        }
+    }
+}
+
+fn truncate_value(value: String) -> String {
+    match value.char_indices().nth(MAX_VALUE_LEN) {
+        Some((end, _)) => format!("{}…", &value[..end]),
+        None => value,
+    }
+}
+
+/// How much of a response body is read before it is [`TestError::TooLarge`]
+pub const DEFAULT_MAX_RESPONSE_SIZE: usize = 8 * 1024 * 1024;
+/// How much of a body that is too large is kept in [`TestError::TooLarge`]
+const TOO_LARGE_PREFIX_LEN: usize = 100;
+
+/// Read the body of `res`, but no more than `limit` bytes of it,
+/// so that a server that never ends its body can't make the reader buffer it forever
+pub async fn read_body(mut res: reqwest::Response, limit: usize) -> Result<Vec<u8>, TestError> {
+    let mut body = Vec::new();
+    while let Some(chunk) = res.chunk().await? {
+        if body.len() + chunk.len() > limit {
+            body.extend_from_slice(&chunk[..chunk.len().min(TOO_LARGE_PREFIX_LEN)]);
+            body.truncate(TOO_LARGE_PREFIX_LEN);
+            return Err(TestError::TooLarge {
+                limit,
+                prefix: String::from_utf8_lossy(&body).into_owned(),
+            });
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+/// The `Accept-Encoding` of the clients of the validators, with the codings that [`decompress`] decodes
+pub const ACCEPT_ENCODING: &str = "gzip, br";
+
+/// The coding of a body with the `Content-Encoding` of `headers`, if it is one of [`ACCEPT_ENCODING`]
+pub fn compression(headers: &HeaderMap) -> Option<&'static str> {
+    let encoding = headers
+        .get(reqwest::header::CONTENT_ENCODING)?
+        .to_str()
+        .ok()?;
+    match encoding.trim().to_ascii_lowercase().as_str() {
+        "gzip" | "x-gzip" => Some("gzip"),
+        "br" => Some("br"),
+        _ => None,
+    }
+}
+
+/// Decompress a `body` of the `encoding` from [`compression`], to no more than `limit` bytes,
+/// so that a small body can't make the reader buffer a huge one
+pub fn decompress(encoding: &str, body: &[u8], limit: usize) -> Result<Vec<u8>, TestError> {
+    let decoder: Box<dyn Read + '_> = match encoding {
+        "gzip" => Box::new(flate2::read::MultiGzDecoder::new(body)),
+        _ => Box::new(brotli::Decompressor::new(body, 4096)),
+    };
+    let mut decompressed = Vec::new();
+    decoder
+        .take(limit as u64 + 1)
+        .read_to_end(&mut decompressed)
+        .map_err(|e| TestError::Decompression {
+            encoding: encoding.to_owned(),
+            error: e.to_string(),
+        })?;
+    if decompressed.len() > limit {
+        decompressed.truncate(TOO_LARGE_PREFIX_LEN);
+        return Err(TestError::TooLarge {
+            limit,
+            prefix: String::from_utf8_lossy(&decompressed).into_owned(),
+        });
+    }
+    Ok(decompressed)
+}
+
+/// The body of `res` as text, read with [`DEFAULT_MAX_RESPONSE_SIZE`]
+async fn read_text(res: reqwest::Response) -> Result<String, TestError> {
+    let body = read_body(res, DEFAULT_MAX_RESPONSE_SIZE).await?;
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+pub fn check_status(actual: StatusCode, expected: StatusCode) -> Result<(), TestError> {
+    if actual != expected {
+        return Err(TestError::Mismatch {
+            expected: expected.to_string(),
+            actual: actual.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Check that each of the `expected` headers has its value.
+/// The [`TestError::Mismatch`] names the first header that differed.
+pub fn check_headers(actual: &HeaderMap, expected: &[(HeaderName, &str)]) -> Result<(), TestError> {
+    for (name, value) in expected {
+        let found = actual.get(name);
+        if found.is_none_or(|v| v != value) {
+            return Err(TestError::Mismatch {
+                expected: format!("{name}: {value:?}"),
+                actual: match found {
+                    Some(v) => format!("{name}: {:?}", String::from_utf8_lossy(v.as_bytes())),
+                    None => format!("no {name} header"),
+                },
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Check that the media type of the `content-type` header is `expected`, e.g. `text/html`,
+/// whatever its parameters like the charset
+pub fn check_content_type(actual: &HeaderMap, expected: &str) -> Result<(), TestError> {
+    let found = actual
+        .get(reqwest::header::CONTENT_TYPE)
+        .map(|v| String::from_utf8_lossy(v.as_bytes()).into_owned());
+    let media_type = found
+        .as_deref()
+        .and_then(|v| v.split(';').next())
+        .map(str::trim);
+    if media_type.is_some_and(|m| m.eq_ignore_ascii_case(expected)) {
+        return Ok(());
+    }
+    Err(TestError::Mismatch {
+        expected: format!("content-type: {expected:?}"),
+        actual: match found {
+            Some(v) => format!("content-type: {v:?}"),
+            None => "no content-type header".to_owned(),
+        },
+    })
+}
+
+/// How close a number has to be to the expected one
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tolerance {
+    /// Less than this far from it
+    Absolute(f64),
+    /// Less than this fraction of it away from it, so that only 0 is near 0
+    Relative(f64),
+}
+
+impl Tolerance {
+    /// Whether `actual` is near enough to `expected`. NaN and infinities never are.
+    pub fn allows(self, actual: f64, expected: f64) -> bool {
+        let bound = match self {
+            Self::Absolute(tol) => tol,
+            Self::Relative(tol) => tol * expected.abs(),
+        };
+        actual.is_finite() && (actual == expected || (actual - expected).abs() < bound)
+    }
+}
+
+impl std::fmt::Display for Tolerance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Absolute(tol) => write!(f, "± {tol}"),
+            Self::Relative(tol) => write!(f, "± {tol} relative"),
+        }
+    }
+}
+
+/// How closely the responses have to follow the challenge text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Strictness {
+    /// A trailing newline of a text is ignored unless the expected text ends with one,
+    /// and numbers may be ten times further off
+    Lenient,
+    /// Texts and numbers are compared as the challenge text says, and a wrong content type is only a warning
+    #[default]
+    Default,
+    /// Like the default, but a wrong content type fails the test, see [`check_content_type`]
+    Strict,
+}
+
+impl Strictness {
+    /// How much of the text `actual` is compared with `expected`
+    pub fn text<'a>(self, actual: &'a str, expected: &str) -> &'a str {
+        match self {
+            Self::Lenient if !expected.ends_with('\n') => actual
+                .strip_suffix('\n')
+                .map_or(actual, |a| a.strip_suffix('\r').unwrap_or(a)),
+            Self::Lenient | Self::Default | Self::Strict => actual,
+        }
+    }
+
+    /// How close a number has to be when the challenge says `tol`
+    pub fn tolerance(self, tol: Tolerance) -> Tolerance {
+        match (self, tol) {
+            (Self::Lenient, Tolerance::Absolute(tol)) => Tolerance::Absolute(tol * 10.0),
+            (Self::Lenient, Tolerance::Relative(tol)) => Tolerance::Relative(tol * 10.0),
+            (Self::Default | Self::Strict, tol) => tol,
+        }
+    }
+}
+
+/// Check that the text `actual` is a number near `expected`
+pub fn check_float_near(actual: &str, expected: f64, tol: Tolerance) -> Result<(), TestError> {
+    let actual = match actual.parse::<f64>() {
+        Ok(num) if tol.allows(num, expected) => return Ok(()),
+        Ok(num) => num.to_string(),
+        Err(_) => format!("{actual:?}"),
+    };
+    Err(TestError::Mismatch {
+        expected: format!("{expected} {tol}"),
+        actual,
+    })
+}
+
+/// Check that the JSON `value` has a number near `expected` at the JSON `pointer`, like `/gallons`.
+/// Integers are numbers too.
+pub fn json_float_near(
+    value: &serde_json::Value,
+    pointer: &str,
+    expected: f64,
+    tol: Tolerance,
+) -> Result<(), TestError> {
+    let actual = match value.pointer(pointer) {
+        Some(found) => match found.as_f64() {
+            Some(num) if tol.allows(num, expected) => return Ok(()),
+            Some(num) => num.to_string(),
+            None => found.to_string(),
+        },
+        None => "nothing".to_owned(),
+    };
+    Err(TestError::Mismatch {
+        expected: format!("{pointer}: {expected} {tol}"),
+        actual: format!("{pointer}: {actual}"),
+    })
+}
+
+/// Texts are shown quoted in a [`TestError::TextMismatch`]
+pub fn check_text(actual: &str, expected: &str) -> Result<(), TestError> {
+    if actual != expected {
+        return Err(TestError::TextMismatch {
+            expected: format!("{expected:?}"),
+            actual: format!("{actual:?}"),
+            detail: text_mismatch_detail(expected, actual),
+        });
+    }
+    Ok(())
+}
+
+/// Which kinds of whitespace differ, like ` (difference is whitespace only: trailing newline)`,
+/// if the texts are the same words with the same whitespace between them otherwise.
+/// Empty if the texts are equal, or differ in more than their whitespace.
+pub fn text_mismatch_detail(expected: &str, actual: &str) -> String {
+    if expected == actual || !expected.split_whitespace().eq(actual.split_whitespace()) {
+        return String::new();
+    }
+    let mut kinds = Vec::new();
+    let crlfs = |s: &str| s.matches("\r\n").count();
+    if crlfs(expected) != crlfs(actual) {
+        kinds.push("CRLF vs LF");
+    }
+    let (expected, actual) = (expected.replace("\r\n", "\n"), actual.replace("\r\n", "\n"));
+    let trailing = |s: &str| s[s.trim_end().len()..].to_owned();
+    let (expected_end, actual_end) = (trailing(&expected), trailing(&actual));
+    if expected_end != actual_end {
+        let newlines = |s: &str| s.chars().all(|c| c == '\n');
+        kinds.push(match newlines(&expected_end) && newlines(&actual_end) {
+            true => "trailing newline",
+            false => "trailing whitespace",
+        });
+    }
+    if expected.trim_end() != actual.trim_end() {
+        kinds.push("extra spaces");
+    }
+    format!(" (difference is whitespace only: {})", kinds.join(", "))
+}
+
+/// The [`TestError::JsonMismatch`] says where the JSON differs, see [`json_differences`]
+pub fn check_json(
+    actual: &serde_json::Value,
+    expected: &serde_json::Value,
+) -> Result<(), TestError> {
+    if actual != expected {
+        return Err(TestError::JsonMismatch {
+            expected: expected.to_string(),
+            actual: actual.to_string(),
+            differences: json_differences(actual, expected),
+        });
+    }
+    Ok(())
+}
+
+/// Like [`check_json`], but the arrays at the `unordered` paths may have their items in any order.
+/// A path has `[*]` for the items of an array, like `$[*].top_gifts`.
+/// The paths of the differences are those of the arrays once they are sorted.
+pub fn check_json_unordered(
+    actual: &serde_json::Value,
+    expected: &serde_json::Value,
+    unordered: &[&str],
+) -> Result<(), TestError> {
+    let (mut actual, mut expected) = (actual.clone(), expected.clone());
+    sort_unordered("$", &mut actual, unordered);
+    sort_unordered("$", &mut expected, unordered);
+    check_json(&actual, &expected)
+}
+
+/// Sort the arrays at the `unordered` paths, so that equal multisets are equal arrays
+fn sort_unordered(path: &str, value: &mut serde_json::Value, unordered: &[&str]) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                sort_unordered(&format!("{path}{}", key_path(key)), value, unordered);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            let item_path = format!("{path}[*]");
+            for item in items.iter_mut() {
+                sort_unordered(&item_path, item, unordered);
+            }
+            if unordered.contains(&path) {
+                items.sort_by_cached_key(serde_json::Value::to_string);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The average latency above which the timing-sensitive challenges can fail, e.g. when a rate limit
+/// bucket refills noticeably during a sequence of requests that expects it to empty
+pub const HIGH_LATENCY: Duration = Duration::from_millis(100);
+
+/// Whether the requests that took `latencies` were slower than [`HIGH_LATENCY`] on average
+pub fn high_latency(latencies: &[Duration]) -> bool {
+    !latencies.is_empty()
+        && latencies.iter().sum::<Duration>() > HIGH_LATENCY * latencies.len() as u32
+}
+
+/// Why a request failed, from the innermost of its errors, which is the most specific one
+pub fn failure_reason(e: &(dyn std::error::Error + 'static)) -> String {
+    let mut reason = e.to_string();
+    let mut source = e.source();
+    while let Some(err) = source {
+        // OS errors are named by their kind, e.g. `connection refused`, without the error code
+        if let Some(io) = err
+            .downcast_ref::<std::io::Error>()
+            .filter(|io| io.raw_os_error().is_some())
+        {
+            return io.kind().to_string();
+        }
+        reason = err.to_string();
+        source = err.source();
+    }
+    reason
+}
+
+/// How many differences [`json_differences`] finds at most
+pub const MAX_JSON_DIFFERENCES: usize = 3;
+
+/// How many characters of a value a difference shows
+const MAX_DIFFERENCE_VALUE_LEN: usize = 40;
+
+/// Where `actual` differs from `expected`, like `at $.pantry["baking powder"]: expected 8622399, got 8622398`.
+/// Arrays only differ at their first differing index.
+pub fn json_differences(actual: &serde_json::Value, expected: &serde_json::Value) -> Vec<String> {
+    let mut differences = Vec::new();
+    diff_json("$".to_owned(), actual, expected, &mut differences);
+    differences
+}
+
+fn diff_json(
+    path: String,
+    actual: &serde_json::Value,
+    expected: &serde_json::Value,
+    differences: &mut Vec<String>,
+) {
+    use serde_json::Value;
+
+    if differences.len() == MAX_JSON_DIFFERENCES || actual == expected {
+        return;
+    }
+    match (actual, expected) {
+        (Value::Object(actual), Value::Object(expected)) => {
+            for (key, value) in expected {
+                let path = format!("{path}{}", key_path(key));
+                match actual.get(key) {
+                    Some(actual) => diff_json(path, actual, value, differences),
+                    None => differences.push(format!(
+                        "at {path}: expected {}, got nothing",
+                        short_json(value)
+                    )),
+                }
+            }
+            for (key, value) in actual {
+                if !expected.contains_key(key) {
+                    differences.push(format!(
+                        "at {path}{}: expected nothing, got {}",
+                        key_path(key),
+                        short_json(value)
+                    ));
+                }
+            }
+            differences.truncate(MAX_JSON_DIFFERENCES);
+        }
+        (Value::Array(actual), Value::Array(expected)) => {
+            match actual.iter().zip(expected).position(|(a, e)| a != e) {
+                Some(i) => diff_json(
+                    format!("{path}[{i}]"),
+                    &actual[i],
+                    &expected[i],
+                    differences,
+                ),
+                None => differences.push(format!(
+                    "at {path}: expected {} items, got {}",
+                    expected.len(),
+                    actual.len()
+                )),
+            }
+        }
+        _ => differences.push(format!(
+            "at {path}: expected {}, got {}",
+            short_json(expected),
+            short_json(actual)
+        )),
+    }
+}
+
+/// `.key`, or `["key"]` when the key is not an identifier
+fn key_path(key: &str) -> String {
+    let mut chars = key.chars();
+    if chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        format!(".{key}")
+    } else {
+        format!("[{}]", serde_json::Value::from(key))
+    }
+}
+
+fn short_json(value: &serde_json::Value) -> String {
+    let json = value.to_string();
+    match json.char_indices().nth(MAX_DIFFERENCE_VALUE_LEN) {
+        Some((i, _)) => format!("{}…", &json[..i]),
+        None => json,
+    }
+}
+
+/// The client, method and headers of the requests of a tester
+#[derive(Debug, Clone)]
+struct RequestOptions {
+    client: reqwest::Client,
+    url: String,
+    method: Method,
+    headers: HeaderMap,
+}
+
+impl RequestOptions {
+    fn new(url: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+            method: Method::POST,
+            headers: HeaderMap::new(),
+        }
+    }
+
+    fn request(&self) -> reqwest::RequestBuilder {
+        self.client
+            .request(self.method.clone(), &self.url)
+            .headers(self.headers.clone())
+    }
+}
+
+/// Sends JSON to an endpoint and checks the JSON it responds with
+#[derive(Debug, Clone)]
+pub struct JSONTester {
+    options: RequestOptions,
+}
+
+impl JSONTester {
+    /// Sends POST requests to `url` with a client of its own
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            options: RequestOptions::new(url.into()),
+        }
+    }
+
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.options.client = client;
+        self
+    }
+
+    pub fn method(mut self, method: Method) -> Self {
+        self.options.method = method;
+        self
+    }
+
+    /// Send this header with every request
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.options.headers.insert(name, value);
+        self
+    }
+
+    /// The request that [`JSONTester::test`] sends, to send it some other way
+    pub fn request(&self, input: &serde_json::Value) -> reqwest::RequestBuilder {
+        self.options.request().json(input)
+    }
+
+    /// Check that a response has the `status`, the `expected_headers` and the `expected` JSON
+    pub async fn check(
+        res: reqwest::Response,
+        status: StatusCode,
+        expected_headers: &[(HeaderName, &str)],
+        expected: &serde_json::Value,
+    ) -> Result<(), TestError> {
+        check_status(res.status(), status)?;
+        check_headers(res.headers(), expected_headers)?;
+        let text = read_text(res).await?;
+        let json = serde_json::from_str(&text).map_err(|_| TestError::Mismatch {
+            expected: expected.to_string(),
+            actual: format!("{text:?}"),
+        })?;
+        check_json(&json, expected)
+    }
+
+    /// Send `input` and check that the response has the `status`, the `expected_headers` and the `expected` JSON
+    pub async fn test(
+        &self,
+        input: &serde_json::Value,
+        status: StatusCode,
+        expected_headers: &[(HeaderName, &str)],
+        expected: &serde_json::Value,
+    ) -> Result<(), TestError> {
+        let res = self.request(input).send().await?;
+        Self::check(res, status, expected_headers, expected).await
+    }
+}
+
+/// Sends text to an endpoint and checks the text it responds with
+#[derive(Debug, Clone)]
+pub struct TextTester {
+    options: RequestOptions,
+}
+
+impl TextTester {
+    /// Sends POST requests to `url` with a client of its own
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            options: RequestOptions::new(url.into()),
+        }
+    }
+
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.options.client = client;
+        self
+    }
+
+    pub fn method(mut self, method: Method) -> Self {
+        self.options.method = method;
+        self
+    }
+
+    /// Send this header with every request
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.options.headers.insert(name, value);
+        self
+    }
+
+    /// The request that [`TextTester::test`] sends, to send it some other way
+    pub fn request(&self, input: &str) -> reqwest::RequestBuilder {
+        self.builder().body(input.to_owned())
+    }
+
+    /// A request with the method and headers of the tester but no body yet,
+    /// e.g. for a body that is streamed instead of held in a string
+    pub fn builder(&self) -> reqwest::RequestBuilder {
+        self.options.request()
+    }
+
+    /// Check that a response has the `status`, the `expected_headers` and the `expected` text
+    pub async fn check(
+        res: reqwest::Response,
+        status: StatusCode,
+        expected_headers: &[(HeaderName, &str)],
+        expected: &str,
+    ) -> Result<(), TestError> {
+        Self::check_as(res, status, expected_headers, expected, Strictness::Default).await
+    }
+
+    /// Like [`TextTester::check`], but the text is compared as `strictness` says
+    pub async fn check_as(
+        res: reqwest::Response,
+        status: StatusCode,
+        expected_headers: &[(HeaderName, &str)],
+        expected: &str,
+        strictness: Strictness,
+    ) -> Result<(), TestError> {
+        check_status(res.status(), status)?;
+        check_headers(res.headers(), expected_headers)?;
+        let text = read_text(res).await?;
+        check_text(strictness.text(&text, expected), expected)
+    }
+
+    /// Send `input` and check that the response has the `status`, the `expected_headers` and the `expected` text
+    pub async fn test(
+        &self,
+        input: &str,
+        status: StatusCode,
+        expected_headers: &[(HeaderName, &str)],
+        expected: &str,
+    ) -> Result<(), TestError> {
+        let res = self.request(input).send().await?;
+        Self::check(res, status, expected_headers, expected).await
+    }
+}
+
+/// Wait for `next` for at most `timeout`, if there is one
+async fn within<T>(
+    timeout: Option<Duration>,
+    next: impl std::future::Future<Output = Result<T, TestError>>,
+) -> Result<T, TestError> {
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, next)
+            .await
+            .map_err(|_| TestError::Request(format!("no message within {timeout:?}")))?,
+        None => next.await,
+    }
+}
+
+/// A message as a failure shows it: text is quoted, and binary data is only counted
+fn describe(msg: &Message) -> String {
+    match msg {
+        Message::Text(text) => format!("{text:?}"),
+        Message::Binary(data) => format!("{} bytes of binary data", data.len()),
+        msg => format!("{msg:?}"),
+    }
+}
+
+/// How long [`WS::close`] waits for the server to complete the close handshake
+pub const CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+type WsError = tokio_tungstenite::tungstenite::Error;
+
+/// A websocket connection that checks the messages it receives
+pub struct WS {
+    w: Pin<Box<dyn Sink<Message, Error = WsError> + Send>>,
+    r: Pin<Box<dyn Stream<Item = Result<Message, WsError>> + Send>>,
+    timeout: Option<Duration>,
+    /// The close frame of the server, once it closed the connection
+    closed: Option<Option<CloseFrame<'static>>>,
+}
+
+impl WS {
+    pub async fn connect(url: &str) -> Result<Self, TestError> {
+        let (stream, _) = tokio_tungstenite::connect_async(url).await?;
+        Ok(Self::from_stream(stream))
+    }
+
+    /// Use a connection that was made some other way, e.g. through a proxy or over a Unix socket
+    pub fn from_stream<S>(stream: WebSocketStream<S>) -> Self
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let (w, r) = stream.split();
+        Self {
+            w: Box::pin(w),
+            r: Box::pin(r),
+            timeout: None,
+            closed: None,
+        }
+    }
+
+    /// How long [`WS::recv`] waits for a message. Without it, it waits as long as it takes.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub async fn send(&mut self, msg: impl Into<String>) -> Result<(), TestError> {
+        Ok(self.w.send(Message::Text(msg.into())).await?)
+    }
+
+    pub async fn send_json(&mut self, msg: &serde_json::Value) -> Result<(), TestError> {
+        self.send(msg.to_string()).await
+    }
+
+    /// The next message, which has to be text and arrive within the [`WS::timeout`]
+    pub async fn recv(&mut self) -> Result<String, TestError> {
+        let timeout = self.timeout;
+        within(timeout, self.next_text()).await
+    }
+
+    /// The next message, which has to be text and arrive within `timeout`
+    pub async fn recv_timeout(&mut self, timeout: Duration) -> Result<String, TestError> {
+        within(Some(timeout), self.next_text()).await
+    }
+
+    /// The next message, which has to be binary and arrive within the [`WS::timeout`]
+    pub async fn recv_binary(&mut self) -> Result<Vec<u8>, TestError> {
+        let timeout = self.timeout;
+        match within(timeout, self.next_data()).await? {
+            Message::Binary(data) => Ok(data),
+            msg => Err(TestError::Request(format!(
+                "expected a binary message, got {}",
+                describe(&msg)
+            ))),
+        }
+    }
+
+    /// Check that nothing arrives for `duration`, not even the end of the connection.
+    /// Pings and pongs don't count.
+    pub async fn expect_silence(&mut self, duration: Duration) -> Result<(), TestError> {
+        let Ok(next) = tokio::time::timeout(duration, self.next_data()).await else {
+            return Ok(());
+        };
+        let actual = match next {
+            Ok(msg) => describe(&msg),
+            Err(TestError::Closed(_)) => "the end of the connection".to_owned(),
+            Err(e) => return Err(e),
+        };
+        Err(TestError::Mismatch {
+            expected: "no message".to_owned(),
+            actual,
+        })
+    }
+
+    async fn next_text(&mut self) -> Result<String, TestError> {
+        match self.next_data().await? {
+            Message::Text(text) => Ok(text),
+            msg => Err(TestError::Request(format!(
+                "expected a text message, got {}",
+                describe(&msg)
+            ))),
+        }
+    }
+
+    /// The next text or binary message, or [`TestError::Closed`] once the server closed the connection.
+    /// tungstenite has already put together the fragments of a message.
+    /// Pings are answered and pongs are skipped, since servers may send them whenever they like.
+    async fn next_data(&mut self) -> Result<Message, TestError> {
+        if let Some(frame) = &self.closed {
+            return Err(TestError::Closed(frame.clone()));
+        }
+        loop {
+            let frame = match self.r.next().await {
+                Some(Ok(msg @ (Message::Text(_) | Message::Binary(_)))) => return Ok(msg),
+                // tungstenite queued the pong, which flushing sends
+                Some(Ok(Message::Ping(_))) => {
+                    self.w.flush().await?;
+                    continue;
+                }
+                Some(Ok(Message::Pong(_) | Message::Frame(_))) => continue,
+                Some(Ok(Message::Close(frame))) => frame,
+                Some(Err(e)) => return Err(e.into()),
+                None => None,
+            };
+            self.closed = Some(frame.clone());
+            return Err(TestError::Closed(frame));
+        }
+    }
+
+    pub async fn recv_str(&mut self, expected: &str) -> Result<(), TestError> {
+        check_text(&self.recv().await?, expected)
+    }
+
+    pub async fn recv_json(&mut self, expected: &serde_json::Value) -> Result<(), TestError> {
+        let text = self.recv().await?;
+        let json = serde_json::from_str(&text).map_err(|_| TestError::Mismatch {
+            expected: expected.to_string(),
+            actual: format!("{text:?}"),
+        })?;
+        check_json(&json, expected)
+    }
+
+    /// Close the connection, and wait for the server to complete the close handshake within [`CLOSE_TIMEOUT`].
+    /// Messages that arrive before the close frame of the server are ignored.
+    /// Returns that close frame, if it had a code.
+    /// Many servers end the connection without one, which is no close code either.
+    pub async fn close(mut self) -> Result<Option<CloseFrame<'static>>, TestError> {
+        use tokio_tungstenite::tungstenite::{error::ProtocolError, Error};
+
+        if let Some(frame) = self.closed {
+            return Ok(frame);
+        }
+        let frame = CloseFrame {
+            code: CloseCode::Normal,
+            reason: "".into(),
+        };
+        self.w.send(Message::Close(Some(frame))).await?;
+        let handshake = async {
+            loop {
+                match self.r.next().await {
+                    Some(Ok(Message::Close(frame))) => return Ok(frame),
+                    Some(Ok(_)) => {}
+                    None
+                    | Some(Err(Error::Protocol(ProtocolError::ResetWithoutClosingHandshake))) => {
+                        return Ok(None)
+                    }
+                    Some(Err(e)) => return Err(e.into()),
+                }
+            }
+        };
+        tokio::time::timeout(CLOSE_TIMEOUT, handshake)
+            .await
+            .map_err(|_| {
+                TestError::Request(format!(
+                    "the server did not complete the close handshake within {CLOSE_TIMEOUT:?}"
+                ))
+            })?
+    }
+
+    /// Close the connection, and check that the server completes the close handshake with `code`
+    pub async fn close_expect(self, code: CloseCode) -> Result<(), TestError> {
+        let actual = self.close().await?.map(|frame| frame.code);
+        if actual != Some(code) {
+            return Err(TestError::Mismatch {
+                expected: format!("close code {code}"),
+                actual: match actual {
+                    Some(actual) => format!("close code {actual}"),
+                    None => "no close code".to_owned(),
+                },
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        extract::ws::{self, WebSocketUpgrade},
+        http::HeaderMap,
+        response::IntoResponse,
+        routing::{any, post, put},
+        Json, Router,
+    };
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    /// A tiny solution to test the testers against
+    async fn fixture() -> String {
+        async fn strength(Json(reindeer): Json<Vec<Value>>) -> Json<Value> {
+            Json(
+                reindeer
+                    .iter()
+                    .map(|r| r["strength"].as_i64().unwrap_or(0))
+                    .sum::<i64>()
+                    .into(),
+            )
+        }
+        async fn shout(headers: HeaderMap, body: String) -> impl IntoResponse {
+            match headers.get("x-elf") {
+                Some(_) => (axum::http::StatusCode::OK, body.to_uppercase()),
+                None => (axum::http::StatusCode::UNAUTHORIZED, String::new()),
+            }
+        }
+        async fn echo(upgrade: WebSocketUpgrade) -> impl IntoResponse {
+            upgrade.on_upgrade(|mut socket| async move {
+                while let Some(Ok(ws::Message::Text(text))) = socket.recv().await {
+                    let _ = socket.send(ws::Message::Text(text)).await;
+                }
+            })
+        }
+        async fn sled() -> impl IntoResponse {
+            (
+                [("content-type", "text/plain"), ("x-sled", "fast")],
+                "wheee",
+            )
+        }
+        async fn endless() -> axum::body::Body {
+            let ho =
+                futures_util::stream::repeat_with(|| Ok::<_, std::io::Error>("ho ".repeat(1000)));
+            axum::body::Body::from_stream(ho)
+        }
+        let app = Router::new()
+            .route("/4/strength", post(strength))
+            .route("/endless", post(endless))
+            .route("/sled", post(sled))
+            .route("/shout", put(shout))
+            .route("/ws", any(echo));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        format!("127.0.0.1:{}", addr.port())
+    }
+
+    /// A websocket server made with tungstenite, which echoes text and closes the connection on `bye`.
+    /// On `frames`, it sends a ping, a pong, a text message in two fragments and binary data,
+    /// and says what the pong to its ping was.
+    async fn echo_server() -> String {
+        use tokio_tungstenite::tungstenite::protocol::frame::{
+            coding::{Data, OpCode},
+            Frame,
+        };
+
+        let fragment = |text: &str, opcode, is_final| {
+            Message::Frame(Frame::message(
+                text.as_bytes().to_vec(),
+                OpCode::Data(opcode),
+                is_final,
+            ))
+        };
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+                    while let Some(Ok(msg)) = ws.next().await {
+                        let _ = match msg {
+                            Message::Text(text) if text == "bye" => {
+                                ws.close(Some(CloseFrame {
+                                    code: CloseCode::Library(4000),
+                                    reason: "bye".into(),
+                                }))
+                                .await
+                            }
+                            Message::Text(text) if text == "frames" => {
+                                for msg in [
+                                    Message::Ping(b"are you there".to_vec()),
+                                    Message::Pong(b"unasked".to_vec()),
+                                    fragment("Hello, ", Data::Text, false),
+                                    fragment("world", Data::Continue, true),
+                                    Message::Binary(vec![1, 2, 3]),
+                                ] {
+                                    ws.send(msg).await.unwrap();
+                                }
+                                Ok(())
+                            }
+                            Message::Text(text) => ws.send(Message::Text(text)).await,
+                            Message::Pong(data) => {
+                                let pong = format!("pong: {}", String::from_utf8_lossy(&data));
+                                ws.send(Message::Text(pong)).await
+                            }
+                            _ => Ok(()),
+                        };
+                    }
+                });
+            }
+        });
+        format!("ws://{addr}")
+    }
+
+    fn client() -> reqwest::Client {
+        reqwest::Client::builder().no_proxy().build().unwrap()
+    }
+
+    #[tokio::test]
+    async fn json_tester_checks_the_response() {
+        let addr = fixture().await;
+        let tester = JSONTester::new(format!("http://{addr}/4/strength")).client(client());
+        let reindeer =
+            json!([{"name": "Dasher", "strength": 5}, {"name": "Dancer", "strength": 6}]);
+        tester
+            .test(&reindeer, StatusCode::OK, &[], &json!(11))
+            .await
+            .unwrap();
+        let e = tester
+            .test(&reindeer, StatusCode::OK, &[], &json!(12))
+            .await
+            .unwrap_err();
+        assert_eq!(e.to_string(), "at $: expected 12, got 11");
+        let e = tester
+            .test(&json!({}), StatusCode::OK, &[], &json!(0))
+            .await
+            .unwrap_err();
+        assert!(matches!(e, TestError::Mismatch { expected, .. } if expected == "200 OK"));
+    }
+
+    #[tokio::test]
+    async fn text_tester_sends_the_method_and_headers() {
+        let addr = fixture().await;
+        let tester = TextTester::new(format!("http://{addr}/shout"))
+            .client(client())
+            .method(Method::PUT);
+        tester
+            .test("ho ho", StatusCode::UNAUTHORIZED, &[], "")
+            .await
+            .unwrap();
+        let tester = tester.header(
+            HeaderName::from_static("x-elf"),
+            HeaderValue::from_static("yes"),
+        );
+        tester
+            .test("ho ho", StatusCode::OK, &[], "HO HO")
+            .await
+            .unwrap();
+        let e = tester
+            .test("ho", StatusCode::OK, &[], "ho")
+            .await
+            .unwrap_err();
+        assert_eq!(e.to_string(), r#"expected "ho", got "HO""#);
+    }
+
+    #[tokio::test]
+    async fn endless_bodies_are_cut_off() {
+        let addr = fixture().await;
+        let url = format!("http://{addr}/endless");
+        let res = client().post(&url).send().await.unwrap();
+        match read_body(res, 10_000).await.unwrap_err() {
+            TestError::TooLarge { limit, prefix } => {
+                assert_eq!(limit, 10_000);
+                assert_eq!(prefix, "ho ".repeat(34)[..100]);
+            }
+            e => panic!("expected TooLarge, got {e:?}"),
+        }
+        let e = TextTester::new(url)
+            .client(client())
+            .test("", StatusCode::OK, &[], "ho")
+            .await
+            .unwrap_err();
+        assert!(e
+            .to_string()
+            .starts_with("response exceeded size limit of 8388608 bytes, starting with \"ho ho"));
+    }
+
+    #[tokio::test]
+    async fn expected_headers_are_checked() {
+        let addr = fixture().await;
+        let tester = TextTester::new(format!("http://{addr}/sled")).client(client());
+        tester
+            .test(
+                "",
+                StatusCode::OK,
+                &[
+                    (header::CONTENT_TYPE, "text/plain"),
+                    (HeaderName::from_static("x-sled"), "fast"),
+                ],
+                "wheee",
+            )
+            .await
+            .unwrap();
+        let e = tester
+            .test(
+                "",
+                StatusCode::OK,
+                &[(HeaderName::from_static("x-sled"), "slow")],
+                "wheee",
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            r#"expected x-sled: "slow", got x-sled: "fast""#
+        );
+        let e = tester
+            .test(
+                "",
+                StatusCode::OK,
+                &[(header::LOCATION, "/north-pole")],
+                "wheee",
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            r#"expected location: "/north-pole", got no location header"#
+        );
+    }
+
+    #[test]
+    fn json_differences_have_a_path() {
+        let expected = json!({
+            "pantry": {"flour": 4, "baking powder": 8622399},
+            "cookies": 3,
+            "gifts": ["toy", "book", {"name": "sled"}],
+        });
+        let actual = json!({
+            "pantry": {"flour": 4, "baking powder": 8622398},
+            "cookies": "3",
+            "gifts": ["toy", "book", {"name": "sleigh"}],
+        });
+        assert_eq!(
+            json_differences(&actual, &expected),
+            [
+                r#"at $.cookies: expected 3, got "3""#,
+                r#"at $.gifts[2].name: expected "sled", got "sleigh""#,
+                r#"at $.pantry["baking powder"]: expected 8622399, got 8622398"#,
+            ]
+        );
+        assert_eq!(
+            json_differences(
+                &json!({"a": [1, 2], "b": 1}),
+                &json!({"a": [1, 2, 3], "c": 1})
+            ),
+            [
+                "at $.a: expected 3 items, got 2",
+                "at $.c: expected 1, got nothing",
+                "at $.b: expected nothing, got 1",
+            ]
+        );
+        let many = |n: i32| Value::Object((0..10).map(|i| (i.to_string(), json!(i * n))).collect());
+        assert_eq!(
+            json_differences(&many(1), &many(2)).len(),
+            MAX_JSON_DIFFERENCES
+        );
+        let e = check_json(&json!([1, "a"]), &json!([1, "b"])).unwrap_err();
+        assert_eq!(e.to_string(), r#"at $[1]: expected "b", got "a""#);
+    }
+
+    #[test]
+    fn unordered_arrays_are_compared_as_multisets() {
+        let expected = json!([
+            {"region": "Europe", "top_gifts": ["Doll", "Toy Train", "Doll"]},
+            {"region": "Asia", "top_gifts": []},
+        ]);
+        let actual = json!([
+            {"region": "Asia", "top_gifts": []},
+            {"region": "Europe", "top_gifts": ["Toy Train", "Doll", "Doll"]},
+        ]);
+        // strict by default
+        assert!(check_json(&actual, &expected).is_err());
+        assert!(check_json_unordered(&actual, &expected, &[]).is_err());
+        assert!(check_json_unordered(&actual, &expected, &["$"]).is_err());
+        check_json_unordered(&actual, &expected, &["$", "$[*].top_gifts"]).unwrap();
+
+        // the items are a multiset, not a set
+        let once = json!([{"region": "Europe", "top_gifts": ["Doll", "Toy Train"]}]);
+        let twice = json!([{"region": "Europe", "top_gifts": ["Doll", "Toy Train", "Doll"]}]);
+        let e = check_json_unordered(&once, &twice, &["$[*].top_gifts"]).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            r#"at $[0].top_gifts[1]: expected "Doll", got "Toy Train""#
+        );
+    }
+
+    #[test]
+    fn content_types_are_compared_without_parameters() {
+        let headers = |value: &'static str| {
+            HeaderMap::from_iter([(
+                reqwest::header::CONTENT_TYPE,
+                HeaderValue::from_static(value),
+            )])
+        };
+        check_content_type(&headers("text/html; charset=utf-8"), "text/html").unwrap();
+        check_content_type(&headers("Application/JSON"), "application/json").unwrap();
+        let e = check_content_type(&headers("text/plain"), "application/json").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            r#"expected content-type: "application/json", got content-type: "text/plain""#
+        );
+        let e = check_content_type(&HeaderMap::new(), "text/html").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            r#"expected content-type: "text/html", got no content-type header"#
+        );
+    }
+
+    #[test]
+    fn lenient_comparisons_ignore_a_trailing_newline_and_widen_tolerances() {
+        let lenient = Strictness::Lenient;
+        let compare = |strictness: Strictness, actual, expected| {
+            check_text(strictness.text(actual, expected), expected)
+        };
+        compare(lenient, "21952\n", "21952").unwrap();
+        compare(lenient, "21952\r\n", "21952").unwrap();
+        assert!(compare(lenient, "21952\n\n", "21952").is_err());
+        assert!(compare(lenient, " 21952", "21952").is_err());
+        // the newline is part of the expected text
+        assert!(compare(lenient, "Milk withdrawn", "Milk withdrawn\n").is_err());
+        compare(lenient, "Milk withdrawn\n", "Milk withdrawn\n").unwrap();
+        let e = compare(Strictness::Default, "21952\n", "21952").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            r#"expected "21952", got "21952\n" (difference is whitespace only: trailing newline)"#
+        );
+        let tol = Tolerance::Absolute(0.001);
+        check_float_near("16.005", 16.0, lenient.tolerance(tol)).unwrap();
+        assert!(check_float_near("16.005", 16.0, Strictness::Default.tolerance(tol)).is_err());
+    }
+
+    #[test]
+    fn whitespace_only_differences_say_which_whitespace() {
+        let detail = |expected, actual| text_mismatch_detail(expected, actual);
+        let only = |kinds| format!(" (difference is whitespace only: {kinds})");
+        assert_eq!(detail("21952", "21952\n"), only("trailing newline"));
+        assert_eq!(detail("21952\n", "21952\r\n"), only("CRLF vs LF"));
+        assert_eq!(detail("a\nb", "a\r\nb"), only("CRLF vs LF"));
+        assert_eq!(
+            detail("a\nb", "a\r\nb\r\n"),
+            only("CRLF vs LF, trailing newline")
+        );
+        assert_eq!(detail("a b", "a  b"), only("extra spaces"));
+        assert_eq!(
+            detail("a b", " a b\t"),
+            only("trailing whitespace, extra spaces")
+        );
+        // not only whitespace
+        assert_eq!(detail("a b", "ab"), "");
+        assert_eq!(detail("a b", "a b"), "");
+        let e = check_text("a\r\nb", "a\nb").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            r#"expected "a\nb", got "a\r\nb" (difference is whitespace only: CRLF vs LF)"#
+        );
+    }
+
+    #[test]
+    fn latency_is_high_above_the_average() {
+        let ms = Duration::from_millis;
+        assert!(!high_latency(&[]));
+        assert!(!high_latency(&[ms(100); 5]));
+        assert!(high_latency(&[ms(20), ms(20), ms(20), ms(20), ms(430)]));
+        assert!(!high_latency(&[ms(20), ms(20), ms(20), ms(20), ms(420)]));
+    }
+
+    #[test]
+    fn compressed_bodies_are_decompressed_within_the_limit() {
+        use std::io::Write;
+
+        let headers = |encoding| {
+            HeaderMap::from_iter([(
+                reqwest::header::CONTENT_ENCODING,
+                HeaderValue::from_static(encoding),
+            )])
+        };
+        assert_eq!(compression(&headers("gzip")), Some("gzip"));
+        assert_eq!(compression(&headers("BR")), Some("br"));
+        assert_eq!(compression(&headers("zstd")), None);
+        assert_eq!(compression(&HeaderMap::new()), None);
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), Default::default());
+        encoder.write_all(&b"ho ".repeat(1000)).unwrap();
+        let gzipped = encoder.finish().unwrap();
+        assert_eq!(
+            decompress("gzip", &gzipped, 3000).unwrap(),
+            b"ho ".repeat(1000)
+        );
+        match decompress("gzip", &gzipped, 2999).unwrap_err() {
+            TestError::TooLarge { limit, prefix } => {
+                assert_eq!(limit, 2999);
+                assert_eq!(prefix, "ho ".repeat(34)[..100]);
+            }
+            e => panic!("expected TooLarge, got {e:?}"),
+        }
+        let e = decompress("br", &gzipped, 3000).unwrap_err();
+        assert!(e
+            .to_string()
+            .starts_with("the br-compressed response could not be decompressed: "));
+    }
+
+    #[test]
+    fn numbers_are_near_within_the_tolerance() {
+        let abs = Tolerance::Absolute(0.001);
+        check_float_near("16.0004", 16.0, abs).unwrap();
+        check_float_near("16", 16.0, abs).unwrap();
+        let e = check_float_near("16.01", 16.0, abs).unwrap_err();
+        assert_eq!(e.to_string(), "expected 16 ± 0.001, got 16.01");
+        let e = check_float_near("sixteen", 16.0, abs).unwrap_err();
+        assert_eq!(e.to_string(), r#"expected 16 ± 0.001, got "sixteen""#);
+        assert!(check_float_near("NaN", 16.0, abs).is_err());
+        assert!(check_float_near("inf", f64::INFINITY, abs).is_err());
+        assert!(check_float_near("-inf", -1e300, Tolerance::Relative(1.0)).is_err());
+
+        let rel = Tolerance::Relative(0.0001);
+        let milk = json!({"gallons": 32525680000.0, "pints": 0, "liters": "7.5"});
+        json_float_near(&milk, "/gallons", 32525687000.0, rel).unwrap();
+        json_float_near(&milk, "/pints", 0.0, rel).unwrap();
+        json_float_near(&milk, "/pints", -0.0, Tolerance::Absolute(0.0)).unwrap();
+        let e = json_float_near(&milk, "/pints", 0.00001, rel).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "expected /pints: 0.00001 ± 0.0001 relative, got /pints: 0"
+        );
+        let e = json_float_near(&milk, "/liters", 7.5, rel).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            r#"expected /liters: 7.5 ± 0.0001 relative, got /liters: "7.5""#
+        );
+        let e = json_float_near(&milk, "/litres", 7.5, rel).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "expected /litres: 7.5 ± 0.0001 relative, got /litres: nothing"
+        );
+    }
+
+    #[tokio::test]
+    async fn websocket_messages_are_checked() {
+        let addr = fixture().await;
+        let mut ws = WS::connect(&format!("ws://{addr}/ws")).await.unwrap();
+        ws.send("ping").await.unwrap();
+        ws.recv_str("ping").await.unwrap();
+        ws.send_json(&json!({"message": "hi"})).await.unwrap();
+        ws.recv_json(&json!({"message": "hi"})).await.unwrap();
+        ws.send("pong").await.unwrap();
+        assert!(ws.recv_json(&json!("pong")).await.is_err());
+        // the fixture ends the connection without completing the close handshake
+        assert_eq!(ws.close().await.unwrap(), None);
+
+        let mut ws = WS::connect(&format!("ws://{addr}/ws"))
+            .await
+            .unwrap()
+            .timeout(Duration::from_millis(50));
+        ws.expect_silence(Duration::from_millis(50)).await.unwrap();
+        let e = ws.recv().await.unwrap_err();
+        assert_eq!(e.to_string(), "request failed: no message within 50ms");
+        ws.send("ping").await.unwrap();
+        let e = ws.expect_silence(Duration::from_secs(5)).await.unwrap_err();
+        assert_eq!(e.to_string(), r#"expected no message, got "ping""#);
+        ws.send("ping").await.unwrap();
+        assert_eq!(
+            ws.recv_timeout(Duration::from_secs(5)).await.unwrap(),
+            "ping"
+        );
+
+        let e = WS::connect("ws://127.0.0.1:1/ws").await.err().unwrap();
+        assert!(matches!(e, TestError::Request(_)));
+    }
+
+    #[tokio::test]
+    async fn close_handshake_is_completed() {
+        let url = echo_server().await;
+        let ws = WS::connect(&url).await.unwrap();
+        let frame = ws.close().await.unwrap().unwrap();
+        assert_eq!(frame.code, CloseCode::Normal);
+        WS::connect(&url)
+            .await
+            .unwrap()
+            .close_expect(CloseCode::Normal)
+            .await
+            .unwrap();
+        let e = WS::connect(&url)
+            .await
+            .unwrap()
+            .close_expect(CloseCode::Away)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "expected close code 1001, got close code 1000"
+        );
+    }
+
+    #[tokio::test]
+    async fn control_frames_and_fragments_are_handled() {
+        let mut ws = WS::connect(&echo_server().await)
+            .await
+            .unwrap()
+            .timeout(Duration::from_secs(5));
+        ws.send("frames").await.unwrap();
+        ws.recv_str("Hello, world").await.unwrap();
+        let e = ws.recv().await.unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "request failed: expected a text message, got 3 bytes of binary data"
+        );
+        ws.recv_str("pong: are you there").await.unwrap();
+
+        ws.send("frames").await.unwrap();
+        let e = ws.recv_binary().await.unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            r#"request failed: expected a binary message, got "Hello, world""#
+        );
+        assert_eq!(ws.recv_binary().await.unwrap(), [1, 2, 3]);
+        // the answer to the pong
+        ws.expect_silence(Duration::from_secs(5)).await.unwrap_err();
+        ws.close_expect(CloseCode::Normal).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn server_can_close_first() {
+        let mut ws = WS::connect(&echo_server().await).await.unwrap();
+        ws.send("ho").await.unwrap();
+        ws.recv_str("ho").await.unwrap();
+        ws.send("bye").await.unwrap();
+        let closed = TestError::Closed(Some(CloseFrame {
+            code: CloseCode::Library(4000),
+            reason: "bye".into(),
+        }));
+        assert_eq!(ws.recv().await.unwrap_err(), closed);
+        assert_eq!(
+            ws.recv_str("ho").await.unwrap_err().to_string(),
+            "the connection was closed with bye (4000)"
+        );
+        assert!(ws.send("ho").await.is_err());
+        ws.close_expect(CloseCode::Library(4000)).await.unwrap();
+
+        let e = WS::connect("ws://127.0.0.1:1/ws").await.err().unwrap();
+        assert!(matches!(e, TestError::Request(_)));
+    }
+}
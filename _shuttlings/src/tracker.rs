@@ -0,0 +1,145 @@
+//! Reporting the outcome of each test of a task while a validator checks it
+
+use std::marker::PhantomData;
+
+use tokio::{
+    sync::mpsc::Sender,
+    time::{Duration, Instant},
+};
+
+use crate::{try_update, LogLevel, SubmissionUpdate, TestResult};
+
+/// What the requests and checks of the current test of a run measured, which a validator keeps
+/// for the run it is in, e.g. in a task-local
+pub trait TestScope {
+    /// Forget what the requests since the last test did, so that it doesn't count towards the next one
+    fn reset();
+
+    /// How long the requests of the current test took, which is zero if it made none
+    fn take_request_time() -> Duration;
+
+    /// What the checks of the current test warned about
+    fn take_warnings() -> Vec<String>;
+}
+
+/// Reports the outcome of each test in a task with [`SubmissionUpdate::TestResult`].
+/// Tests are numbered by their task and their number in it.
+#[derive(Debug)]
+pub struct TestTracker<S: TestScope> {
+    tx: Sender<SubmissionUpdate>,
+    current: Option<((i32, i32), Instant)>,
+    // the scope is only named, so that any tracker can be sent to another thread
+    scope: PhantomData<fn() -> S>,
+}
+
+impl<S: TestScope> TestTracker<S> {
+    pub fn new(tx: Sender<SubmissionUpdate>) -> Self {
+        Self {
+            tx,
+            current: None,
+            scope: PhantomData,
+        }
+    }
+
+    /// Start the next test, which means the previous one passed
+    pub async fn start(&mut self, test: (i32, i32)) -> (i32, i32) {
+        self.pass().await;
+        // requests made between tests don't count towards the next one
+        S::reset();
+        self.current = Some((test, Instant::now()));
+        test
+    }
+
+    /// The current test passed
+    pub async fn pass(&mut self) {
+        if let Some((test, started)) = self.current.take() {
+            self.finish(test, started, None).await;
+        }
+    }
+
+    /// The current test failed because of `detail`. `test` is only used if no test was started.
+    pub async fn fail(&mut self, test: (i32, i32), detail: String) {
+        let (test, started) = self.current.take().unwrap_or((test, Instant::now()));
+        self.finish(test, started, Some(detail)).await;
+    }
+
+    /// Send the result of a test, which passed unless there is a `failure` detail
+    async fn finish(&self, (task, test): (i32, i32), started: Instant, failure: Option<String>) {
+        // the time of its requests, unless it had none like a websocket test
+        let duration = Some(S::take_request_time())
+            .filter(|d| !d.is_zero())
+            .unwrap_or_else(|| started.elapsed());
+        let result = TestResult {
+            task,
+            test,
+            passed: failure.is_none(),
+            duration_ms: duration.as_millis() as u64,
+            detail: failure,
+        };
+        // a closed channel stops the task at its next update
+        let _ = try_update(&self.tx, result).await;
+        for warning in S::take_warnings() {
+            let line =
+                format!("Warning: Task {task}: test #{test} {warning} (a failure with --strict)");
+            let _ = try_update(&self.tx, (LogLevel::Warn, line)).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A run whose tests each take 5 ms of requests and warn once
+    struct Slow;
+
+    impl TestScope for Slow {
+        fn reset() {}
+
+        fn take_request_time() -> Duration {
+            Duration::from_millis(5)
+        }
+
+        fn take_warnings() -> Vec<String> {
+            vec!["was redirected".to_owned()]
+        }
+    }
+
+    #[tokio::test]
+    async fn tests_pass_when_the_next_one_starts() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(32);
+        let mut tests = TestTracker::<Slow>::new(tx);
+        tests.start((1, 1)).await;
+        tests.start((1, 2)).await;
+        tests.fail((1, 3), "expected 4".to_owned()).await;
+        tests.pass().await;
+        drop(tests);
+        let mut updates = Vec::new();
+        while let Some(update) = rx.recv().await {
+            updates.push(update);
+        }
+        let result = |test, detail: Option<&str>| {
+            SubmissionUpdate::from(TestResult {
+                task: 1,
+                test,
+                passed: detail.is_none(),
+                duration_ms: 5,
+                detail: detail.map(str::to_owned),
+            })
+        };
+        let warning = |test| {
+            let line =
+                format!("Warning: Task 1: test #{test} was redirected (a failure with --strict)");
+            SubmissionUpdate::from((LogLevel::Warn, line))
+        };
+        assert_eq!(
+            updates,
+            [
+                result(1, None),
+                warning(1),
+                result(2, Some("expected 4")),
+                warning(2)
+            ]
+        );
+    }
+}
@@ -0,0 +1,204 @@
+//! What sends the requests of a validation run: the network through a [`reqwest::Client`],
+//! a [`Replay`] of a recording, or a [`MockTransport`] that tests a validator without a server
+
+use std::{collections::HashMap, future::Future, pin::Pin, sync::Mutex};
+
+use reqwest::{Method, StatusCode};
+
+use crate::{har::Replay, testing::TestError};
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Sends a request and receives its response
+pub trait Transport: std::fmt::Debug + Send + Sync {
+    fn execute(
+        &self,
+        request: reqwest::Request,
+    ) -> BoxFuture<'_, Result<reqwest::Response, TransportError>>;
+}
+
+/// Why a [`Transport`] has no response to a request
+#[derive(Debug)]
+pub enum TransportError {
+    /// The request could not be sent, or the response could not be received
+    Http(reqwest::Error),
+    /// The transport doesn't know the request, e.g. one that is not in a replayed recording
+    Unknown(String),
+}
+
+impl std::fmt::Display for TransportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Http(e) => write!(f, "{e}"),
+            Self::Unknown(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for TransportError {}
+
+impl From<reqwest::Error> for TransportError {
+    fn from(e: reqwest::Error) -> Self {
+        Self::Http(e)
+    }
+}
+
+impl From<TransportError> for TestError {
+    fn from(e: TransportError) -> Self {
+        Self::Request(e.to_string())
+    }
+}
+
+impl Transport for reqwest::Client {
+    fn execute(
+        &self,
+        request: reqwest::Request,
+    ) -> BoxFuture<'_, Result<reqwest::Response, TransportError>> {
+        let res = reqwest::Client::execute(self, request);
+        Box::pin(async move { Ok(res.await?) })
+    }
+}
+
+impl Transport for Replay {
+    fn execute(
+        &self,
+        request: reqwest::Request,
+    ) -> BoxFuture<'_, Result<reqwest::Response, TransportError>> {
+        let res = self.respond(&request).map_err(TransportError::Unknown);
+        Box::pin(async move { res })
+    }
+}
+
+type Handler = Box<dyn Fn(&reqwest::Request) -> http::Response<Vec<u8>> + Send + Sync>;
+
+/// A transport that answers each request with what the handler of its method and path returns.
+/// The query of the URL is not part of the path, so the handler can look at it.
+///
+/// ```
+/// use shuttlings::transport::{response, MockTransport};
+/// use shuttlings::testing::{Method, StatusCode};
+///
+/// let mock = MockTransport::new()
+///     .canned(Method::GET, "/", StatusCode::OK, "Hello, world!")
+///     .route(Method::POST, "/4/strength", |req| {
+///         let body = req.body().and_then(|b| b.as_bytes()).unwrap_or_default();
+///         response(StatusCode::OK, body.len().to_string())
+///     });
+/// ```
+#[derive(Default)]
+pub struct MockTransport {
+    routes: HashMap<(Method, String), Handler>,
+    requests: Mutex<Vec<String>>,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer the `method` requests to `path` with what `handler` returns for each of them
+    pub fn route(
+        mut self,
+        method: Method,
+        path: &str,
+        handler: impl Fn(&reqwest::Request) -> http::Response<Vec<u8>> + Send + Sync + 'static,
+    ) -> Self {
+        self.routes
+            .insert((method, path.to_owned()), Box::new(handler));
+        self
+    }
+
+    /// Answer the `method` requests to `path` with `status` and `body`, whatever they send
+    pub fn canned(
+        self,
+        method: Method,
+        path: &str,
+        status: StatusCode,
+        body: impl Into<Vec<u8>>,
+    ) -> Self {
+        let body = body.into();
+        self.route(method, path, move |_| response(status, body.clone()))
+    }
+
+    /// The requests that were sent so far, as their method and path, e.g. `GET /1/4/8`
+    pub fn requests(&self) -> Vec<String> {
+        self.requests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+impl std::fmt::Debug for MockTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MockTransport")
+            .field("routes", &self.routes.keys().collect::<Vec<_>>())
+            .field("requests", &self.requests)
+            .finish()
+    }
+}
+
+impl Transport for MockTransport {
+    fn execute(
+        &self,
+        request: reqwest::Request,
+    ) -> BoxFuture<'_, Result<reqwest::Response, TransportError>> {
+        let (method, path) = (request.method().clone(), request.url().path().to_owned());
+        self.requests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(format!("{method} {path}"));
+        let res = match self.routes.get(&(method, path)) {
+            Some(handler) => Ok(handler(&request).into()),
+            None => Err(TransportError::Unknown(format!(
+                "no mock response for {} {}",
+                request.method(),
+                request.url().path()
+            ))),
+        };
+        Box::pin(async move { res })
+    }
+}
+
+/// A response with `status` and `body`, for a handler of a [`MockTransport`]
+pub fn response(status: StatusCode, body: impl Into<Vec<u8>>) -> http::Response<Vec<u8>> {
+    let mut res = http::Response::new(body.into());
+    *res.status_mut() = status;
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn mocks_answer_the_routes_they_have() {
+        let mock = MockTransport::new()
+            .canned(Method::GET, "/", StatusCode::OK, "Hello, world!")
+            .route(Method::POST, "/4/strength", |req| {
+                let body = req.body().and_then(|b| b.as_bytes()).unwrap_or_default();
+                response(StatusCode::OK, body.len().to_string())
+            });
+        let client = reqwest::Client::new();
+
+        let req = client.get("http://mock/?x=1").build().unwrap();
+        let res = mock.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.text().await.unwrap(), "Hello, world!");
+        let req = client
+            .post("http://mock/4/strength")
+            .body("[1, 2]")
+            .build()
+            .unwrap();
+        let res = mock.execute(req).await.unwrap();
+        assert_eq!(res.text().await.unwrap(), "6");
+        let req = client.get("http://mock/4/strength").build().unwrap();
+        let e = mock.execute(req).await.unwrap_err();
+        assert_eq!(e.to_string(), "no mock response for GET /4/strength");
+
+        assert_eq!(
+            mock.requests(),
+            ["GET /", "POST /4/strength", "GET /4/strength"]
+        );
+    }
+}
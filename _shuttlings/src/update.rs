@@ -0,0 +1,115 @@
+//! Checking crates.io for a newer version of a validator, so that people don't validate with tests
+//! that were fixed since. The check is a courtesy, so any failure of it is silent.
+
+use serde::Deserialize;
+use tokio::time::{timeout, Duration};
+
+/// How long the check may take before it is given up on
+pub const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+/// The sparse index of crates.io, see <https://doc.rust-lang.org/cargo/reference/registry-index.html>
+const INDEX_URL: &str = "https://index.crates.io";
+
+/// The newest version of crate `name` on crates.io, if it is newer than `current`.
+/// `None` if it isn't, or if the index could not be read within [`CHECK_TIMEOUT`].
+pub async fn newer_version(name: &str, current: &str) -> Option<String> {
+    newer_version_in(INDEX_URL, name, current).await
+}
+
+async fn newer_version_in(index: &str, name: &str, current: &str) -> Option<String> {
+    let client = reqwest::Client::builder()
+        .user_agent(format!("{name}/{current}"))
+        .build()
+        .ok()?;
+    let url = format!("{index}/{}", index_path(name));
+    let read = async {
+        let res = client.get(url).send().await.ok()?.error_for_status().ok()?;
+        res.text().await.ok()
+    };
+    let index_file = timeout(CHECK_TIMEOUT, read).await.ok()??;
+    let latest = latest_version(&index_file)?;
+    (release(&latest)? > release(current)?).then_some(latest)
+}
+
+/// The path of the index file of crate `name`, e.g. `cc/h2/cch24-validator`
+fn index_path(name: &str) -> String {
+    let name = name.to_lowercase();
+    match name.len() {
+        1 | 2 => format!("{}/{name}", name.len()),
+        3 => format!("3/{}/{name}", &name[..1]),
+        _ => format!("{}/{}/{name}", &name[..2], &name[2..4]),
+    }
+}
+
+/// The newest version in an index file, which has a line of JSON per published version.
+/// Yanked versions and pre-releases are left out.
+fn latest_version(index_file: &str) -> Option<String> {
+    #[derive(Deserialize)]
+    struct Entry {
+        vers: String,
+        #[serde(default)]
+        yanked: bool,
+    }
+
+    index_file
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Entry>(line).ok())
+        .filter(|e| !e.yanked)
+        .filter_map(|e| Some((release(&e.vers)?, e.vers)))
+        .max()
+        .map(|(_, vers)| vers)
+}
+
+/// The major, minor and patch numbers of a version without its build metadata, or `None` for a pre-release
+fn release(version: &str) -> Option<(u64, u64, u64)> {
+    let version = version.split('+').next()?;
+    let mut numbers = version.split('.').map(|n| n.parse().ok());
+    let release = (numbers.next()??, numbers.next()??, numbers.next()??);
+    numbers.next().is_none().then_some(release)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{routing::get, Router};
+
+    use super::*;
+
+    const INDEX_FILE: &str = r#"{"name":"cch24-validator","vers":"23.0.1","yanked":false}
+{"name":"cch24-validator","vers":"23.1.0","yanked":false}
+{"name":"cch24-validator","vers":"23.2.0","yanked":true}
+{"name":"cch24-validator","vers":"24.0.0-rc.1","yanked":false}
+"#;
+
+    #[test]
+    fn index_paths_depend_on_the_length_of_the_name() {
+        assert_eq!(index_path("cch24-validator"), "cc/h2/cch24-validator");
+        assert_eq!(index_path("Axum"), "ax/um/axum");
+        assert_eq!(index_path("url"), "3/u/url");
+        assert_eq!(index_path("io"), "2/io");
+    }
+
+    #[test]
+    fn the_latest_version_is_released_and_not_yanked() {
+        assert_eq!(latest_version(INDEX_FILE).as_deref(), Some("23.1.0"));
+        assert_eq!(latest_version(""), None);
+        assert_eq!(release("23.0.10+build.5"), Some((23, 0, 10)));
+        assert_eq!(release("23.0"), None);
+    }
+
+    #[tokio::test]
+    async fn only_newer_versions_are_announced() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let index = format!("http://{}", listener.local_addr().unwrap());
+        let app = Router::new().route("/cc/h2/cch24-validator", get(|| async { INDEX_FILE }));
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let newer = |current| newer_version_in(&index, "cch24-validator", current);
+        assert_eq!(newer("23.0.1").await.as_deref(), Some("23.1.0"));
+        assert_eq!(newer("23.1.0").await, None);
+        assert_eq!(newer("23.1.1").await, None);
+        // a crate that isn't in the index is not an error
+        assert_eq!(
+            newer_version_in(&index, "cch23-validator", "22.0.5").await,
+            None
+        );
+    }
+}
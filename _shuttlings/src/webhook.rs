@@ -0,0 +1,64 @@
+//! Sending the summary of a run to a webhook when it finishes, e.g. one of a chat
+
+use tokio::time::{sleep, Duration};
+
+use crate::RunSummary;
+
+/// Times a webhook is called before giving up, waiting twice as long after each failure
+const WEBHOOK_ATTEMPTS: u32 = 3;
+const WEBHOOK_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// POST the summary of a run to a webhook, retrying a few times if it fails
+pub async fn send_webhook(url: &str, summary: &RunSummary) -> Result<(), String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut delay = WEBHOOK_RETRY_DELAY;
+    let mut attempt = 1;
+    loop {
+        let error = match client.post(url).json(summary).send().await {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) => format!("the webhook responded with {}", response.status()),
+            Err(e) => e.to_string(),
+        };
+        if attempt == WEBHOOK_ATTEMPTS {
+            return Err(error);
+        }
+        sleep(delay).await;
+        delay *= 2;
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn webhook_is_retried_until_it_succeeds() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // fails the first request, and takes the second
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let mut requests = Vec::new();
+            for status in ["500 Internal Server Error", "200 OK"] {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = vec![0; 4096];
+                let n = stream.read(&mut request).await.unwrap();
+                requests.push(String::from_utf8_lossy(&request[..n]).into_owned());
+                let response =
+                    format!("HTTP/1.1 {status}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n");
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+            requests
+        });
+        let summary = RunSummary::new(Vec::new(), Duration::ZERO);
+        send_webhook(&url, &summary).await.unwrap();
+        let requests = server.await.unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[1].starts_with("POST /hook"));
+    }
+}
@@ -0,0 +1,343 @@
+//! The files that some challenges send or expect. They are read from an assets directory
+//! when it has them, and otherwise come from the copies of the `embedded-assets` feature.
+//! The ones that are sent are streamed from their files, see [`Upload`].
+
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+};
+
+use reqwest::{header::CONTENT_LENGTH, multipart::Part};
+use sha2::{Digest, Sha256};
+use shuttlings::{try_update, SubmissionUpdate};
+use tokio::{fs::File, io::AsyncReadExt, sync::mpsc::Sender};
+use tokio_util::io::ReaderStream;
+use tracing::warn;
+
+use crate::{TaskTest, TestFailure, RUN};
+
+/// The directory that is looked in when no other one is given, next to the executable
+pub(crate) const DEFAULT_ASSETS_DIR: &str = "assets";
+
+/// A file of the `assets` directory of this crate
+#[derive(Debug)]
+pub(crate) struct Asset {
+    pub(crate) name: &'static str,
+    /// SHA-256 of the contents in hex, which a file in the assets directory must have
+    sha256: &'static str,
+    #[cfg(feature = "embedded-assets")]
+    embedded: &'static [u8],
+}
+
+macro_rules! asset {
+    ($name:literal, $sha256:literal) => {
+        Asset {
+            name: $name,
+            sha256: $sha256,
+            #[cfg(feature = "embedded-assets")]
+            embedded: include_bytes!(concat!("../assets/", $name)),
+        }
+    };
+}
+
+pub(crate) const DECORATION: Asset = asset!(
+    "decoration.png",
+    "fae3df6641b2917fa628f77ac57a65cd9bf2014338bb64bc2a944a86969309e6"
+);
+pub(crate) const DECORATION2: Asset = asset!(
+    "decoration2.png",
+    "b58dd0e1edda847b62645fdf10eaa430cfa4a902b88512b9a1408644a6da408f"
+);
+pub(crate) const DECORATION3: Asset = asset!(
+    "decoration3.png",
+    "4dc82e4a67e6e14ba610db7b712517c0b066d133a9b8d9af8ef64dc045d15581"
+);
+pub(crate) const DECORATION4: Asset = asset!(
+    "decoration4.png",
+    "9183afa7e9638a5106fa2d480caa0125dd1102f6b3c576eeb25c7d1f0b6ae336"
+);
+pub(crate) const NORTHPOLE: Asset = asset!(
+    "northpole20231220.tar",
+    "30a0f9be808e91111307408caa1d40096a68be0508398388d081ec939c4be499"
+);
+pub(crate) const COOKIEJAR: Asset = asset!(
+    "cookiejar.tar",
+    "44946171a26194a1c82e1da0ea23e70d70301167cf71cc80d76f782a1a3138a9"
+);
+pub(crate) const LOTTERY: Asset = asset!(
+    "lottery.tar",
+    "ec9ad0ae76dc6e66753b1efd99891de5b626d73c546871d3c76890114e9e5d9d"
+);
+pub(crate) const NUMBERS: Asset = asset!(
+    "numbers.txt",
+    "e74bc3391d407d981d46ab7f1226b23392e74829b52fd5f5213d1a5063ee3272"
+);
+
+/// The assets directory of the current run, see [`crate::ValidationConfig::assets_dir`]
+fn dir() -> Option<PathBuf> {
+    RUN.try_with(|r| r.assets_dir.clone())
+        .ok()
+        .flatten()
+        .or_else(default_dir)
+}
+
+/// [`DEFAULT_ASSETS_DIR`] next to the executable
+fn default_dir() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    Some(exe.parent()?.join(DEFAULT_ASSETS_DIR))
+}
+
+/// Where the contents of an asset come from
+#[derive(Debug)]
+enum Source {
+    /// A file that has the expected checksum
+    File {
+        path: PathBuf,
+        len: u64,
+    },
+    Embedded(&'static [u8]),
+}
+
+/// The body of a request that sends an asset, which is streamed if it comes from a file
+#[derive(Debug)]
+pub(crate) struct Upload {
+    body: reqwest::Body,
+    len: u64,
+}
+
+impl Upload {
+    /// A part of a multipart form with the asset, see [`Upload`]
+    pub(crate) fn part(self) -> Part {
+        Part::stream_with_length(self.body, self.len)
+    }
+}
+
+pub(crate) trait WithUpload {
+    /// Send the asset as the body. Its length is sent too, so that a streamed body isn't chunked.
+    fn upload(self, upload: Upload) -> Self;
+}
+
+impl WithUpload for reqwest::RequestBuilder {
+    fn upload(self, upload: Upload) -> Self {
+        self.header(CONTENT_LENGTH, upload.len).body(upload.body)
+    }
+}
+
+impl Asset {
+    /// The contents of the asset, or a failure of `test` that says why there are none
+    pub(crate) async fn load(
+        &self,
+        test: TaskTest,
+        tx: &Sender<SubmissionUpdate>,
+    ) -> Result<Cow<'static, [u8]>, TestFailure> {
+        or_fail(self.load_from(dir().as_deref()).await, test, tx).await
+    }
+
+    async fn load_from(&self, dir: Option<&Path>) -> Result<Cow<'static, [u8]>, String> {
+        match self.source(dir).await? {
+            Source::File { path, .. } => tokio::fs::read(&path)
+                .await
+                .map(Cow::Owned)
+                .map_err(|e| format!("Failed to read {}: {e}", path.display())),
+            Source::Embedded(contents) => Ok(Cow::Borrowed(contents)),
+        }
+    }
+
+    /// A body with the asset, or a failure of `test` that says why there is none.
+    /// Bodies streamed from a file can't be sent again, so their requests are not retried.
+    pub(crate) async fn upload(
+        &self,
+        test: TaskTest,
+        tx: &Sender<SubmissionUpdate>,
+    ) -> Result<Upload, TestFailure> {
+        or_fail(self.upload_from(dir().as_deref()).await, test, tx).await
+    }
+
+    async fn upload_from(&self, dir: Option<&Path>) -> Result<Upload, String> {
+        match self.source(dir).await? {
+            Source::File { path, len } => File::open(&path)
+                .await
+                .map(|file| Upload {
+                    body: reqwest::Body::wrap_stream(ReaderStream::new(file)),
+                    len,
+                })
+                .map_err(|e| format!("Failed to read {}: {e}", path.display())),
+            Source::Embedded(contents) => Ok(Upload {
+                body: contents.into(),
+                len: contents.len() as u64,
+            }),
+        }
+    }
+
+    /// The file in `dir` if it has the expected checksum, or else the embedded copy
+    async fn source(&self, dir: Option<&Path>) -> Result<Source, String> {
+        if let Some(path) = dir.map(|dir| dir.join(self.name)) {
+            match checksum(&path).await {
+                Ok((sha256, len)) if sha256 == self.sha256 => {
+                    return Ok(Source::File { path, len })
+                }
+                Ok(_) => warn!(path = %path.display(), "Ignoring asset with a wrong checksum"),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => (),
+                Err(e) => warn!(path = %path.display(), error = %e, "Failed to read asset"),
+            }
+        }
+        self.embedded()
+            .map(Source::Embedded)
+            .ok_or_else(|| match dir {
+                Some(dir) => format!(
+                    "{} has no valid copy of {}, and this validator has no embedded one",
+                    dir.display(),
+                    self.name
+                ),
+                None => format!("{} is not embedded in this validator", self.name),
+            })
+    }
+
+    #[cfg(feature = "embedded-assets")]
+    fn embedded(&self) -> Option<&'static [u8]> {
+        Some(self.embedded)
+    }
+
+    #[cfg(not(feature = "embedded-assets"))]
+    fn embedded(&self) -> Option<&'static [u8]> {
+        None
+    }
+}
+
+/// The value of `result`, or a failure of `test` that says what went wrong
+async fn or_fail<T>(
+    result: Result<T, String>,
+    test: TaskTest,
+    tx: &Sender<SubmissionUpdate>,
+) -> Result<T, TestFailure> {
+    match result {
+        Ok(t) => Ok(t),
+        Err(e) => {
+            try_update(tx, format!("Error: {e}")).await?;
+            Err(test.into())
+        }
+    }
+}
+
+/// The SHA-256 in hex and the length of the file at `path`, which is read in chunks
+async fn checksum(path: &Path) -> std::io::Result<(String, u64)> {
+    let mut file = File::open(path).await?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0; 64 * 1024];
+    let mut len = 0;
+    loop {
+        let n = file.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        len += n as u64;
+    }
+    let sha256 = hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    Ok((sha256, len))
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{io::AsyncWriteExt, net::TcpListener};
+
+    use super::*;
+
+    const ALL: &[Asset] = &[
+        DECORATION,
+        DECORATION2,
+        DECORATION3,
+        DECORATION4,
+        NORTHPOLE,
+        COOKIEJAR,
+        LOTTERY,
+        NUMBERS,
+    ];
+
+    fn crate_assets() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("assets")
+    }
+
+    #[tokio::test]
+    async fn checksums_match_the_assets() {
+        for asset in ALL {
+            let (sha256, _) = checksum(&crate_assets().join(asset.name)).await.unwrap();
+            assert_eq!(sha256, asset.sha256, "{} has a new checksum", asset.name);
+        }
+    }
+
+    #[tokio::test]
+    async fn assets_come_from_the_directory_unless_stale() {
+        let dir = std::env::temp_dir().join(format!("cch23-assets-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::copy(crate_assets().join(NUMBERS.name), dir.join(NUMBERS.name)).unwrap();
+        std::fs::write(dir.join(LOTTERY.name), "stale").unwrap();
+
+        let numbers = NUMBERS.load_from(Some(&dir)).await.unwrap();
+        assert!(matches!(numbers, Cow::Owned(_)));
+        let lottery = LOTTERY.load_from(Some(&dir)).await;
+        let cookiejar = COOKIEJAR.load_from(Some(&dir)).await;
+        std::fs::remove_dir_all(&dir).unwrap();
+        if cfg!(feature = "embedded-assets") {
+            assert!(matches!(lottery, Ok(Cow::Borrowed(_))));
+            assert!(matches!(cookiejar, Ok(Cow::Borrowed(_))));
+        } else {
+            assert!(lottery
+                .unwrap_err()
+                .contains("no valid copy of lottery.tar"));
+            assert!(cookiejar.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn uploads_are_streamed_with_their_length() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 8192];
+            let head_end = loop {
+                let n = stream.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                if let Some(i) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                    break i + 4;
+                }
+            };
+            let head = String::from_utf8_lossy(&request[..head_end]).to_lowercase();
+            let len: usize = head
+                .lines()
+                .find_map(|l| l.strip_prefix("content-length: "))
+                .unwrap()
+                .parse()
+                .unwrap();
+            while request.len() < head_end + len {
+                let n = stream.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                .await
+                .unwrap();
+            (head, request.split_off(head_end))
+        });
+
+        let upload = NORTHPOLE.upload_from(Some(&crate_assets())).await.unwrap();
+        reqwest::Client::new()
+            .post(url)
+            .upload(upload)
+            .send()
+            .await
+            .unwrap();
+        let (head, body) = server.await.unwrap();
+        assert!(!head.contains("transfer-encoding"));
+        assert_eq!(
+            body,
+            std::fs::read(crate_assets().join(NORTHPOLE.name)).unwrap()
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use shuttlings::{try_update, SubmissionUpdate};
+use tokio::sync::mpsc::Sender;
+
+use crate::{
+    helpers::{expect_text, shared_client, SendWithRetry},
+    BoxFuture, Challenge, ChallengeInfo, TaskTest, TestInfo, TestTracker, ValidateResult,
+};
+
+pub(crate) const TESTS: &[TestInfo] = &[
+    TestInfo {
+        task: 1,
+        test: 1,
+        description: "GET /1/2/3 should return 1",
+    },
+    TestInfo {
+        task: 1,
+        test: 2,
+        description: "GET /1/12/16 should return 21952",
+    },
+    TestInfo {
+        task: 2,
+        test: 1,
+        description: "GET /1/3/5/7/9 should return 512",
+    },
+    TestInfo {
+        task: 2,
+        test: 2,
+        description: "GET /1/0/0/0 should return 0",
+    },
+    TestInfo {
+        task: 2,
+        test: 3,
+        description: "GET /1/-3/1 should return -64",
+    },
+    TestInfo {
+        task: 2,
+        test: 4,
+        description: "GET /1/3/5/7/9/2/13/12/16/18 should return 729",
+    },
+];
+
+pub(crate) const CHALLENGE: ChallengeInfo = ChallengeInfo {
+    challenge: 1,
+    description: "Cubing the XOR of packet IDs",
+    core: 1,
+    bonus: &[0, 100],
+    scored: true,
+    exclusive: false,
+    time_scaled: false,
+    tests: TESTS,
+    timeout: 20,
+    validator: &Day,
+};
+
+#[derive(Debug)]
+struct Day;
+
+impl Challenge for Day {
+    fn validate<'a>(
+        &'a self,
+        url: &'a str,
+        task: i32,
+        tx: Sender<SubmissionUpdate>,
+        tests: &'a mut TestTracker,
+    ) -> BoxFuture<'a, Option<ValidateResult>> {
+        Box::pin(async move {
+            Some(match task {
+                1 => task1(url, tx, tests).await,
+                2 => task2(url, tx, tests).await,
+                _ => return None,
+            })
+        })
+    }
+}
+
+async fn task1(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    // TASK 1: basic formula
+    test = tests.start((1, 1)).await;
+    let url = &format!("{}/1/2/3", base_url);
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_text(test, &text, "1")?;
+    test = tests.start((1, 2)).await;
+    let url = &format!("{}/1/12/16", base_url);
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_text(test, &text, "21952")?;
+    // TASK 1 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(1)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task2(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    // TASK 2: multiple and zero and negative numbers
+    test = tests.start((2, 1)).await;
+    let url = &format!("{}/1/3/5/7/9", base_url);
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_text(test, &text, "512")?;
+    test = tests.start((2, 2)).await;
+    let url = &format!("{}/1/0/0/0", base_url);
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_text(test, &text, "0")?;
+    test = tests.start((2, 3)).await;
+    let url = &format!("{}/1/-3/1", base_url);
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_text(test, &text, "-64")?;
+    test = tests.start((2, 4)).await;
+    let url = &format!("{}/1/3/5/7/9/2/13/12/16/18", base_url);
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_text(test, &text, "729")?;
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(2)).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use reqwest::{Method, StatusCode};
+    use shuttlings::{testing::Strictness, transport::MockTransport};
+    use uuid::Uuid;
+
+    use crate::{run_with_config, tests::run_mocked, RunOutcome, ValidationConfig};
+
+    const PATHS: &[&str] = &[
+        "/1/2/3",
+        "/1/12/16",
+        "/1/3/5/7/9",
+        "/1/0/0/0",
+        "/1/-3/1",
+        "/1/3/5/7/9/2/13/12/16/18",
+    ];
+
+    /// A solution that answers the paths of the validator, with `sled_id` for the numbers in each
+    fn solution(sled_id: fn(&[i64]) -> i64) -> MockTransport {
+        solution_with_suffix(sled_id, "")
+    }
+
+    /// Like [`solution`], with `suffix` after each number
+    fn solution_with_suffix(sled_id: fn(&[i64]) -> i64, suffix: &str) -> MockTransport {
+        PATHS.iter().fold(MockTransport::new(), |mock, path| {
+            let nums: Vec<i64> = path[3..].split('/').map(|n| n.parse().unwrap()).collect();
+            mock.canned(
+                Method::GET,
+                path,
+                StatusCode::OK,
+                format!("{}{suffix}", sled_id(&nums)),
+            )
+        })
+    }
+
+    #[tokio::test]
+    async fn the_cube_of_the_xor_passes() {
+        let xor_cubed = |nums: &[i64]| nums.iter().fold(0, |a, n| a ^ n).pow(3);
+        assert_eq!(
+            run_mocked(1, 1, solution(xor_cubed)).await,
+            RunOutcome::Completed
+        );
+        assert_eq!(
+            run_mocked(1, 2, solution(xor_cubed)).await,
+            RunOutcome::Completed
+        );
+    }
+
+    #[tokio::test]
+    async fn only_two_numbers_fail_task2() {
+        let two = |nums: &[i64]| match nums {
+            [a, b] => (a ^ b).pow(3),
+            _ => 0,
+        };
+        assert_eq!(run_mocked(1, 1, solution(two)).await, RunOutcome::Completed);
+        assert_eq!(
+            run_mocked(1, 2, solution(two)).await,
+            RunOutcome::Failed { task: 2, test: 1 }
+        );
+    }
+
+    #[tokio::test]
+    async fn a_trailing_newline_only_passes_lenient_runs() {
+        let xor_cubed = |nums: &[i64]| nums.iter().fold(0, |a, n| a ^ n).pow(3);
+        assert_eq!(
+            run_mocked(1, 1, solution_with_suffix(xor_cubed, "\n")).await,
+            RunOutcome::Failed { task: 1, test: 1 }
+        );
+        let config = ValidationConfig {
+            task: Some(1),
+            transport: Some(Arc::new(solution_with_suffix(xor_cubed, "\n"))),
+            strictness: Strictness::Lenient,
+            ..Default::default()
+        };
+        let (tx, mut rx) = tokio::sync::mpsc::channel(32);
+        tokio::spawn(async move { while rx.recv().await.is_some() {} });
+        let outcome = run_with_config("http://mock".into(), Uuid::nil(), 1, &config, tx).await;
+        assert_eq!(outcome, RunOutcome::Completed);
+    }
+}
@@ -0,0 +1,183 @@
+use shuttlings::{try_update, SubmissionUpdate};
+use tokio::sync::mpsc::Sender;
+
+use crate::{
+    helpers::{expect_json, expect_text, shared_client, SendWithRetry},
+    BoxFuture, Challenge, ChallengeInfo, TestInfo, TestTracker, ValidateResult,
+};
+
+pub(crate) const TESTS: &[TestInfo] = &[
+    TestInfo {
+        task: 1,
+        test: 1,
+        description: "POST /4/strength should return the combined strength of the reindeer",
+    },
+    TestInfo {
+        task: 2,
+        test: 1,
+        description: "POST /4/contest should announce the winners of each category",
+    },
+];
+
+pub(crate) const CHALLENGE: ChallengeInfo = ChallengeInfo {
+    challenge: 4,
+    description: "Reindeer strength and candy eating contest",
+    core: 1,
+    bonus: &[0, 150],
+    scored: true,
+    exclusive: false,
+    time_scaled: false,
+    tests: TESTS,
+    timeout: 20,
+    validator: &Day,
+};
+
+#[derive(Debug)]
+struct Day;
+
+impl Challenge for Day {
+    fn validate<'a>(
+        &'a self,
+        url: &'a str,
+        task: i32,
+        tx: Sender<SubmissionUpdate>,
+        tests: &'a mut TestTracker,
+    ) -> BoxFuture<'a, Option<ValidateResult>> {
+        Box::pin(async move {
+            Some(match task {
+                1 => task1(url, tx, tests).await,
+                2 => task2(url, tx, tests).await,
+                _ => return None,
+            })
+        })
+    }
+}
+
+async fn task1(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    // TASK 1
+    let test = tests.start((1, 1)).await;
+    let url = &format!("{}/4/strength", base_url);
+    let res = client
+        .post(url)
+        .json(&serde_json::json!([
+            {
+              "name": "Zeus",
+              "strength": 8
+            },
+            {
+              "name": "Oner",
+              "strength": 6
+            },
+            {
+              "name": "Faker",
+              "strength": 7
+            },
+            {
+              "name": "Gumayusi",
+              "strength": 6
+            },
+            {
+              "name": "Keria",
+              "strength": 6
+            }
+        ]))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_text(test, &text, "33")?;
+    // TASK 1 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(1)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task2(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    // TASK 2
+    let test = tests.start((2, 1)).await;
+    let url = &format!("{}/4/contest", base_url);
+    let res = client
+        .post(url)
+        .json(&serde_json::json!([
+        {
+            "name": "Zeus",
+            "strength": 8,
+            "speed": 51.2,
+            "height": 81,
+            "antler_width": 31,
+            "snow_magic_power": 311,
+            "favorite_food": "pizza",
+            "cAnD13s_3ATeN-yesT3rdAy": 4
+        },
+        {
+            "name": "Oner",
+            "strength": 6,
+            "speed": 41.3,
+            "height": 51,
+            "antler_width": 30,
+            "snow_magic_power": 321,
+            "favorite_food": "burger",
+            "cAnD13s_3ATeN-yesT3rdAy": 1
+        },
+        {
+            "name": "Faker",
+            "strength": 7,
+            "speed": 50,
+            "height": 50,
+            "antler_width": 37,
+            "snow_magic_power": 6667,
+            "favorite_food": "broccoli",
+            "cAnD13s_3ATeN-yesT3rdAy": 1
+        },
+        {
+            "name": "Gumayusi",
+            "strength": 6,
+            "speed": 60.1,
+            "height": 50,
+            "antler_width": 34,
+            "snow_magic_power": 2323,
+            "favorite_food": "pizza",
+            "cAnD13s_3ATeN-yesT3rdAy": 1
+        },
+        {
+            "name": "Keria",
+            "strength": 6,
+            "speed": 48.2,
+            "height": 65,
+            "antler_width": 33,
+            "snow_magic_power": 5014,
+            "favorite_food": "wok",
+            "cAnD13s_3ATeN-yesT3rdAy": 5
+        }
+        ]))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    let json = res.json::<serde_json::Value>().await.map_err(|_| test)?;
+    expect_json(
+        test,
+        &json,
+        &serde_json::json!({
+            "fastest":"Speeding past the finish line with a strength of 6 is Gumayusi",
+            "tallest":"Zeus is standing tall with his 31 cm wide antlers",
+            "magician":"Faker could blast you away with a snow magic power of 6667",
+            "consumer":"Keria ate lots of candies, but also some wok"
+        }),
+    )?;
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(2)).await?;
+
+    Ok(())
+}
@@ -0,0 +1,214 @@
+use reqwest::StatusCode;
+use shuttlings::{try_update, SubmissionUpdate};
+use tokio::sync::mpsc::Sender;
+
+use crate::{
+    helpers::JSONTester, BoxFuture, Challenge, ChallengeInfo, TestInfo, TestTracker, ValidateResult,
+};
+
+pub(crate) const TESTS: &[TestInfo] = &[
+    TestInfo {
+        task: 1,
+        test: 1,
+        description: "POST /5?offset=0&limit=8 should return the first eight names",
+    },
+    TestInfo {
+        task: 1,
+        test: 2,
+        description: "POST /5?offset=10&limit=4 should return four names from the middle",
+    },
+    TestInfo {
+        task: 2,
+        test: 1,
+        description: "POST /5 with an empty list should return an empty list",
+    },
+    TestInfo {
+        task: 2,
+        test: 2,
+        description: "POST /5 without parameters should return every name",
+    },
+    TestInfo {
+        task: 2,
+        test: 3,
+        description: "POST /5?offset=2 should return the names after the offset",
+    },
+    TestInfo {
+        task: 2,
+        test: 4,
+        description: "POST /5?offset=2&limit=0 should return an empty list",
+    },
+    TestInfo {
+        task: 2,
+        test: 5,
+        description: "POST /5?split=6 should split the names into lists of six",
+    },
+    TestInfo {
+        task: 2,
+        test: 6,
+        description: "POST /5?offset=2&limit=4&split=1 should return single name lists",
+    },
+    TestInfo {
+        task: 2,
+        test: 7,
+        description: "POST /5?limit=0 should return an empty list",
+    },
+    TestInfo {
+        task: 2,
+        test: 8,
+        description: "POST /5?offset=0&limit=0 should return an empty list",
+    },
+];
+
+pub(crate) const CHALLENGE: ChallengeInfo = ChallengeInfo {
+    challenge: 5,
+    description: "Paginating and splitting a list of names",
+    core: 1,
+    bonus: &[0, 150],
+    scored: true,
+    exclusive: false,
+    time_scaled: false,
+    tests: TESTS,
+    timeout: 20,
+    validator: &Day,
+};
+
+#[derive(Debug)]
+struct Day;
+
+impl Challenge for Day {
+    fn validate<'a>(
+        &'a self,
+        url: &'a str,
+        task: i32,
+        tx: Sender<SubmissionUpdate>,
+        tests: &'a mut TestTracker,
+    ) -> BoxFuture<'a, Option<ValidateResult>> {
+        Box::pin(async move {
+            Some(match task {
+                1 => task1(url, tx, tests).await,
+                2 => task2(url, tx, tests).await,
+                _ => return None,
+            })
+        })
+    }
+}
+
+async fn task1(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    // TASK 1
+    let t = JSONTester::new(format!("{}/5?offset=0&limit=8", base_url));
+    t.test(
+        tests.start((1, 1)).await,
+        &serde_json::json!(["Ava", "Caleb", "Mia", "Owen", "Lily", "Ethan", "Zoe", "Nolan"]),
+        StatusCode::OK,
+        &serde_json::json!(["Ava", "Caleb", "Mia", "Owen", "Lily", "Ethan", "Zoe", "Nolan"]),
+    )
+    .await?;
+    let t = JSONTester::new(format!("{}/5?offset=10&limit=4", base_url));
+    t.test(
+        tests.start((1, 2)).await,
+        &serde_json::json!([
+            "Ava", "Caleb", "Mia", "Owen", "Lily", "Ethan", "Zoe", "Nolan", "Harper", "Lucas",
+            "Stella", "Mason", "Olivia", "Wyatt", "Isabella", "Logan",
+        ]),
+        StatusCode::OK,
+        &serde_json::json!(["Stella", "Mason", "Olivia", "Wyatt"]),
+    )
+    .await?;
+    // TASK 1 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(1)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task2(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    // TASK 2
+    let t = JSONTester::new(format!("{}/5?offset=0&limit=5", base_url));
+    t.test(
+        tests.start((2, 1)).await,
+        &serde_json::json!([]),
+        StatusCode::OK,
+        &serde_json::json!([]),
+    )
+    .await?;
+    let t = JSONTester::new(format!("{}/5", base_url));
+    t.test(
+        tests.start((2, 2)).await,
+        &serde_json::json!(["Alice", "Bob", "Charlie", "David"]),
+        StatusCode::OK,
+        &serde_json::json!(["Alice", "Bob", "Charlie", "David"]),
+    )
+    .await?;
+    let t = JSONTester::new(format!("{}/5?offset=2", base_url));
+    t.test(
+        tests.start((2, 3)).await,
+        &serde_json::json!(["Alice", "Bob", "Charlie", "David"]),
+        StatusCode::OK,
+        &serde_json::json!(["Charlie", "David"]),
+    )
+    .await?;
+    let t = JSONTester::new(format!("{}/5?offset=2&limit=0", base_url));
+    t.test(
+        tests.start((2, 4)).await,
+        &serde_json::json!(["Alice", "Bob", "Charlie", "David"]),
+        StatusCode::OK,
+        &serde_json::json!([]),
+    )
+    .await?;
+    let t = JSONTester::new(format!("{}/5?split=6", base_url));
+    t.test(
+        tests.start((2, 5)).await,
+        &serde_json::json!([
+            "Alice", "Bob", "Charlie", "David", "Eva", "Frank", "Grace", "Hank", "Ivy", "Jack",
+            "Katie", "Liam", "Mia", "Nathan", "Olivia", "Paul", "Quinn", "Rachel", "Samuel",
+            "Tara", "Aria", "Jackson"
+        ]),
+        StatusCode::OK,
+        &serde_json::json!([
+            ["Alice", "Bob", "Charlie", "David", "Eva", "Frank"],
+            ["Grace", "Hank", "Ivy", "Jack", "Katie", "Liam"],
+            ["Mia", "Nathan", "Olivia", "Paul", "Quinn", "Rachel"],
+            ["Samuel", "Tara", "Aria", "Jackson"]
+        ]),
+    )
+    .await?;
+    let t = JSONTester::new(format!("{}/5?offset=2&limit=4&split=1", base_url));
+    t.test(
+        tests.start((2, 6)).await,
+        &serde_json::json!([
+            "Alice", "Bob", "Charlie", "David", "Alice", "Bob", "Charlie", "David"
+        ]),
+        StatusCode::OK,
+        &serde_json::json!([["Charlie"], ["David"], ["Alice"], ["Bob"],]),
+    )
+    .await?;
+    let t = JSONTester::new(format!("{}/5?limit=0", base_url));
+    t.test(
+        tests.start((2, 7)).await,
+        &serde_json::json!(["Alice", "Bob", "Charlie", "David"]),
+        StatusCode::OK,
+        &serde_json::json!([]),
+    )
+    .await?;
+    let t = JSONTester::new(format!("{}/5?offset=0&limit=0", base_url));
+    t.test(
+        tests.start((2, 8)).await,
+        &serde_json::json!(["Alice", "Bob", "Charlie", "David"]),
+        StatusCode::OK,
+        &serde_json::json!([]),
+    )
+    .await?;
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(2)).await?;
+
+    Ok(())
+}
@@ -0,0 +1,171 @@
+use shuttlings::{try_update, SubmissionUpdate};
+use tokio::sync::mpsc::Sender;
+
+use crate::{
+    helpers::{expect_json, shared_client, SendWithRetry},
+    BoxFuture, Challenge, ChallengeInfo, TaskTest, TestInfo, TestTracker, ValidateResult,
+};
+
+pub(crate) const TESTS: &[TestInfo] = &[
+    TestInfo {
+        task: 1,
+        test: 1,
+        description: "POST /6 with \"elf elf elf\" should count 3 elves",
+    },
+    TestInfo {
+        task: 1,
+        test: 2,
+        description: "POST /6 with a story should count 6 elves",
+    },
+    TestInfo {
+        task: 2,
+        test: 1,
+        description: "POST /6 with \"elf elf elf on a shelf\" should count 1 elf on a shelf",
+    },
+    TestInfo {
+        task: 2,
+        test: 2,
+        description: "POST /6 should count overlapping elves on shelves",
+    },
+    TestInfo {
+        task: 2,
+        test: 3,
+        description: "POST /6 should count shelves with no elf on them",
+    },
+];
+
+pub(crate) const CHALLENGE: ChallengeInfo = ChallengeInfo {
+    challenge: 6,
+    description: "Counting elves on shelves",
+    core: 1,
+    bonus: &[0, 200],
+    scored: true,
+    exclusive: false,
+    time_scaled: false,
+    tests: TESTS,
+    timeout: 20,
+    validator: &Day,
+};
+
+#[derive(Debug)]
+struct Day;
+
+impl Challenge for Day {
+    fn validate<'a>(
+        &'a self,
+        url: &'a str,
+        task: i32,
+        tx: Sender<SubmissionUpdate>,
+        tests: &'a mut TestTracker,
+    ) -> BoxFuture<'a, Option<ValidateResult>> {
+        Box::pin(async move {
+            Some(match task {
+                1 => task1(url, tx, tests).await,
+                2 => task2(url, tx, tests).await,
+                _ => return None,
+            })
+        })
+    }
+}
+
+async fn task1(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    let url = &format!("{}/6", base_url);
+    // TASK 1: elf
+    test = tests.start((1, 1)).await;
+    let res = client
+        .post(url)
+        .body("elf elf elf")
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    let json = res.json::<serde_json::Value>().await.map_err(|_| test)?;
+    expect_json(test, &json["elf"], &serde_json::json!(3))?;
+    test = tests.start((1, 2)).await;
+    let res = client
+        .post(url)
+        .body("In the quirky town of Elf stood an enchanting shop named 'The Elf & Shelf.' Managed by Wally, a mischievous elf with a knack for crafting exquisite shelves, the shop was a bustling hub of elf after elf who wanter to see their dear elf in Belfast.")
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    let json = res.json::<serde_json::Value>().await.map_err(|_| test)?;
+    expect_json(test, &json["elf"], &serde_json::json!(6))?;
+    // TASK 1 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(1)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task2(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    let url = &format!("{}/6", base_url);
+    // TASK 2: more strings
+    test = tests.start((2, 1)).await;
+    let res = client
+        .post(url)
+        .body("elf elf elf on a shelf")
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    let json = res.json::<serde_json::Value>().await.map_err(|_| test)?;
+    expect_json(
+        test,
+        &json,
+        &serde_json::json!({
+            "elf":4,
+            "elf on a shelf":1,
+            "shelf with no elf on it":0
+        }),
+    )?;
+    test = tests.start((2, 2)).await;
+    let res = client
+        .post(url)
+        .body("In Belfast I heard an elf on a shelf on a shelf on a ")
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    let json = res.json::<serde_json::Value>().await.map_err(|_| test)?;
+    expect_json(
+        test,
+        &json,
+        &serde_json::json!({
+            "elf":4,
+            "elf on a shelf":2,
+            "shelf with no elf on it":0
+        }),
+    )?;
+    test = tests.start((2, 3)).await;
+    let res = client
+        .post(url)
+        .body("Somewhere in Belfast under a shelf store but above the shelf realm there's an elf on a shelf on a shelf on a shelf on a elf on a shelf on a shelf on a shelf on a shelf on a elf on a elf on a elf on a shelf on a ")
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    let json = res.json::<serde_json::Value>().await.map_err(|_| test)?;
+    expect_json(
+        test,
+        &json,
+        &serde_json::json!({
+            "elf":16,
+            "elf on a shelf":8,
+            "shelf with no elf on it":2
+        }),
+    )?;
+    // TASK 2 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(2)).await?;
+
+    Ok(())
+}
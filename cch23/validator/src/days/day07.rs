@@ -0,0 +1,459 @@
+use base64::{engine::general_purpose, Engine};
+use shuttlings::{try_update, SubmissionUpdate};
+use tokio::sync::mpsc::Sender;
+
+use crate::{
+    helpers::{expect_content_type, expect_json, shared_client, SendWithRetry},
+    BoxFuture, Challenge, ChallengeInfo, TaskTest, TestInfo, TestTracker, ValidateResult,
+};
+
+pub(crate) const TESTS: &[TestInfo] = &[
+    TestInfo {
+        task: 1,
+        test: 1,
+        description: "GET /7/decode should return the recipe in the cookie (as application/json with --strict)",
+    },
+    TestInfo {
+        task: 1,
+        test: 2,
+        description: "GET /7/decode should return another recipe in the cookie (as application/json with --strict)",
+    },
+    TestInfo {
+        task: 2,
+        test: 1,
+        description: "GET /7/bake should bake as many cookies as the pantry allows (as application/json with --strict)",
+    },
+    TestInfo {
+        task: 2,
+        test: 2,
+        description: "GET /7/bake should handle very large pantry amounts (as application/json with --strict)",
+    },
+    TestInfo {
+        task: 3,
+        test: 1,
+        description: "GET /7/bake with an empty pantry should bake 0 cookies (as application/json with --strict)",
+    },
+    TestInfo {
+        task: 3,
+        test: 2,
+        description: "GET /7/bake should ignore pantry ingredients missing from the recipe (as application/json with --strict)",
+    },
+    TestInfo {
+        task: 3,
+        test: 3,
+        description: "GET /7/bake should ignore recipe ingredients with a quantity of 0 (as application/json with --strict)",
+    },
+    TestInfo {
+        task: 3,
+        test: 4,
+        description: "GET /7/bake should allow recipe ingredients of 0 missing from the pantry (as application/json with --strict)",
+    },
+];
+
+pub(crate) const CHALLENGE: ChallengeInfo = ChallengeInfo {
+    challenge: 7,
+    description: "Cookie recipe decoding and baking",
+    core: 1,
+    bonus: &[0, 120, 100],
+    scored: true,
+    exclusive: false,
+    time_scaled: false,
+    tests: TESTS,
+    timeout: 20,
+    validator: &Day,
+};
+
+#[derive(Debug)]
+struct Day;
+
+impl Challenge for Day {
+    fn validate<'a>(
+        &'a self,
+        url: &'a str,
+        task: i32,
+        tx: Sender<SubmissionUpdate>,
+        tests: &'a mut TestTracker,
+    ) -> BoxFuture<'a, Option<ValidateResult>> {
+        Box::pin(async move {
+            Some(match task {
+                1 => task1(url, tx, tests).await,
+                2 => task2(url, tx, tests).await,
+                3 => task3(url, tx, tests).await,
+                _ => return None,
+            })
+        })
+    }
+}
+
+async fn task1(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    // TASK 1
+    test = tests.start((1, 1)).await;
+    let url = &format!("{}/7/decode", base_url);
+    let data = serde_json::json!({
+        "recipe": {
+            "flour": 4,
+            "sugar": 3,
+            "butter": 3,
+            "baking powder": 1,
+            "raisins": 50
+        },
+    });
+    let b64 = general_purpose::STANDARD.encode(serde_json::to_vec(&data).unwrap());
+    let res = client
+        .get(url)
+        .header("Cookie", format!("recipe={b64}"))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    expect_content_type(test, res.headers(), "application/json")?;
+    let json = res.json::<serde_json::Value>().await.map_err(|_| test)?;
+    expect_json(test, &json, &data)?;
+    test = tests.start((1, 2)).await;
+    let data = serde_json::json!({
+        "recipe": {
+            "peanuts": 26,
+            "dough": 37,
+            "extra salt": 1,
+            "raisins": 50
+        },
+    });
+    let b64 = general_purpose::STANDARD.encode(serde_json::to_vec(&data).unwrap());
+    let res = client
+        .get(url)
+        .header("Cookie", format!("recipe={b64}"))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    expect_content_type(test, res.headers(), "application/json")?;
+    let json = res.json::<serde_json::Value>().await.map_err(|_| test)?;
+    expect_json(test, &json, &data)?;
+    // TASK 1 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(1)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task2(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let mut test: TaskTest;
+    // TASK 2
+    test = tests.start((2, 1)).await;
+    test_bake(
+        base_url,
+        test,
+        serde_json::json!({
+            "recipe": {
+                "flour": 35,
+                "sugar": 56,
+                "butter": 3,
+                "baking powder": 1001,
+                "chocolate chips": 55
+            },
+            "pantry": {
+                "flour": 4045,
+                "sugar": 9606,
+                "butter": 99, // will land at 0
+                "baking powder": 8655432,
+                "chocolate chips": 4587
+            }
+        }),
+        serde_json::json!({
+            "cookies": 33,
+            "pantry": {
+                "flour": 2890,
+                "sugar": 7758,
+                "butter": 0,
+                "baking powder": 8622399,
+                "chocolate chips": 2772
+            }
+        }),
+    )
+    .await?;
+    test = tests.start((2, 2)).await;
+    test_bake(
+        base_url,
+        test,
+        serde_json::json!({
+            "recipe": {
+                "flour": 35,
+                "sugar": 56,
+                "butter": 3,
+                "baking powder": 1001,
+                "chocolate chips": 55
+            },
+            "pantry": {
+                "flour": 4045,
+                "sugar": 7606,
+                "butter": 100,
+                "baking powder": 865543211516164409i64,
+                "chocolate chips": 4587
+            }
+        }),
+        serde_json::json!({
+            "cookies": 33,
+            "pantry": {
+                "flour": 2890,
+                "sugar": 5758,
+                "butter": 1,
+                "baking powder": 865543211516131376i64,
+                "chocolate chips": 2772
+            }
+        }),
+    )
+    .await?;
+    // TASK 2 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(2)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task3(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let mut test: TaskTest;
+    // TASK 3
+    test = tests.start((3, 1)).await;
+    test_bake(
+        base_url,
+        test,
+        serde_json::json!({
+            "recipe": {
+                "chicken": 1,
+            },
+            "pantry": {
+                "chicken": 0,
+            }
+        }),
+        serde_json::json!({
+            "cookies": 0,
+            "pantry": {
+                "chicken": 0,
+            }
+        }),
+    )
+    .await?;
+    test = tests.start((3, 2)).await;
+    test_bake(
+        base_url,
+        test,
+        serde_json::json!({
+            "recipe": {
+                "cocoa bean": 1,
+                "chicken": 0,
+            },
+            "pantry": {
+                "cocoa bean": 5,
+                "corn": 5,
+                "cucumber": 0,
+            }
+        }),
+        serde_json::json!({
+            "cookies": 5,
+            "pantry": {
+                "cocoa bean": 0,
+                "corn": 5,
+                "cucumber": 0,
+            }
+        }),
+    )
+    .await?;
+    test = tests.start((3, 3)).await;
+    test_bake(
+        base_url,
+        test,
+        serde_json::json!({
+            "recipe": {
+                "cocoa bean": 1,
+                "chicken": 0,
+            },
+            "pantry": {
+                "cocoa bean": 5,
+                "chicken": 0,
+            }
+        }),
+        serde_json::json!({
+            "cookies": 5,
+            "pantry": {
+                "cocoa bean": 0,
+                "chicken": 0,
+            }
+        }),
+    )
+    .await?;
+    test = tests.start((3, 4)).await;
+    test_bake(
+        base_url,
+        test,
+        serde_json::json!({
+            "recipe": {
+                "cocoa bean": 1,
+                "chicken": 0,
+            },
+            "pantry": {
+                "cocoa bean": 5,
+            }
+        }),
+        serde_json::json!({
+            "cookies": 5,
+            "pantry": {
+                "cocoa bean": 0,
+            }
+        }),
+    )
+    .await?;
+    // TASK 3 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(3)).await?;
+
+    Ok(())
+}
+
+async fn test_bake(
+    base_url: &str,
+    test: TaskTest,
+    i: serde_json::Value,
+    o: serde_json::Value,
+) -> ValidateResult {
+    let client = shared_client();
+    let url = &format!("{}/7/bake", base_url);
+    let b64 = general_purpose::STANDARD.encode(serde_json::to_vec(&i).unwrap());
+    let res = client
+        .get(url)
+        .header("Cookie", format!("recipe={b64}"))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    expect_content_type(test, res.headers(), "application/json")?;
+    let json = res.json::<serde_json::Value>().await.map_err(|_| test)?;
+    expect_json(test, &json, &o)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use reqwest::{Method, StatusCode};
+    use serde_json::{json, Value};
+    use shuttlings::{
+        testing::Strictness,
+        transport::{response, MockTransport},
+        LogLevel,
+    };
+
+    use super::*;
+    use uuid::Uuid;
+
+    use crate::{run_with_config, tests::run_mocked, RunOutcome, ValidationConfig};
+
+    /// The JSON in the `recipe` cookie of a request
+    fn cookie(req: &reqwest::Request) -> Value {
+        let cookie = req.headers()["cookie"].to_str().unwrap();
+        let b64 = cookie.strip_prefix("recipe=").unwrap();
+        serde_json::from_slice(&general_purpose::STANDARD.decode(b64).unwrap()).unwrap()
+    }
+
+    fn bake(input: &Value) -> Value {
+        let (recipe, pantry) = (&input["recipe"], input["pantry"].as_object().unwrap());
+        let cookies = recipe
+            .as_object()
+            .unwrap()
+            .iter()
+            .filter_map(|(k, n)| Some((k, n.as_i64().filter(|&n| n > 0)?)))
+            .map(|(k, n)| pantry.get(k).and_then(Value::as_i64).unwrap_or(0) / n)
+            .min()
+            .unwrap_or(0);
+        let pantry: serde_json::Map<_, _> = pantry
+            .iter()
+            .map(|(k, have)| {
+                let used = cookies * recipe[k].as_i64().unwrap_or(0);
+                (k.clone(), json!(have.as_i64().unwrap() - used))
+            })
+            .collect();
+        json!({"cookies": cookies, "pantry": pantry})
+    }
+
+    fn solution(bake: fn(&Value) -> Value) -> MockTransport {
+        MockTransport::new()
+            .route(Method::GET, "/7/decode", |req| {
+                response(StatusCode::OK, cookie(req).to_string())
+            })
+            .route(Method::GET, "/7/bake", move |req| {
+                response(StatusCode::OK, bake(&cookie(req)).to_string())
+            })
+    }
+
+    #[tokio::test]
+    async fn baking_what_the_pantry_allows_passes() {
+        for task in 1..=3 {
+            assert_eq!(
+                run_mocked(7, task, solution(bake)).await,
+                RunOutcome::Completed
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn an_untouched_pantry_fails_task2() {
+        let untouched =
+            |input: &Value| json!({"cookies": bake(input)["cookies"], "pantry": input["pantry"]});
+        assert_eq!(
+            run_mocked(7, 2, solution(untouched)).await,
+            RunOutcome::Failed { task: 2, test: 1 }
+        );
+    }
+
+    #[tokio::test]
+    async fn json_without_its_content_type_only_fails_strict_runs() {
+        let run = |strictness| async move {
+            let config = ValidationConfig {
+                task: Some(1),
+                transport: Some(Arc::new(solution(bake))),
+                strictness,
+                ..Default::default()
+            };
+            let (tx, mut rx) = tokio::sync::mpsc::channel(32);
+            let outcome = run_with_config("http://mock".into(), Uuid::nil(), 7, &config, tx).await;
+            let mut warnings = Vec::new();
+            while let Some(update) = rx.recv().await {
+                if let SubmissionUpdate::LogLine {
+                    level: LogLevel::Warn,
+                    message,
+                } = update
+                {
+                    warnings.push(message)
+                }
+            }
+            (outcome, warnings)
+        };
+        let warning = |test| {
+            format!(
+                "Warning: Task 1: test #{test} expected content-type: \"application/json\", \
+                got no content-type header (a failure with --strict)"
+            )
+        };
+        assert_eq!(
+            run(Strictness::Default).await,
+            (RunOutcome::Completed, vec![warning(1), warning(2)])
+        );
+        assert_eq!(
+            run(Strictness::Strict).await,
+            (RunOutcome::Failed { task: 1, test: 1 }, vec![])
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use shuttlings::{testing::Tolerance, try_update, SubmissionUpdate};
+use tokio::sync::mpsc::Sender;
+
+use crate::{
+    helpers::{expect_number, shared_client, SendWithRetry},
+    BoxFuture, Challenge, ChallengeInfo, TaskTest, TestInfo, TestTracker, ValidateResult,
+};
+
+pub(crate) const TESTS: &[TestInfo] = &[
+    TestInfo {
+        task: 1,
+        test: 1,
+        description: "GET /8/weight/225 should return 16 (within 10 times the tolerance with --lenient)",
+    },
+    TestInfo {
+        task: 1,
+        test: 2,
+        description: "GET /8/weight/393 should return 5.2 (within 10 times the tolerance with --lenient)",
+    },
+    TestInfo {
+        task: 1,
+        test: 3,
+        description: "GET /8/weight/92 should return 0.1 (within 10 times the tolerance with --lenient)",
+    },
+    TestInfo {
+        task: 2,
+        test: 1,
+        description: "GET /8/drop/383 should return 13316.953480432378 (within 10 times the tolerance with --lenient)",
+    },
+    TestInfo {
+        task: 2,
+        test: 2,
+        description: "GET /8/drop/16 should return 25.23212238397714 (within 10 times the tolerance with --lenient)",
+    },
+    TestInfo {
+        task: 2,
+        test: 3,
+        description: "GET /8/drop/143 should return 6448.2090536830465 (within 10 times the tolerance with --lenient)",
+    },
+];
+
+pub(crate) const CHALLENGE: ChallengeInfo = ChallengeInfo {
+    challenge: 8,
+    description: "Pokémon weights and drop momentum",
+    core: 1,
+    bonus: &[0, 160],
+    scored: true,
+    exclusive: false,
+    time_scaled: false,
+    tests: TESTS,
+    timeout: 60,
+    validator: &Day,
+};
+
+#[derive(Debug)]
+struct Day;
+
+impl Challenge for Day {
+    fn validate<'a>(
+        &'a self,
+        url: &'a str,
+        task: i32,
+        tx: Sender<SubmissionUpdate>,
+        tests: &'a mut TestTracker,
+    ) -> BoxFuture<'a, Option<ValidateResult>> {
+        Box::pin(async move {
+            Some(match task {
+                1 => task1(url, tx, tests).await,
+                2 => task2(url, tx, tests).await,
+                _ => return None,
+            })
+        })
+    }
+}
+
+async fn task1(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    let tol = Tolerance::Absolute(0.001);
+    // TASK 1
+    test = tests.start((1, 1)).await;
+    let url = &format!("{}/8/weight/225", base_url);
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_number(test, &text, 16.0, tol)?;
+    test = tests.start((1, 2)).await;
+    let url = &format!("{}/8/weight/393", base_url);
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_number(test, &text, 5.2, tol)?;
+    test = tests.start((1, 3)).await;
+    let url = &format!("{}/8/weight/92", base_url);
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_number(test, &text, 0.1, tol)?;
+    // TASK 1 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(1)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task2(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    let tol = Tolerance::Absolute(0.001);
+    // TASK 2
+    test = tests.start((2, 1)).await;
+    let url = &format!("{}/8/drop/383", base_url);
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_number(test, &text, 13316.953480432378, tol)?;
+    test = tests.start((2, 2)).await;
+    let url = &format!("{}/8/drop/16", base_url);
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_number(test, &text, 25.23212238397714, tol)?;
+    test = tests.start((2, 3)).await;
+    let url = &format!("{}/8/drop/143", base_url);
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_number(test, &text, 6448.2090536830465, tol)?;
+    // TASK 2 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(2)).await?;
+
+    Ok(())
+}
@@ -0,0 +1,169 @@
+use reqwest::{
+    header::{CONTENT_LENGTH, CONTENT_TYPE},
+    multipart::Form,
+};
+use shuttlings::{try_update, SubmissionUpdate};
+use tokio::sync::mpsc::Sender;
+
+use crate::{
+    assets::{DECORATION, DECORATION2, DECORATION3, DECORATION4},
+    helpers::{expect_headers, expect_text, shared_client, SendWithRetry},
+    BoxFuture, Challenge, ChallengeInfo, TaskTest, TestInfo, TestTracker, ValidateResult,
+};
+
+pub(crate) const TESTS: &[TestInfo] = &[
+    TestInfo {
+        task: 1,
+        test: 1,
+        description: "GET /11/assets/decoration.png should serve the image",
+    },
+    TestInfo {
+        task: 2,
+        test: 1,
+        description: "POST /11/red_pixels with decoration2.png should return 152107",
+    },
+    TestInfo {
+        task: 2,
+        test: 2,
+        description: "POST /11/red_pixels with decoration3.png should return 40263",
+    },
+    TestInfo {
+        task: 2,
+        test: 3,
+        description: "POST /11/red_pixels with decoration4.png should return 86869",
+    },
+];
+
+pub(crate) const CHALLENGE: ChallengeInfo = ChallengeInfo {
+    challenge: 11,
+    description: "Serving assets and counting red pixels",
+    core: 1,
+    bonus: &[0, 200],
+    scored: true,
+    exclusive: false,
+    time_scaled: false,
+    tests: TESTS,
+    timeout: 30,
+    validator: &Day,
+};
+
+#[derive(Debug)]
+struct Day;
+
+impl Challenge for Day {
+    fn validate<'a>(
+        &'a self,
+        url: &'a str,
+        task: i32,
+        tx: Sender<SubmissionUpdate>,
+        tests: &'a mut TestTracker,
+    ) -> BoxFuture<'a, Option<ValidateResult>> {
+        Box::pin(async move {
+            Some(match task {
+                1 => task1(url, tx, tests).await,
+                2 => task2(url, tx, tests).await,
+                _ => return None,
+            })
+        })
+    }
+}
+
+async fn task1(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    // TASK 1
+    let test = tests.start((1, 1)).await;
+    let url = &format!("{}/11/assets/decoration.png", base_url);
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    expect_headers(
+        test,
+        res.headers(),
+        &[(CONTENT_TYPE, "image/png"), (CONTENT_LENGTH, "787297")],
+    )?;
+    let bytes = res.bytes().await.map_err(|_| test)?;
+    if *bytes != *DECORATION.load(test, &tx).await? {
+        return Err(test.into());
+    }
+    // TASK 1 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(1)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task2(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    // TASK 2
+    test = tests.start((2, 1)).await;
+    let url = &format!("{}/11/red_pixels", base_url);
+    let form = Form::new().part(
+        "image",
+        DECORATION2
+            .upload(test, &tx)
+            .await?
+            .part()
+            .file_name(DECORATION2.name)
+            .mime_str("image/png")
+            .unwrap(),
+    );
+    let res = client
+        .post(url)
+        .multipart(form)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_text(test, &text, "152107")?;
+    test = tests.start((2, 2)).await;
+    let form = Form::new().part(
+        "image",
+        DECORATION3
+            .upload(test, &tx)
+            .await?
+            .part()
+            .file_name(DECORATION3.name)
+            .mime_str("image/png")
+            .unwrap(),
+    );
+    let res = client
+        .post(url)
+        .multipart(form)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_text(test, &text, "40263")?;
+    test = tests.start((2, 3)).await;
+    let form = Form::new().part(
+        "image",
+        DECORATION4
+            .upload(test, &tx)
+            .await?
+            .part()
+            .file_name(DECORATION4.name)
+            .mime_str("image/png")
+            .unwrap(),
+    );
+    let res = client
+        .post(url)
+        .multipart(form)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_text(test, &text, "86869")?;
+    // TASK 2 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(2)).await?;
+
+    Ok(())
+}
@@ -0,0 +1,313 @@
+use reqwest::StatusCode;
+use shuttlings::{testing::text_mismatch_detail, try_update, SubmissionUpdate};
+use tokio::{
+    sync::mpsc::Sender,
+    time::{sleep, Duration, Instant},
+};
+
+use crate::{
+    helpers::{expect_json, expect_status, shared_client, strictness, time_scale, SendWithRetry},
+    BoxFuture, Challenge, ChallengeInfo, TaskTest, TestFailure, TestInfo, TestTracker,
+    ValidateResult,
+};
+
+pub(crate) const TESTS: &[TestInfo] = &[
+    TestInfo {
+        task: 1,
+        test: 1,
+        description: "GET /12/load should return the seconds since the packet was saved",
+    },
+    TestInfo {
+        task: 1,
+        test: 2,
+        description: "GET /12/load should track each saved packet separately",
+    },
+    TestInfo {
+        task: 2,
+        test: 1,
+        description: "POST /12/ulids should convert the ULIDs to UUIDs in reverse order",
+    },
+    TestInfo {
+        task: 2,
+        test: 2,
+        description: "POST /12/ulids with an empty list should return an empty list",
+    },
+    TestInfo {
+        task: 3,
+        test: 1,
+        description: "POST /12/ulids/5 should count the ULIDs for weekday 5",
+    },
+    TestInfo {
+        task: 3,
+        test: 2,
+        description: "POST /12/ulids/0 should count the ULIDs for weekday 0",
+    },
+    TestInfo {
+        task: 3,
+        test: 3,
+        description: "POST /12/ulids/2 should count a single ULID",
+    },
+];
+
+pub(crate) const CHALLENGE: ChallengeInfo = ChallengeInfo {
+    challenge: 12,
+    description: "Packet timekeeping and ULID analysis",
+    core: 1,
+    bonus: &[0, 100, 200],
+    scored: true,
+    exclusive: false,
+    time_scaled: true,
+    tests: TESTS,
+    timeout: 90,
+    validator: &Day,
+};
+
+#[derive(Debug)]
+struct Day;
+
+impl Challenge for Day {
+    fn validate<'a>(
+        &'a self,
+        url: &'a str,
+        task: i32,
+        tx: Sender<SubmissionUpdate>,
+        tests: &'a mut TestTracker,
+    ) -> BoxFuture<'a, Option<ValidateResult>> {
+        Box::pin(async move {
+            Some(match task {
+                1 => task1(url, tx, tests).await,
+                2 => task2(url, tx, tests).await,
+                3 => task3(url, tx, tests).await,
+                _ => return None,
+            })
+        })
+    }
+}
+
+async fn task1(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = &shared_client();
+    let mut test: TaskTest;
+    // the server counts whole seconds, so the scaled pauses are too
+    let pause = |secs: f64| Duration::from_secs((secs * time_scale()).round() as u64);
+    let (two, one) = (pause(2.0), pause(1.0));
+    // TASK 1
+    test = tests.start((1, 1)).await;
+    let cch23 = save(client, base_url, "cch23", test).await?;
+    sleep(two).await;
+    expect_seconds(client, base_url, "cch23", &cch23, test).await?;
+    sleep(one).await;
+    expect_seconds(client, base_url, "cch23", &cch23, test).await?;
+    test = tests.start((1, 2)).await;
+    let alpha = save(client, base_url, "alpha", test).await?;
+    sleep(two).await;
+    let omega = save(client, base_url, "omega", test).await?;
+    sleep(one).await;
+    expect_seconds(client, base_url, "alpha", &alpha, test).await?;
+    let alpha = save(client, base_url, "alpha", test).await?;
+    sleep(one).await;
+    expect_seconds(client, base_url, "omega", &omega, test).await?;
+    expect_seconds(client, base_url, "alpha", &alpha, test).await?;
+    // TASK 1 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(1)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+/// When the server saved a packet: some time between sending the request and getting its response
+struct Saved {
+    sent: Instant,
+    answered: Instant,
+}
+
+async fn save(
+    client: &reqwest::Client,
+    base_url: &str,
+    packet: &str,
+    test: TaskTest,
+) -> Result<Saved, TestFailure> {
+    let url = format!("{}/12/save/{}", base_url, packet);
+    let sent = Instant::now();
+    let res = client.post(url).send_with_retry().await.map_err(|_| test)?;
+    let answered = Instant::now();
+    expect_status(test, res.status(), StatusCode::OK)?;
+    Ok(Saved { sent, answered })
+}
+
+/// Expect the seconds since `saved` to be one that the server could have counted.
+/// The requests take time, so the seconds that passed are only known to lie in a window,
+/// and a server may round them down or to the nearest second.
+async fn expect_seconds(
+    client: &reqwest::Client,
+    base_url: &str,
+    packet: &str,
+    saved: &Saved,
+    test: TaskTest,
+) -> ValidateResult {
+    let url = format!("{}/12/load/{}", base_url, packet);
+    let sent = Instant::now();
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    let answered = Instant::now();
+    let text = res.text().await.map_err(|_| test)?;
+    let (shortest, longest) = (sent - saved.answered, answered - saved.sent);
+    let accepted = shortest.as_secs()..=longest.as_secs_f64().round() as u64;
+    // a number never ends with a newline
+    let secs = strictness().text(&text, "").parse();
+    if secs.is_ok_and(|secs| accepted.contains(&secs)) {
+        return Ok(());
+    }
+    let expected = if accepted.start() == accepted.end() {
+        accepted.start().to_string()
+    } else {
+        format!("{} to {}", accepted.start(), accepted.end())
+    };
+    let detail = text_mismatch_detail(&expected, &text);
+    Err(TestFailure::mismatch(
+        test,
+        format!(
+            "{expected} ({:.2}s to {:.2}s passed since the save, rounded down or to the nearest second)",
+            shortest.as_secs_f64(),
+            longest.as_secs_f64()
+        ),
+        format!("{text}{detail}"),
+    ))
+}
+
+async fn task2(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    // TASK 2
+    test = tests.start((2, 1)).await;
+    let url = &format!("{}/12/ulids", base_url);
+    let res = client
+        .post(url)
+        .json(&serde_json::json!([
+            "01BJQ0E1C3Z56ABCD0E11HYX4M",
+            "01BJQ0E1C3Z56ABCD0E11HYX5N",
+            "01BJQ0E1C3Z56ABCD0E11HYX6Q",
+            "01BJQ0E1C3Z56ABCD0E11HYX7R",
+            "01BJQ0E1C3Z56ABCD0E11HYX8P"
+        ]))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    let json = res.json::<serde_json::Value>().await.map_err(|_| test)?;
+    expect_json(
+        test,
+        &json,
+        &serde_json::json!([
+            "015cae07-0583-f94c-a5b1-a070431f7516",
+            "015cae07-0583-f94c-a5b1-a070431f74f8",
+            "015cae07-0583-f94c-a5b1-a070431f74d7",
+            "015cae07-0583-f94c-a5b1-a070431f74b5",
+            "015cae07-0583-f94c-a5b1-a070431f7494"
+        ]),
+    )?;
+    test = tests.start((2, 2)).await;
+    let res = client
+        .post(url)
+        .json(&serde_json::json!([]))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    let json = res.json::<serde_json::Value>().await.map_err(|_| test)?;
+    expect_json(test, &json, &serde_json::json!([]))?;
+    // TASK 2 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(2)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task3(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    // TASK 3
+    test = tests.start((3, 1)).await;
+    let ids = serde_json::json!([
+        "00WEGGF0G0J5HEYXS3D7RWZGV8",
+        "76EP4G39R8JD1N8AQNYDVJBRCF",
+        "018CJ7KMG0051CDCS3B7BFJ3AK",
+        "00Y986KPG0AMGB78RD45E9109K",
+        "010451HTG0NYWMPWCEXG6AJ8F2",
+        "01HH9SJEG0KY16H81S3N1BMXM4",
+        "01HH9SJEG0P9M22Z9VGHH9C8CX",
+        "017F8YY0G0NQA16HHC2QT5JD6X",
+        "03QCPC7P003V1NND3B3QJW72QJ"
+    ]);
+    let url = &format!("{}/12/ulids/5", base_url);
+    let res = client
+        .post(url)
+        .json(&ids)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    let json = res.json::<serde_json::Value>().await.map_err(|_| test)?;
+    expect_json(
+        test,
+        &json,
+        &serde_json::json!({
+            "christmas eve": 3,
+            "weekday": 1,
+            "in the future": 2,
+            "LSB is 1": 5
+        }),
+    )?;
+    test = tests.start((3, 2)).await;
+    let url = &format!("{}/12/ulids/0", base_url);
+    let res = client
+        .post(url)
+        .json(&ids)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    let json = res.json::<serde_json::Value>().await.map_err(|_| test)?;
+    expect_json(
+        test,
+        &json,
+        &serde_json::json!({
+            "christmas eve": 3,
+            "weekday": 0,
+            "in the future": 2,
+            "LSB is 1": 5
+        }),
+    )?;
+    test = tests.start((3, 3)).await;
+    let url = &format!("{}/12/ulids/2", base_url);
+    let res = client
+        .post(url)
+        .json(&serde_json::json!(["04BJK8N300BAMR9SQQWPWHVYKZ"]))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    let json = res.json::<serde_json::Value>().await.map_err(|_| test)?;
+    expect_json(
+        test,
+        &json,
+        &serde_json::json!({
+            "christmas eve": 1,
+            "weekday": 1,
+            "in the future": 1,
+            "LSB is 1": 1
+        }),
+    )?;
+    // TASK 3 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(3)).await?;
+
+    Ok(())
+}
@@ -0,0 +1,260 @@
+use reqwest::StatusCode;
+use shuttlings::{try_update, SubmissionUpdate};
+use tokio::sync::mpsc::Sender;
+
+use crate::{
+    helpers::{expect_json, expect_status, expect_text, shared_client, SendWithRetry},
+    BoxFuture, Challenge, ChallengeInfo, TaskTest, TestInfo, TestTracker, ValidateResult,
+};
+
+pub(crate) const TESTS: &[TestInfo] = &[
+    TestInfo {
+        task: 1,
+        test: 1,
+        description: "GET /13/sql should return 20231213",
+    },
+    TestInfo {
+        task: 2,
+        test: 1,
+        description: "POST /13/orders should store the orders for GET /13/orders/total",
+    },
+    TestInfo {
+        task: 2,
+        test: 2,
+        description: "GET /13/orders/total should include orders posted later",
+    },
+    TestInfo {
+        task: 3,
+        test: 1,
+        description: "GET /13/orders/popular without orders should return null",
+    },
+    TestInfo {
+        task: 3,
+        test: 2,
+        description: "GET /13/orders/popular should return the most ordered gift",
+    },
+];
+
+pub(crate) const CHALLENGE: ChallengeInfo = ChallengeInfo {
+    challenge: 13,
+    description: "Gift orders in an SQL database",
+    core: 2,
+    bonus: &[0, 0, 100],
+    scored: true,
+    exclusive: true,
+    time_scaled: false,
+    tests: TESTS,
+    timeout: 60,
+    validator: &Day,
+};
+
+#[derive(Debug)]
+struct Day;
+
+impl Challenge for Day {
+    fn validate<'a>(
+        &'a self,
+        url: &'a str,
+        task: i32,
+        tx: Sender<SubmissionUpdate>,
+        tests: &'a mut TestTracker,
+    ) -> BoxFuture<'a, Option<ValidateResult>> {
+        Box::pin(async move {
+            Some(match task {
+                1 => task1(url, tx, tests).await,
+                2 => task2(url, tx, tests).await,
+                3 => task3(url, tx, tests).await,
+                _ => return None,
+            })
+        })
+    }
+}
+
+async fn task1(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    // TASK 1
+    let test = tests.start((1, 1)).await;
+    let url = &format!("{}/13/sql", base_url);
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_text(test, &text, "20231213")?;
+    // TASK 1 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(1)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task2(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    // TASK 2
+    test = tests.start((2, 1)).await;
+    let reset_url = &format!("{}/13/reset", base_url);
+    let order_url = &format!("{}/13/orders", base_url);
+    let total_url = &format!("{}/13/orders/total", base_url);
+    let res = client
+        .post(reset_url)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    expect_status(test, res.status(), StatusCode::OK)?;
+    let res = client
+        .post(order_url)
+        .json(&serde_json::json!([
+            {"id":1,"region_id":2,"gift_name":"Toy Train","quantity":5},
+            {"id":2,"region_id":2,"gift_name":"Doll","quantity":8},
+            {"id":3,"region_id":3,"gift_name":"Action Figure","quantity":12},
+            {"id":4,"region_id":4,"gift_name":"Board Game","quantity":10},
+            {"id":5,"region_id":2,"gift_name":"Teddy Bear","quantity":6},
+            {"id":6,"region_id":3,"gift_name":"Toy Train","quantity":3},
+        ]))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    expect_status(test, res.status(), StatusCode::OK)?;
+    let res = client
+        .get(total_url)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    let json = res.json::<serde_json::Value>().await.map_err(|_| test)?;
+    expect_json(test, &json, &serde_json::json!({"total": 44}))?;
+    test = tests.start((2, 2)).await;
+    let res = client
+        .post(order_url)
+        .json(&serde_json::json!([
+            {"id":123,"region_id":6,"gift_name":"Unknown","quantity":333},
+        ]))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    expect_status(test, res.status(), StatusCode::OK)?;
+    let res = client
+        .get(total_url)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    let json = res.json::<serde_json::Value>().await.map_err(|_| test)?;
+    expect_json(test, &json, &serde_json::json!({"total": 377}))?;
+    // TASK 2 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(2)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task3(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    // TASK 3
+    test = tests.start((3, 1)).await;
+    let reset_url = &format!("{}/13/reset", base_url);
+    let order_url = &format!("{}/13/orders", base_url);
+    let popular_url = &format!("{}/13/orders/popular", base_url);
+    let res = client
+        .post(reset_url)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    expect_status(test, res.status(), StatusCode::OK)?;
+    let res = client
+        .get(popular_url)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    let json = res.json::<serde_json::Value>().await.map_err(|_| test)?;
+    expect_json(test, &json, &serde_json::json!({"popular": null}))?;
+    test = tests.start((3, 2)).await;
+    let res = client
+        .post(order_url)
+        .json(&serde_json::json!([
+            {"id":1,"region_id":2,"gift_name":"Lego Rocket","quantity":12},
+            {"id":2,"region_id":2,"gift_name":"Action Figure","quantity":18},
+            {"id":3,"region_id":5,"gift_name":"Toy Train","quantity":19},
+            {"id":4,"region_id":5,"gift_name":"Lego Rocket","quantity":12},
+            {"id":5,"region_id":4,"gift_name":"Toy Train","quantity":15},
+            {"id":6,"region_id":2,"gift_name":"Toy Train","quantity":7},
+            {"id":7,"region_id":3,"gift_name":"Toy Train","quantity":19},
+            {"id":8,"region_id":4,"gift_name":"Action Figure","quantity":8},
+            {"id":9,"region_id":2,"gift_name":"Toy Axe","quantity":15},
+            {"id":10,"region_id":4,"gift_name":"Toy Axe","quantity":1},
+            {"id":11,"region_id":2,"gift_name":"Toy Train","quantity":17},
+            {"id":12,"region_id":4,"gift_name":"Toy Train","quantity":5},
+            {"id":13,"region_id":4,"gift_name":"Sweater","quantity":20},
+            {"id":14,"region_id":4,"gift_name":"Action Figure","quantity":7},
+            {"id":15,"region_id":2,"gift_name":"Toy Train","quantity":16},
+            {"id":16,"region_id":3,"gift_name":"Action Figure","quantity":12},
+            {"id":17,"region_id":4,"gift_name":"Toy Axe","quantity":2},
+            {"id":18,"region_id":3,"gift_name":"Toy Train","quantity":9},
+            {"id":19,"region_id":2,"gift_name":"Sweater","quantity":9},
+            {"id":20,"region_id":5,"gift_name":"Toy Train","quantity":9},
+            {"id":21,"region_id":4,"gift_name":"Action Figure","quantity":11},
+            {"id":22,"region_id":3,"gift_name":"Toy Train","quantity":7},
+            {"id":23,"region_id":2,"gift_name":"Action Figure","quantity":5},
+            {"id":24,"region_id":4,"gift_name":"Action Figure","quantity":17},
+            {"id":25,"region_id":5,"gift_name":"Lego Rocket","quantity":6},
+            {"id":26,"region_id":2,"gift_name":"Sweater","quantity":5},
+            {"id":27,"region_id":5,"gift_name":"Toy Train","quantity":4},
+            {"id":28,"region_id":4,"gift_name":"Lego Rocket","quantity":8},
+            {"id":29,"region_id":2,"gift_name":"Toy Train","quantity":3},
+            {"id":30,"region_id":4,"gift_name":"Toy Axe","quantity":20},
+            {"id":31,"region_id":2,"gift_name":"Action Figure","quantity":5},
+            {"id":32,"region_id":2,"gift_name":"Lego Rocket","quantity":10},
+            {"id":33,"region_id":5,"gift_name":"Toy Train","quantity":4},
+            {"id":34,"region_id":2,"gift_name":"Toy Axe","quantity":14},
+            {"id":35,"region_id":3,"gift_name":"Action Figure","quantity":18},
+            {"id":36,"region_id":5,"gift_name":"Toy Axe","quantity":10},
+            {"id":37,"region_id":4,"gift_name":"Lego Rocket","quantity":6},
+            {"id":38,"region_id":4,"gift_name":"Action Figure","quantity":16},
+            {"id":39,"region_id":4,"gift_name":"Toy Axe","quantity":15},
+            {"id":40,"region_id":5,"gift_name":"Lego Rocket","quantity":15},
+            {"id":41,"region_id":5,"gift_name":"Action Figure","quantity":7},
+            {"id":42,"region_id":3,"gift_name":"Action Figure","quantity":16},
+            {"id":43,"region_id":3,"gift_name":"Toy Train","quantity":8},
+            {"id":44,"region_id":4,"gift_name":"Action Figure","quantity":13},
+            {"id":45,"region_id":3,"gift_name":"Lego Rocket","quantity":12},
+            {"id":46,"region_id":3,"gift_name":"Toy Train","quantity":1},
+            {"id":47,"region_id":2,"gift_name":"Toy Train","quantity":11},
+            {"id":48,"region_id":5,"gift_name":"Action Figure","quantity":1},
+            {"id":49,"region_id":4,"gift_name":"Toy Train","quantity":13},
+            {"id":50,"region_id":5,"gift_name":"Action Figure","quantity":16},
+            {"id":51,"region_id":4,"gift_name":"Toy Axe","quantity":19},
+            {"id":52,"region_id":2,"gift_name":"Toy Train","quantity":14},
+            {"id":53,"region_id":3,"gift_name":"Action Figure","quantity":16},
+        ]))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    expect_status(test, res.status(), StatusCode::OK)?;
+    let res = client
+        .get(popular_url)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    let json = res.json::<serde_json::Value>().await.map_err(|_| test)?;
+    expect_json(
+        test,
+        &json,
+        &serde_json::json!({"popular": "Action Figure"}),
+    )?;
+    // TASK 3 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(3)).await?;
+
+    Ok(())
+}
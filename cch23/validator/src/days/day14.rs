@@ -0,0 +1,157 @@
+use shuttlings::{try_update, SubmissionUpdate};
+use tokio::sync::mpsc::Sender;
+
+use crate::{
+    helpers::{expect_content_type, expect_text, shared_client, SendWithRetry},
+    BoxFuture, Challenge, ChallengeInfo, TaskTest, TestInfo, TestTracker, ValidateResult,
+};
+
+pub(crate) const TESTS: &[TestInfo] = &[
+    TestInfo {
+        task: 1,
+        test: 1,
+        description: "POST /14/unsafe should render the content in the page (as text/html with --strict)",
+    },
+    TestInfo {
+        task: 1,
+        test: 2,
+        description: "POST /14/unsafe should render HTML in the content unescaped (as text/html with --strict)",
+    },
+    TestInfo {
+        task: 2,
+        test: 1,
+        description: "POST /14/safe should escape HTML in the content (as text/html with --strict)",
+    },
+];
+
+pub(crate) const CHALLENGE: ChallengeInfo = ChallengeInfo {
+    challenge: 14,
+    description: "Rendering unsafe and safe HTML",
+    core: 1,
+    bonus: &[0, 100],
+    scored: true,
+    exclusive: false,
+    time_scaled: false,
+    tests: TESTS,
+    timeout: 20,
+    validator: &Day,
+};
+
+#[derive(Debug)]
+struct Day;
+
+impl Challenge for Day {
+    fn validate<'a>(
+        &'a self,
+        url: &'a str,
+        task: i32,
+        tx: Sender<SubmissionUpdate>,
+        tests: &'a mut TestTracker,
+    ) -> BoxFuture<'a, Option<ValidateResult>> {
+        Box::pin(async move {
+            Some(match task {
+                1 => task1(url, tx, tests).await,
+                2 => task2(url, tx, tests).await,
+                _ => return None,
+            })
+        })
+    }
+}
+
+async fn task1(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    // TASK 1
+    test = tests.start((1, 1)).await;
+    let url = &format!("{}/14/unsafe", base_url);
+    let res = client
+        .post(url)
+        .json(&serde_json::json!({"content": "Bing Chilling 🥶🍦"}))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    expect_content_type(test, res.headers(), "text/html")?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_text(
+        test,
+        &text,
+        "\
+<html>
+  <head>
+    <title>CCH23 Day 14</title>
+  </head>
+  <body>
+    Bing Chilling 🥶🍦
+  </body>
+</html>",
+    )?;
+    test = tests.start((1, 2)).await;
+    let res = client
+        .post(url)
+        .json(&serde_json::json!({"content": r#"<script>alert("XSS Attack Success!")</script>"#}))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    expect_content_type(test, res.headers(), "text/html")?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_text(
+        test,
+        &text,
+        "\
+<html>
+  <head>
+    <title>CCH23 Day 14</title>
+  </head>
+  <body>
+    <script>alert(\"XSS Attack Success!\")</script>
+  </body>
+</html>",
+    )?;
+    // TASK 1 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(1)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task2(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    // TASK 2
+    let test = tests.start((2, 1)).await;
+    let url = &format!("{}/14/safe", base_url);
+    let res = client
+        .post(url)
+        .json(&serde_json::json!({"content": r#"<script>alert("XSS Attack Failed!")</script>"#}))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    expect_content_type(test, res.headers(), "text/html")?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_text(
+        test,
+        &text,
+        "\
+<html>
+  <head>
+    <title>CCH23 Day 14</title>
+  </head>
+  <body>
+    &lt;script&gt;alert(&quot;XSS Attack Failed!&quot;)&lt;/script&gt;
+  </body>
+</html>",
+    )?;
+    // TASK 2 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(2)).await?;
+
+    Ok(())
+}
@@ -0,0 +1,423 @@
+use reqwest::{
+    header::{HeaderValue, CONTENT_TYPE},
+    StatusCode,
+};
+use shuttlings::{try_update, SubmissionUpdate};
+use tokio::sync::mpsc::Sender;
+
+use crate::{
+    helpers::{expect_status, shared_client, JSONTester, SendWithRetry},
+    BoxFuture, Challenge, ChallengeInfo, TestInfo, TestTracker, ValidateResult,
+};
+
+pub(crate) const TESTS: &[TestInfo] = &[
+    TestInfo {
+        task: 1,
+        test: 1,
+        description: "POST /15/nice with \"hello there\" should be nice",
+    },
+    TestInfo {
+        task: 1,
+        test: 2,
+        description: "POST /15/nice with \"he77o there\" should be naughty",
+    },
+    TestInfo {
+        task: 1,
+        test: 3,
+        description: "POST /15/nice with \"hello\" should be naughty",
+    },
+    TestInfo {
+        task: 1,
+        test: 4,
+        description: "POST /15/nice with \"hello xylophone\" should be naughty",
+    },
+    TestInfo {
+        task: 1,
+        test: 5,
+        description: "POST /15/nice with \"password\" should be naughty",
+    },
+    TestInfo {
+        task: 1,
+        test: 6,
+        description: "POST /15/nice with invalid JSON should return 400 Bad Request",
+    },
+    TestInfo {
+        task: 2,
+        test: 1,
+        description: "POST /15/game with \"mario\" should fail rule 1 (8 chars)",
+    },
+    TestInfo {
+        task: 2,
+        test: 2,
+        description: "POST /15/game with \"mariobro\" should fail rule 2 (more types of chars)",
+    },
+    TestInfo {
+        task: 2,
+        test: 3,
+        description: "POST /15/game with \"EEEEEEEEEEE\" should fail rule 2 (more types of chars)",
+    },
+    TestInfo {
+        task: 2,
+        test: 4,
+        description: "POST /15/game with \"E3E3E3E3E3E\" should fail rule 2 (more types of chars)",
+    },
+    TestInfo {
+        task: 2,
+        test: 5,
+        description: "POST /15/game without 5 digits should fail rule 3 (55555)",
+    },
+    TestInfo {
+        task: 2,
+        test: 6,
+        description: "POST /15/game with \"Password12345\" should fail rule 4 (math is hard)",
+    },
+    TestInfo {
+        task: 2,
+        test: 7,
+        description: "POST /15/game with integers not adding up to 2023 should fail rule 4 (math is hard)",
+    },
+    TestInfo {
+        task: 2,
+        test: 8,
+        description: "POST /15/game without j, o and y should fail rule 5 (not joyful enough)",
+    },
+    TestInfo {
+        task: 2,
+        test: 9,
+        description: "POST /15/game with j, o and y out of order should fail rule 5 (not joyful enough)",
+    },
+    TestInfo {
+        task: 2,
+        test: 10,
+        description: "POST /15/game with a repeated joy should fail rule 5 (not joyful enough)",
+    },
+    TestInfo {
+        task: 2,
+        test: 11,
+        description: "POST /15/game with an extra o after joy should fail rule 5 (not joyful enough)",
+    },
+    TestInfo {
+        task: 2,
+        test: 12,
+        description: "POST /15/game without a letter sandwich should fail rule 6 (illegal: no sandwich)",
+    },
+    TestInfo {
+        task: 2,
+        test: 13,
+        description: "POST /15/game with digits around the sandwich should fail rule 6 (illegal: no sandwich)",
+    },
+    TestInfo {
+        task: 2,
+        test: 14,
+        description: "POST /15/game without a character in U+2980..U+2BFF should fail rule 7 (outranged)",
+    },
+    TestInfo {
+        task: 2,
+        test: 15,
+        description: "POST /15/game with a character just outside U+2980..U+2BFF should fail rule 7 (outranged)",
+    },
+    TestInfo {
+        task: 2,
+        test: 16,
+        description: "POST /15/game without an emoji should fail rule 8 (😳)",
+    },
+    TestInfo {
+        task: 2,
+        test: 17,
+        description: "POST /15/game with a SHA-256 not ending in a should fail rule 9 (not a coffee brewer)",
+    },
+    TestInfo {
+        task: 2,
+        test: 18,
+        description: "POST /15/game with a password passing every rule should be nice",
+    },
+];
+
+pub(crate) const CHALLENGE: ChallengeInfo = ChallengeInfo {
+    challenge: 15,
+    description: "Password validation rules",
+    core: 1,
+    bonus: &[0, 400],
+    scored: true,
+    exclusive: false,
+    time_scaled: false,
+    tests: TESTS,
+    timeout: 20,
+    validator: &Day,
+};
+
+#[derive(Debug)]
+struct Day;
+
+impl Challenge for Day {
+    fn validate<'a>(
+        &'a self,
+        url: &'a str,
+        task: i32,
+        tx: Sender<SubmissionUpdate>,
+        tests: &'a mut TestTracker,
+    ) -> BoxFuture<'a, Option<ValidateResult>> {
+        Box::pin(async move {
+            Some(match task {
+                1 => task1(url, tx, tests).await,
+                2 => task2(url, tx, tests).await,
+                _ => return None,
+            })
+        })
+    }
+}
+
+async fn task1(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    // TASK 1
+    let t = JSONTester::new(format!("{}/15/nice", base_url));
+    t.test(
+        tests.start((1, 1)).await,
+        &serde_json::json!({"input": "hello there"}),
+        StatusCode::OK,
+        &serde_json::json!({"result": "nice"}),
+    )
+    .await?;
+    t.test(
+        tests.start((1, 2)).await,
+        &serde_json::json!({"input": "he77o there"}),
+        StatusCode::BAD_REQUEST,
+        &serde_json::json!({"result": "naughty"}),
+    )
+    .await?;
+    t.test(
+        tests.start((1, 3)).await,
+        &serde_json::json!({"input": "hello"}),
+        StatusCode::BAD_REQUEST,
+        &serde_json::json!({"result": "naughty"}),
+    )
+    .await?;
+    t.test(
+        tests.start((1, 4)).await,
+        &serde_json::json!({"input": "hello xylophone"}),
+        StatusCode::BAD_REQUEST,
+        &serde_json::json!({"result": "naughty"}),
+    )
+    .await?;
+    t.test(
+        tests.start((1, 5)).await,
+        &serde_json::json!({"input": "password"}),
+        StatusCode::BAD_REQUEST,
+        &serde_json::json!({"result": "naughty"}),
+    )
+    .await?;
+    let test = tests.start((1, 6)).await;
+    let res = shared_client()
+        .post(format!("{}/15/nice", base_url))
+        .header(CONTENT_TYPE, HeaderValue::from_static("application/json"))
+        .body("WooooOOOooOOOoooOO 👻")
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    expect_status(test, res.status(), StatusCode::BAD_REQUEST)?;
+    // TASK 1 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(1)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task2(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    // TASK 2
+    let t = JSONTester::new(format!("{}/15/game", base_url));
+    t.test(
+        tests.start((2, 1)).await,
+        &serde_json::json!({"input": "mario"}),
+        StatusCode::BAD_REQUEST,
+        &serde_json::json!({"result": "naughty", "reason": "8 chars"}),
+    )
+    .await?;
+    t.test(
+        tests.start((2, 2)).await,
+        &serde_json::json!({"input": "mariobro"}),
+        StatusCode::BAD_REQUEST,
+        &serde_json::json!({"result": "naughty", "reason": "more types of chars"}),
+    )
+    .await?;
+    t.test(
+        tests.start((2, 3)).await,
+        &serde_json::json!({"input": "EEEEEEEEEEE"}),
+        StatusCode::BAD_REQUEST,
+        &serde_json::json!({"result": "naughty", "reason": "more types of chars"}),
+    )
+    .await?;
+    t.test(
+        tests.start((2, 4)).await,
+        &serde_json::json!({"input": "E3E3E3E3E3E"}),
+        StatusCode::BAD_REQUEST,
+        &serde_json::json!({"result": "naughty", "reason": "more types of chars"}),
+    )
+    .await?;
+    t.test(
+        tests.start((2, 5)).await,
+        &serde_json::json!({"input": "e3E3e#eE#ee3#EeE3"}),
+        StatusCode::BAD_REQUEST,
+        &serde_json::json!({"result": "naughty", "reason": "55555"}),
+    )
+    .await?;
+    t.test(
+        tests.start((2, 6)).await,
+        &serde_json::json!({"input": "Password12345"}),
+        StatusCode::BAD_REQUEST,
+        &serde_json::json!({"result": "naughty", "reason": "math is hard"}),
+    )
+    .await?;
+    t.test(
+        tests.start((2, 7)).await,
+        &serde_json::json!({"input": "2 00 2 3 OOgaBooga"}),
+        StatusCode::BAD_REQUEST,
+        &serde_json::json!({"result": "naughty", "reason": "math is hard"}),
+    )
+    .await?;
+    t.test(
+        tests.start((2, 8)).await,
+        &serde_json::json!({"input": "2+2/2-8*8 = 1-2000 OOgaBooga"}),
+        StatusCode::NOT_ACCEPTABLE,
+        &serde_json::json!({"result": "naughty", "reason": "not joyful enough"}),
+    )
+    .await?;
+    t.test(
+        tests.start((2, 9)).await,
+        &serde_json::json!({"input": "2000.23.A yoyoj"}),
+        StatusCode::NOT_ACCEPTABLE,
+        &serde_json::json!({"result": "naughty", "reason": "not joyful enough"}),
+    )
+    .await?;
+    t.test(
+        tests.start((2, 10)).await,
+        &serde_json::json!({"input": "2000.23.A joy joy"}),
+        StatusCode::NOT_ACCEPTABLE,
+        &serde_json::json!({"result": "naughty", "reason": "not joyful enough"}),
+    )
+    .await?;
+    t.test(
+        tests.start((2, 11)).await,
+        &serde_json::json!({"input": "2000.23.A joyo"}),
+        StatusCode::NOT_ACCEPTABLE,
+        &serde_json::json!({"result": "naughty", "reason": "not joyful enough"}),
+    )
+    .await?;
+    t.test(
+        tests.start((2, 12)).await,
+        &serde_json::json!({"input": "2000.23.A j  ;)  o  ;)  y "}),
+        StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS,
+        &serde_json::json!({"result": "naughty", "reason": "illegal: no sandwich"}),
+    )
+    .await?;
+    t.test(
+        tests.start((2, 13)).await,
+        &serde_json::json!({"input": "2020.3.A j  ;)  o  ;)  y"}),
+        StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS,
+        &serde_json::json!({"result": "naughty", "reason": "illegal: no sandwich"}),
+    )
+    .await?;
+    t.test(
+        tests.start((2, 14)).await,
+        &serde_json::json!({"input": "2000.23.A j  ;)  o  ;)  y AzA"}),
+        StatusCode::RANGE_NOT_SATISFIABLE,
+        &serde_json::json!({"result": "naughty", "reason": "outranged"}),
+    )
+    .await?;
+    t.test(
+        tests.start((2, 15)).await,
+        &serde_json::json!({"input": "2000.23.A j  ;)  o  ;)  y⥿ AzA"}),
+        StatusCode::RANGE_NOT_SATISFIABLE,
+        &serde_json::json!({"result": "naughty", "reason": "outranged"}),
+    )
+    .await?;
+    t.test(
+        tests.start((2, 16)).await,
+        &serde_json::json!({"input": "2000.23.A j  ;)  o  ;)  y ⦄AzA"}),
+        StatusCode::UPGRADE_REQUIRED,
+        &serde_json::json!({"result": "naughty", "reason": "😳"}),
+    )
+    .await?;
+    t.test(
+        tests.start((2, 17)).await,
+        &serde_json::json!({"input": "2000.23.A j  🥶  o  🍦  y ⦄AzA"}),
+        StatusCode::IM_A_TEAPOT,
+        &serde_json::json!({"result": "naughty", "reason": "not a coffee brewer"}),
+    )
+    .await?;
+    t.test(
+        tests.start((2, 18)).await,
+        &serde_json::json!({"input": "2000.23.A j ⦖⦖⦖⦖⦖⦖⦖⦖ 🥶  o  🍦  y ⦄AzA"}),
+        StatusCode::OK,
+        &serde_json::json!({"result": "nice", "reason": "that's a nice password"}),
+    )
+    .await?;
+    // TASK 2 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(2)).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::Method;
+    use serde_json::{json, Value};
+    use shuttlings::transport::{response, MockTransport};
+
+    use super::*;
+    use crate::{tests::run_mocked, RunOutcome};
+
+    fn is_nice(s: &str) -> bool {
+        let vowels = s.chars().filter(|c| "aeiouy".contains(*c)).count();
+        let double = s
+            .as_bytes()
+            .windows(2)
+            .any(|w| w[0] == w[1] && w[0].is_ascii_alphabetic());
+        let banned = ["ab", "cd", "pq", "xy"].iter().any(|b| s.contains(b));
+        vowels >= 3 && double && !banned
+    }
+
+    fn solution(is_nice: fn(&str) -> bool) -> MockTransport {
+        MockTransport::new().route(Method::POST, "/15/nice", move |req| {
+            let body = req.body().and_then(|b| b.as_bytes()).unwrap_or_default();
+            let Some(input) = serde_json::from_slice::<Value>(body)
+                .ok()
+                .and_then(|v| v["input"].as_str().map(str::to_owned))
+            else {
+                return response(StatusCode::BAD_REQUEST, "");
+            };
+            match is_nice(&input) {
+                true => response(StatusCode::OK, json!({"result": "nice"}).to_string()),
+                false => response(
+                    StatusCode::BAD_REQUEST,
+                    json!({"result": "naughty"}).to_string(),
+                ),
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn the_nice_rules_pass() {
+        assert_eq!(
+            run_mocked(15, 1, solution(is_nice)).await,
+            RunOutcome::Completed
+        );
+    }
+
+    #[tokio::test]
+    async fn allowing_banned_pairs_fails() {
+        let no_bans = |s: &str| is_nice(&s.replace(['x', 'y'], "o"));
+        assert_eq!(
+            run_mocked(15, 1, solution(no_bans)).await,
+            RunOutcome::Failed { task: 1, test: 4 }
+        );
+    }
+}
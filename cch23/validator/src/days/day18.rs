@@ -0,0 +1,418 @@
+use reqwest::StatusCode;
+use shuttlings::{testing::JSONTester, try_update, SubmissionUpdate};
+use tokio::sync::mpsc::Sender;
+
+use crate::{
+    helpers::{expect_status, shared_client, SendWithRetry},
+    BoxFuture, Challenge, ChallengeInfo, TaskTest, TestFailure, TestInfo, TestTracker,
+    ValidateResult,
+};
+
+pub(crate) const TESTS: &[TestInfo] = &[
+    TestInfo {
+        task: 1,
+        test: 1,
+        description: "GET /18/regions/total with only regions should return an empty list",
+    },
+    TestInfo {
+        task: 1,
+        test: 2,
+        description: "GET /18/regions/total with only orders should return an empty list",
+    },
+    TestInfo {
+        task: 1,
+        test: 3,
+        description: "GET /18/regions/total should total a single order",
+    },
+    TestInfo {
+        task: 1,
+        test: 4,
+        description: "GET /18/regions/total should add up the orders of a region",
+    },
+    TestInfo {
+        task: 1,
+        test: 5,
+        description: "GET /18/regions/total should total each region",
+    },
+    TestInfo {
+        task: 1,
+        test: 6,
+        description: "GET /18/regions/total should skip orders of unknown regions",
+    },
+    TestInfo {
+        task: 1,
+        test: 7,
+        description: "GET /18/regions/total should handle large quantities",
+    },
+    TestInfo {
+        task: 1,
+        test: 8,
+        description: "GET /18/regions/total should handle negative ids and quantities",
+    },
+    TestInfo {
+        task: 2,
+        test: 1,
+        description: "GET /18/regions/top_list/2 without data should return an empty list",
+    },
+    TestInfo {
+        task: 2,
+        test: 2,
+        description: "GET /18/regions/top_list/2 should list regions without orders",
+    },
+    TestInfo {
+        task: 2,
+        test: 3,
+        description: "GET /18/regions/top_list/2 with only orders should return an empty list",
+    },
+    TestInfo {
+        task: 2,
+        test: 4,
+        description: "GET /18/regions/top_list/2 should skip orders of unknown regions",
+    },
+    TestInfo {
+        task: 2,
+        test: 5,
+        description: "GET /18/regions/top_list/2 should order tied gifts by name",
+    },
+    TestInfo {
+        task: 2,
+        test: 6,
+        description: "GET /18/regions/top_list/2 should list the top gifts of every region",
+    },
+    TestInfo {
+        task: 2,
+        test: 7,
+        description: "GET /18/regions/top_list/3 should list up to three gifts per region",
+    },
+    TestInfo {
+        task: 2,
+        test: 8,
+        description: "GET /18/regions/top_list/0 should list no gifts",
+    },
+];
+
+pub(crate) const CHALLENGE: ChallengeInfo = ChallengeInfo {
+    challenge: 18,
+    description: "Gift order analytics per region",
+    core: 1,
+    bonus: &[0, 600],
+    scored: true,
+    exclusive: true,
+    time_scaled: false,
+    tests: TESTS,
+    timeout: 60,
+    validator: &Day,
+};
+
+#[derive(Debug)]
+struct Day;
+
+impl Challenge for Day {
+    fn validate<'a>(
+        &'a self,
+        url: &'a str,
+        task: i32,
+        tx: Sender<SubmissionUpdate>,
+        tests: &'a mut TestTracker,
+    ) -> BoxFuture<'a, Option<ValidateResult>> {
+        Box::pin(async move {
+            Some(match task {
+                1 => task1(url, tx, tests).await,
+                2 => task2(url, tx, tests).await,
+                _ => return None,
+            })
+        })
+    }
+}
+
+struct RegionGiftTester {
+    client: reqwest::Client,
+    reset_url: String,
+    regions_url: String,
+    orders_url: String,
+    final_url: String,
+}
+
+impl RegionGiftTester {
+    async fn test(
+        &self,
+        test: TaskTest,
+        i1: &serde_json::Value,
+        i2: &serde_json::Value,
+        o: &serde_json::Value,
+    ) -> ValidateResult {
+        let res = self
+            .client
+            .post(&self.reset_url)
+            .send_with_retry()
+            .await
+            .map_err(|_| test)?;
+        expect_status(test, res.status(), StatusCode::OK)?;
+        let res = self
+            .client
+            .post(&self.regions_url)
+            .json(i1)
+            .send_with_retry()
+            .await
+            .map_err(|_| test)?;
+        expect_status(test, res.status(), StatusCode::OK)?;
+        let res = self
+            .client
+            .post(&self.orders_url)
+            .json(i2)
+            .send_with_retry()
+            .await
+            .map_err(|_| test)?;
+        expect_status(test, res.status(), StatusCode::OK)?;
+        let res = self
+            .client
+            .get(&self.final_url)
+            .send_with_retry()
+            .await
+            .map_err(|_| test)?;
+        JSONTester::check(res, StatusCode::OK, &[], o)
+            .await
+            .map_err(|e| TestFailure::failed(test, e))
+    }
+}
+
+async fn task1(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    // TASK 1
+    let t = RegionGiftTester {
+        client: shared_client(),
+        reset_url: format!("{}/18/reset", base_url),
+        regions_url: format!("{}/18/regions", base_url),
+        orders_url: format!("{}/18/orders", base_url),
+        final_url: format!("{}/18/regions/total", base_url),
+    };
+    t.test(
+        tests.start((1, 1)).await,
+        &serde_json::json!([{"id":1,"name":"North Pole"}]),
+        &serde_json::json!([]),
+        &serde_json::json!([]),
+    )
+    .await?;
+    t.test(
+        tests.start((1, 2)).await,
+        &serde_json::json!([]),
+        &serde_json::json!([{"id":1,"region_id":2,"gift_name":"Board Game","quantity":5}]),
+        &serde_json::json!([]),
+    )
+    .await?;
+    t.test(
+        tests.start((1, 3)).await,
+        &serde_json::json!([{"id":1,"name":"A"}]),
+        &serde_json::json!([{"id":1,"region_id":1,"gift_name":"A","quantity":1}]),
+        &serde_json::json!([{"region":"A","total":1}]),
+    )
+    .await?;
+    t.test(
+        tests.start((1, 4)).await,
+        &serde_json::json!([{"id":1,"name":"A"}]),
+        &serde_json::json!([
+            {"id":1,"region_id":1,"gift_name":"A","quantity":1},
+            {"id":2,"region_id":1,"gift_name":"A","quantity":1},
+            {"id":3,"region_id":1,"gift_name":"A","quantity":1}
+        ]),
+        &serde_json::json!([{"region":"A","total":3}]),
+    )
+    .await?;
+    t.test(
+        tests.start((1, 5)).await,
+        &serde_json::json!([
+            {"id":1,"name":"A"},
+            {"id":2,"name":"B"}
+        ]),
+        &serde_json::json!([
+            {"id":1,"region_id":1,"gift_name":"A","quantity":1},
+            {"id":2,"region_id":1,"gift_name":"A","quantity":1},
+            {"id":3,"region_id":2,"gift_name":"B","quantity":1}
+        ]),
+        &serde_json::json!([
+            {"region":"A","total":2},
+            {"region":"B","total":1}
+        ]),
+    )
+    .await?;
+    t.test(
+        tests.start((1, 6)).await,
+        &serde_json::json!([
+            {"id":1,"name":"A"},
+            {"id":2,"name":"B"}
+        ]),
+        &serde_json::json!([
+            {"id":1,"region_id":1,"gift_name":"A","quantity":1},
+            {"id":2,"region_id":1,"gift_name":"A","quantity":1},
+            {"id":3,"region_id":3,"gift_name":"C","quantity":1}
+        ]),
+        &serde_json::json!([{"region":"A","total":2}]),
+    )
+    .await?;
+    t.test(
+        tests.start((1, 7)).await,
+        &serde_json::json!([{"id":1,"name":"A"}]),
+        &serde_json::json!([{"id":1,"region_id":1,"gift_name":"A","quantity":555555555}]),
+        &serde_json::json!([{"region":"A","total":555555555}]),
+    )
+    .await?;
+    t.test(
+        tests.start((1, 8)).await,
+        &serde_json::json!([{"id":-1,"name":"A"}]),
+        &serde_json::json!([
+            {"id":-1,"region_id":-1,"gift_name":"A","quantity":-1},
+            {"id":0,"region_id":-1,"gift_name":"A","quantity":1}
+        ]),
+        &serde_json::json!([{"region":"A","total":0}]),
+    )
+    .await?;
+    // TASK 1 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(1)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task2(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    // TASK 2
+    let t = RegionGiftTester {
+        client: shared_client(),
+        reset_url: format!("{}/18/reset", base_url),
+        regions_url: format!("{}/18/regions", base_url),
+        orders_url: format!("{}/18/orders", base_url),
+        final_url: format!("{}/18/regions/top_list/2", base_url),
+    };
+    t.test(
+        tests.start((2, 1)).await,
+        &serde_json::json!([]),
+        &serde_json::json!([]),
+        &serde_json::json!([]),
+    )
+    .await?;
+    t.test(
+        tests.start((2, 2)).await,
+        &serde_json::json!([{"id":1,"name":"A"}]),
+        &serde_json::json!([]),
+        &serde_json::json!([{"region":"A","top_gifts":[]}]),
+    )
+    .await?;
+    t.test(
+        tests.start((2, 3)).await,
+        &serde_json::json!([]),
+        &serde_json::json!([{"id":1,"region_id":2,"gift_name":"B","quantity":5}]),
+        &serde_json::json!([]),
+    )
+    .await?;
+    t.test(
+        tests.start((2, 4)).await,
+        &serde_json::json!([{"id":1,"name":"A"}]),
+        &serde_json::json!([{"id":1,"region_id":2,"gift_name":"B","quantity":5}]),
+        &serde_json::json!([{"region":"A","top_gifts":[]}]),
+    )
+    .await?;
+    t.test(
+        tests.start((2, 5)).await,
+        &serde_json::json!([{"id":1,"name":"A"}]),
+        &serde_json::json!([
+            {"id":1,"region_id":1,"gift_name":"B","quantity":10},
+            {"id":2,"region_id":1,"gift_name":"A","quantity":5},
+            {"id":3,"region_id":1,"gift_name":"A","quantity":5},
+            {"id":4,"region_id":1,"gift_name":"C","quantity":9}
+        ]),
+        &serde_json::json!([{"region":"A","top_gifts":["A","B"]}]),
+    )
+    .await?;
+    let regions = serde_json::json!([
+        {"id":1,"name":"North Pole"},
+        {"id":2,"name":"Europe"},
+        {"id":3,"name":"North America"},
+        {"id":4,"name":"South America"},
+        {"id":5,"name":"Africa"},
+        {"id":6,"name":"Asia"},
+        {"id":7,"name":"Oceania"}
+    ]);
+    t.test(
+        tests.start((2, 6)).await,
+        &regions,
+        &serde_json::json!([
+            {"id":1,"region_id":2,"gift_name":"Toy Train","quantity":5},
+            {"id":2,"region_id":2,"gift_name":"Toy Train","quantity":3},
+            {"id":3,"region_id":2,"gift_name":"Doll","quantity":8},
+            {"id":4,"region_id":3,"gift_name":"Toy Train","quantity":3},
+            {"id":5,"region_id":2,"gift_name":"Teddy Bear","quantity":6},
+            {"id":6,"region_id":3,"gift_name":"Action Figure","quantity":12},
+            {"id":7,"region_id":4,"gift_name":"Board Game","quantity":10},
+            {"id":8,"region_id":3,"gift_name":"Teddy Bear","quantity":1},
+            {"id":9,"region_id":3,"gift_name":"Teddy Bear","quantity":2}
+        ]),
+        &serde_json::json!([
+            {"region":"Africa","top_gifts":[]},
+            {"region":"Asia","top_gifts":[]},
+            {"region":"Europe","top_gifts":["Doll","Toy Train"]},
+            {"region":"North America","top_gifts":["Action Figure","Teddy Bear"]},
+            {"region":"North Pole","top_gifts":[]},
+            {"region":"Oceania","top_gifts":[]},
+            {"region":"South America","top_gifts":["Board Game"]},
+        ]),
+    )
+    .await?;
+    let t = RegionGiftTester {
+        client: shared_client(),
+        reset_url: format!("{}/18/reset", base_url),
+        regions_url: format!("{}/18/regions", base_url),
+        orders_url: format!("{}/18/orders", base_url),
+        final_url: format!("{}/18/regions/top_list/3", base_url),
+    };
+    t.test(
+        tests.start((2, 7)).await,
+        &regions,
+        &serde_json::json!([
+            {"id":1,"region_id":2,"gift_name":"Toy Train","quantity":5},
+            {"id":2,"region_id":2,"gift_name":"Toy Train","quantity":3},
+            {"id":3,"region_id":2,"gift_name":"Doll","quantity":8},
+            {"id":4,"region_id":3,"gift_name":"Toy Train","quantity":3},
+            {"id":5,"region_id":2,"gift_name":"Teddy Bear","quantity":6},
+            {"id":6,"region_id":3,"gift_name":"Action Figure","quantity":12},
+            {"id":7,"region_id":4,"gift_name":"Board Game","quantity":10},
+            {"id":8,"region_id":3,"gift_name":"Teddy Bear","quantity":1},
+            {"id":9,"region_id":3,"gift_name":"Teddy Bear","quantity":2}
+        ]),
+        &serde_json::json!([
+            {"region":"Africa","top_gifts":[]},
+            {"region":"Asia","top_gifts":[]},
+            {"region":"Europe","top_gifts":["Doll","Toy Train","Teddy Bear"]},
+            {"region":"North America","top_gifts":["Action Figure","Teddy Bear","Toy Train"]},
+            {"region":"North Pole","top_gifts":[]},
+            {"region":"Oceania","top_gifts":[]},
+            {"region":"South America","top_gifts":["Board Game"]},
+        ]),
+    )
+    .await?;
+    let t = RegionGiftTester {
+        client: shared_client(),
+        reset_url: format!("{}/18/reset", base_url),
+        regions_url: format!("{}/18/regions", base_url),
+        orders_url: format!("{}/18/orders", base_url),
+        final_url: format!("{}/18/regions/top_list/0", base_url),
+    };
+    t.test(
+        tests.start((2, 8)).await,
+        &serde_json::json!([{"id":1,"name":"A"}]),
+        &serde_json::json!([{"id":1,"region_id":1,"gift_name":"A","quantity":555555555}]),
+        &serde_json::json!([{"region":"A","top_gifts":[]}]),
+    )
+    .await?;
+    // TASK 2 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(2)).await?;
+
+    Ok(())
+}
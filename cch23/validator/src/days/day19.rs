@@ -0,0 +1,380 @@
+use std::{ops::Deref, sync::Arc};
+
+use reqwest::StatusCode;
+use shuttlings::{try_update, SubmissionUpdate};
+use tokio::{
+    sync::mpsc::Sender,
+    time::{sleep, Duration, Instant},
+};
+
+use crate::{
+    helpers::{expect_text, load, scaled, shared_client, strictness, ws_url, SendWithRetry, WS},
+    BoxFuture, Challenge, ChallengeInfo, TaskTest, TestFailure, TestInfo, TestTracker,
+    ValidateResult,
+};
+
+/// How long the views may take to reach the expected count
+const VIEWS_GRACE: Duration = Duration::from_secs(2);
+/// How often the views are fetched until they reach the expected count
+const VIEWS_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// The tweets of the load test, one per line, generated with https://github.com/orhun/godsays
+const PHRASES: &str = include_str!("day19_phrases.txt");
+
+pub(crate) const TESTS: &[TestInfo] = &[
+    TestInfo {
+        task: 1,
+        test: 1,
+        description: "GET /19/ws/ping should only answer \"pong\" after \"serve\"",
+    },
+    TestInfo {
+        task: 1,
+        test: 2,
+        description: "GET /19/ws/ping should ignore other messages",
+    },
+    TestInfo {
+        task: 1,
+        test: 3,
+        description: "GET /19/ws/ping should answer every \"ping\"",
+    },
+    TestInfo {
+        task: 2,
+        test: 1,
+        description: "POST /19/reset should reset the view count to 0",
+    },
+    TestInfo {
+        task: 2,
+        test: 2,
+        description: "A tweet should be broadcast to its sender and counted as a view",
+    },
+    TestInfo {
+        task: 2,
+        test: 3,
+        description: "A tweet over 128 characters should be ignored",
+    },
+    TestInfo {
+        task: 2,
+        test: 4,
+        description: "Tweets should only be broadcast within their room",
+    },
+    TestInfo {
+        task: 2,
+        test: 5,
+        description: "Closed connections should stop receiving tweets",
+    },
+    TestInfo {
+        task: 2,
+        test: 6,
+        description: "Users joining a room should only receive later tweets",
+    },
+    TestInfo {
+        task: 2,
+        test: 7,
+        description: "Many concurrent users should all count their views",
+    },
+];
+
+pub(crate) const CHALLENGE: ChallengeInfo = ChallengeInfo {
+    challenge: 19,
+    description: "Websocket ping and tweet rooms",
+    core: 1,
+    bonus: &[0, 500],
+    scored: true,
+    exclusive: true,
+    time_scaled: true,
+    tests: TESTS,
+    timeout: 180,
+    validator: &Day,
+};
+
+#[derive(Debug)]
+struct Day;
+
+impl Challenge for Day {
+    fn validate<'a>(
+        &'a self,
+        url: &'a str,
+        task: i32,
+        tx: Sender<SubmissionUpdate>,
+        tests: &'a mut TestTracker,
+    ) -> BoxFuture<'a, Option<ValidateResult>> {
+        Box::pin(async move {
+            Some(match task {
+                1 => task1(url, tx, tests).await,
+                2 => task2(url, tx, tests).await,
+                _ => return None,
+            })
+        })
+    }
+}
+
+/// The websocket URL of `base_url`, or a failure of `test` that says why there is none
+async fn ws_base_url(
+    base_url: &str,
+    test: TaskTest,
+    tx: &Sender<SubmissionUpdate>,
+) -> Result<String, TestFailure> {
+    match ws_url(base_url) {
+        Ok(url) => Ok(url),
+        Err(e) => {
+            try_update(tx, format!("Error: {e}")).await?;
+            Err(test.into())
+        }
+    }
+}
+
+async fn task1(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let mut test: TaskTest;
+    // TASK 1
+    test = tests.start((1, 1)).await;
+    let ws_base_url = ws_base_url(base_url, test, &tx).await?;
+    let mut ws = WS::new(test, format!("{}/19/ws/ping", ws_base_url)).await?;
+    ws.send("ping").await?;
+    ws.expect_silence(scaled(Duration::from_secs(1))).await?;
+    ws.send("serve").await?;
+    ws.send("ping").await?;
+    ws.recv_str("pong").await?;
+    test = tests.start((1, 2)).await;
+    ws.test = test;
+    ws.send("ding").await?;
+    ws.expect_silence(scaled(Duration::from_secs(1))).await?;
+    test = tests.start((1, 3)).await;
+    ws.test = test;
+    ws.send("ping").await?;
+    ws.send("ping").await?;
+    ws.recv_str("pong").await?;
+    ws.recv_str("pong").await?;
+    ws.expect_silence(scaled(Duration::from_millis(500)))
+        .await?;
+    ws.close().await?;
+    // TASK 1 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(1)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task2(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let mut test: TaskTest;
+    // TASK 2
+    let client = &shared_client();
+    let reset_url = &format!("{}/19/reset", base_url);
+    let reset = || async move {
+        let res = client
+            .post(reset_url)
+            .send_with_retry()
+            .await
+            .map_err(|_| ())?;
+        if res.status() != StatusCode::OK {
+            return Err(());
+        }
+        Ok(())
+    };
+    let views_url = &format!("{}/19/views", base_url);
+    // the views are counted as the tweets are broadcast, which can lag behind the received tweets
+    let ensure_views = |test: TaskTest, v: u64| async move {
+        let expected = v.to_string();
+        let deadline = Instant::now() + scaled(VIEWS_GRACE);
+        loop {
+            let res = client
+                .get(views_url)
+                .send_with_retry()
+                .await
+                .map_err(|_| test)?;
+            let text = res.text().await.map_err(|_| test)?;
+            if strictness().text(&text, &expected) == expected || Instant::now() >= deadline {
+                return expect_text(test, &text, &expected);
+            }
+            sleep(VIEWS_POLL_INTERVAL).await;
+        }
+    };
+
+    test = tests.start((2, 1)).await;
+    let ws_base_url = ws_base_url(base_url, test, &tx).await?;
+    reset().await.map_err(|_| test)?;
+    ensure_views(test, 0).await?;
+
+    test = tests.start((2, 2)).await;
+    let mut elon = WS::new(test, format!("{}/19/ws/room/1/user/elonmusk", ws_base_url)).await?;
+    let s = "Next I'm buying Coca-Cola to put the cocaine back in";
+    elon.send_tweet(s).await?;
+    elon.recv_json(&serde_json::json!({"user": "elonmusk", "message": s}))
+        .await?;
+    ensure_views(test, 1).await?;
+
+    test = tests.start((2, 3)).await;
+    elon.test = test;
+    let s = "I've concocted a whimsical idea to bring a bit of the ol' history back to life by attempting to put the cocaine back in Coca-Cola, rekindling the rebellious spirit of its original formulation";
+    elon.send_tweet(s).await?;
+    elon.expect_silence(scaled(Duration::from_secs(1))).await?;
+    ensure_views(test, 1).await?;
+    elon.close().await?;
+    sleep(scaled(Duration::from_millis(10))).await;
+
+    test = tests.start((2, 4)).await;
+    reset().await.map_err(|_| test)?;
+    ensure_views(test, 0).await?;
+    let mut a1 = WS::new(test, format!("{}/19/ws/room/44/user/annifrid", ws_base_url)).await?;
+    let mut b1 = WS::new(test, format!("{}/19/ws/room/55/user/bjorn", ws_base_url)).await?;
+    let mut b2 = WS::new(test, format!("{}/19/ws/room/55/user/benny", ws_base_url)).await?;
+    let mut a2 = WS::new(test, format!("{}/19/ws/room/44/user/agnetha", ws_base_url)).await?;
+    let l1 = "thank you for the music";
+    let l2 = "the songs i'm singing";
+    let l3 = "thanks for all";
+    let l4 = "the joy they're bringing";
+    let l5 = "who can live without it";
+    let l6 = "i ask in all honesty";
+    let x1 = "uhhhhhhhh?";
+    let x2 = "wazzaaaaa?";
+    a1.send_tweet(l1).await?;
+    sleep(scaled(Duration::from_millis(10))).await;
+    a2.send_tweet(l2).await?;
+    sleep(scaled(Duration::from_millis(10))).await;
+    a1.send_tweet(l3).await?;
+    sleep(scaled(Duration::from_millis(10))).await;
+    b1.send_tweet(x1).await?;
+    sleep(scaled(Duration::from_millis(10))).await;
+    a2.send_tweet(l4).await?;
+    sleep(scaled(Duration::from_millis(10))).await;
+    a1.send_tweet(l5).await?;
+    sleep(scaled(Duration::from_millis(10))).await;
+    a1.recv_json(&serde_json::json!({"user": "annifrid", "message": l1}))
+        .await?;
+    a2.recv_json(&serde_json::json!({"user": "annifrid", "message": l1}))
+        .await?;
+    a1.recv_json(&serde_json::json!({"user": "agnetha", "message": l2}))
+        .await?;
+    a2.recv_json(&serde_json::json!({"user": "agnetha", "message": l2}))
+        .await?;
+    a1.recv_json(&serde_json::json!({"user": "annifrid", "message": l3}))
+        .await?;
+    a2.recv_json(&serde_json::json!({"user": "annifrid", "message": l3}))
+        .await?;
+    a1.recv_json(&serde_json::json!({"user": "agnetha", "message": l4}))
+        .await?;
+    a2.recv_json(&serde_json::json!({"user": "agnetha", "message": l4}))
+        .await?;
+    a1.recv_json(&serde_json::json!({"user": "annifrid", "message": l5}))
+        .await?;
+    a2.recv_json(&serde_json::json!({"user": "annifrid", "message": l5}))
+        .await?;
+    ensure_views(test, 12).await?;
+
+    test = tests.start((2, 5)).await;
+    a1.close().await?;
+    sleep(scaled(Duration::from_millis(10))).await;
+    a2.send_tweet(l6).await?;
+    a2.recv_json(&serde_json::json!({"user": "agnetha", "message": l6}))
+        .await?;
+    ensure_views(test, 13).await?;
+
+    test = tests.start((2, 6)).await;
+    let mut a1 = WS::new(test, format!("{}/19/ws/room/55/user/annifrid", ws_base_url)).await?;
+    a1.expect_silence(scaled(Duration::from_secs(1))).await?;
+    b1.recv_json(&serde_json::json!({"user": "bjorn", "message": x1}))
+        .await?;
+    b2.recv_json(&serde_json::json!({"user": "bjorn", "message": x1}))
+        .await?;
+    a1.send_tweet(x2).await?;
+    sleep(scaled(Duration::from_millis(10))).await;
+    b1.close().await?;
+    a1.send_tweet(x2).await?;
+    b2.recv_json(&serde_json::json!({"user": "annifrid", "message": x2}))
+        .await?;
+    b2.recv_json(&serde_json::json!({"user": "annifrid", "message": x2}))
+        .await?;
+    a1.recv_json(&serde_json::json!({"user": "annifrid", "message": x2}))
+        .await?;
+    a1.recv_json(&serde_json::json!({"user": "annifrid", "message": x2}))
+        .await?;
+    ensure_views(test, 18).await?;
+
+    test = tests.start((2, 7)).await;
+    reset().await.map_err(|_| test)?;
+    ensure_views(test, 0).await?;
+    let phrases: Arc<[&str]> = phrases().into();
+    let mut joins = tokio::task::JoinSet::<ValidateResult>::new();
+    let mut tasks = vec![];
+    let views_url = Arc::new(views_url.clone());
+    let load = load();
+    let messages = load.messages as usize;
+    for i in 0..load.users {
+        let u = ws_base_url.clone();
+        let ps = phrases.clone();
+        let views_url = views_url.clone();
+        // the tasks are spawned, which leaves the client and time scale of the run behind
+        let client = client.clone();
+        let (tweet_pause, close_pause) = (
+            scaled(Duration::from_millis(150)),
+            scaled(Duration::from_secs(2)),
+        );
+        let mut user = WS::new(test, format!("{}/19/ws/room/1/user/{}", u, i)).await?;
+        tasks.push(async move {
+            for (ii, p) in ps.iter().cycle().take(messages).enumerate() {
+                user.send_tweet(*p).await?;
+                sleep(tweet_pause).await;
+                if i == 0 && ii == messages / 2 {
+                    client
+                        .get(views_url.deref())
+                        .send_with_retry()
+                        .await
+                        .map_err(|_| test)?;
+                }
+            }
+            sleep(close_pause).await;
+            user.close().await?;
+
+            Ok(())
+        });
+    }
+    for t in tasks.into_iter() {
+        joins.spawn(t);
+    }
+    while let Some(Ok(r)) = joins.join_next().await {
+        r?;
+    }
+    ensure_views(test, load.views()).await?;
+    // TASK 2 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(2)).await?;
+
+    Ok(())
+}
+
+/// The tweets that each user of the load test sends in turn
+fn phrases() -> Vec<&'static str> {
+    PHRASES.lines().filter(|p| !p.is_empty()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Load;
+
+    #[test]
+    fn every_tweet_of_the_load_test_is_counted() {
+        let phrases = phrases();
+        assert_eq!(phrases.len(), 100);
+        // longer tweets are ignored, see test 2.3
+        assert!(phrases.iter().all(|p| p.chars().count() <= 128));
+        // each user sees the tweets of every user, and the messages go around the phrases
+        for load in [
+            Load::default(),
+            Load {
+                users: 20,
+                messages: 250,
+            },
+        ] {
+            let tweets = phrases.iter().cycle().take(load.messages as usize).count() as u64;
+            assert_eq!(u64::from(load.users).pow(2) * tweets, load.views());
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use shuttlings::{try_update, SubmissionUpdate};
+use tokio::sync::mpsc::Sender;
+
+use crate::{
+    assets::{WithUpload, COOKIEJAR, LOTTERY, NORTHPOLE},
+    helpers::{expect_text, shared_client, SendWithRetry},
+    BoxFuture, Challenge, ChallengeInfo, TaskTest, TestInfo, TestTracker, ValidateResult,
+};
+
+pub(crate) const TESTS: &[TestInfo] = &[
+    TestInfo {
+        task: 1,
+        test: 1,
+        description: "POST /20/archive_files should count the files in the tar archive",
+    },
+    TestInfo {
+        task: 1,
+        test: 2,
+        description: "POST /20/archive_files_size should add up the file sizes in the tar archive",
+    },
+    TestInfo {
+        task: 2,
+        test: 1,
+        description: "POST /20/cookie should find the commit that added the cookie",
+    },
+    TestInfo {
+        task: 2,
+        test: 2,
+        description: "POST /20/cookie should find the cookie in another repository",
+    },
+];
+
+pub(crate) const CHALLENGE: ChallengeInfo = ChallengeInfo {
+    challenge: 20,
+    description: "Reading archives and git history",
+    core: 1,
+    bonus: &[0, 350],
+    scored: true,
+    exclusive: false,
+    time_scaled: false,
+    tests: TESTS,
+    timeout: 60,
+    validator: &Day,
+};
+
+#[derive(Debug)]
+struct Day;
+
+impl Challenge for Day {
+    fn validate<'a>(
+        &'a self,
+        url: &'a str,
+        task: i32,
+        tx: Sender<SubmissionUpdate>,
+        tests: &'a mut TestTracker,
+    ) -> BoxFuture<'a, Option<ValidateResult>> {
+        Box::pin(async move {
+            Some(match task {
+                1 => task1(url, tx, tests).await,
+                2 => task2(url, tx, tests).await,
+                _ => return None,
+            })
+        })
+    }
+}
+
+async fn task1(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    // TASK 1
+    test = tests.start((1, 1)).await;
+    let url = &format!("{}/20/archive_files", base_url);
+    let res = client
+        .post(url)
+        .upload(NORTHPOLE.upload(test, &tx).await?)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_text(test, &text, "6")?;
+    test = tests.start((1, 2)).await;
+    let url = &format!("{}/20/archive_files_size", base_url);
+    let res = client
+        .post(url)
+        .upload(NORTHPOLE.upload(test, &tx).await?)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_text(test, &text, "1196282")?;
+    // TASK 1 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(1)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task2(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    // TASK 2
+    test = tests.start((2, 1)).await;
+    let url = &format!("{}/20/cookie", base_url);
+    let res = client
+        .post(url)
+        .upload(COOKIEJAR.upload(test, &tx).await?)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_text(
+        test,
+        &text,
+        "Grinch 71dfab551a1958b35b7436c54b7455dcec99a12c",
+    )?;
+    test = tests.start((2, 2)).await;
+    let url = &format!("{}/20/cookie", base_url);
+    let res = client
+        .post(url)
+        .upload(LOTTERY.upload(test, &tx).await?)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_text(
+        test,
+        &text,
+        "elf-27221 6342c1dbdb560f0d5dcaac7566fca51454866664",
+    )?;
+    // TASK 2 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(2)).await?;
+
+    Ok(())
+}
@@ -0,0 +1,205 @@
+use shuttlings::{try_update, SubmissionUpdate};
+use tokio::sync::mpsc::Sender;
+
+use crate::{
+    helpers::{expect_text, shared_client, SendWithRetry},
+    BoxFuture, Challenge, ChallengeInfo, TaskTest, TestInfo, TestTracker, ValidateResult,
+};
+
+pub(crate) const TESTS: &[TestInfo] = &[
+    TestInfo {
+        task: 1,
+        test: 1,
+        description: "GET /21/coords should convert the cell in the Arctic",
+    },
+    TestInfo {
+        task: 1,
+        test: 2,
+        description: "GET /21/coords should convert a cell in the southern hemisphere",
+    },
+    TestInfo {
+        task: 1,
+        test: 3,
+        description: "GET /21/coords should convert a cell in the eastern hemisphere",
+    },
+    TestInfo {
+        task: 2,
+        test: 1,
+        description: "GET /21/country should return Madagascar",
+    },
+    TestInfo {
+        task: 2,
+        test: 2,
+        description: "GET /21/country should return Brunei",
+    },
+    TestInfo {
+        task: 2,
+        test: 3,
+        description: "GET /21/country should return Brazil",
+    },
+    TestInfo {
+        task: 2,
+        test: 4,
+        description: "GET /21/country should return Mongolia",
+    },
+    TestInfo {
+        task: 2,
+        test: 5,
+        description: "GET /21/country should return Nepal",
+    },
+    TestInfo {
+        task: 2,
+        test: 6,
+        description: "GET /21/country should return Belgium",
+    },
+    TestInfo {
+        task: 2,
+        test: 7,
+        description: "GET /21/country should return Iceland",
+    },
+];
+
+pub(crate) const CHALLENGE: ChallengeInfo = ChallengeInfo {
+    challenge: 21,
+    description: "S2 cell coordinates and countries",
+    core: 1,
+    bonus: &[0, 300],
+    scored: true,
+    exclusive: false,
+    time_scaled: false,
+    tests: TESTS,
+    timeout: 60,
+    validator: &Day,
+};
+
+#[derive(Debug)]
+struct Day;
+
+impl Challenge for Day {
+    fn validate<'a>(
+        &'a self,
+        url: &'a str,
+        task: i32,
+        tx: Sender<SubmissionUpdate>,
+        tests: &'a mut TestTracker,
+    ) -> BoxFuture<'a, Option<ValidateResult>> {
+        Box::pin(async move {
+            Some(match task {
+                1 => task1(url, tx, tests).await,
+                2 => task2(url, tx, tests).await,
+                _ => return None,
+            })
+        })
+    }
+}
+
+async fn task1(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    // TASK 1
+    test = tests.start((1, 1)).await;
+    let url = &format!(
+        "{}/21/coords/0100111110010011000110011001010101011111000010100011110001011011",
+        base_url
+    );
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_text(test, &text, "83°39'54.324''N 30°37'40.584''W")?;
+    test = tests.start((1, 2)).await;
+    let url = &format!(
+        "{}/21/coords/0010000111110000011111100000111010111100000100111101111011000101",
+        base_url
+    );
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_text(test, &text, "18°54'55.944''S 47°31'17.976''E")?;
+    test = tests.start((1, 3)).await;
+    let url = &format!(
+        "{}/21/coords/0101110100010001110001111100100111000111100010111100111101110001",
+        base_url
+    );
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_text(test, &text, "51°26'57.804''N 99°28'33.204''E")?;
+    // TASK 1 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(1)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task2(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    // TASK 2
+    test = tests.start((2, 1)).await;
+    let url = &format!(
+        "{}/21/country/0010000111110000011111100000111010111100000100111101111011000101",
+        base_url
+    );
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_text(test, &text, "Madagascar")?;
+    test = tests.start((2, 2)).await;
+    let url = &format!(
+        "{}/21/country/0011001000100010100010110001110100000111000010111000100000010101",
+        base_url
+    );
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_text(test, &text, "Brunei")?;
+    test = tests.start((2, 3)).await;
+    let url = &format!(
+        "{}/21/country/1001010011001110010011100110001000100110100111001001000100110001",
+        base_url
+    );
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_text(test, &text, "Brazil")?;
+    test = tests.start((2, 4)).await;
+    let url = &format!(
+        "{}/21/country/0101110100010001110001111100100111000111100010111100111101110001",
+        base_url
+    );
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_text(test, &text, "Mongolia")?;
+    test = tests.start((2, 5)).await;
+    let url = &format!(
+        "{}/21/country/0011100111101001000010001100001100111111101001100110000010101011",
+        base_url
+    );
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_text(test, &text, "Nepal")?;
+    test = tests.start((2, 6)).await;
+    let url = &format!(
+        "{}/21/country/0100011111000110101110101100011001101001111111001011000011101111",
+        base_url
+    );
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_text(test, &text, "Belgium")?;
+    test = tests.start((2, 7)).await;
+    let url = &format!(
+        "{}/21/country/0100111100110010101001010001010100100110110000100100101011011111",
+        base_url
+    );
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    let text = res.text().await.map_err(|_| test)?;
+    expect_text(test, &text, "Iceland")?;
+    // TASK 2 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(2)).await?;
+
+    Ok(())
+}
@@ -0,0 +1,654 @@
+use reqwest::StatusCode;
+use shuttlings::{try_update, SubmissionUpdate};
+use tokio::sync::mpsc::Sender;
+
+use crate::{
+    assets::NUMBERS, helpers::TextTester, BoxFuture, Challenge, ChallengeInfo, TestInfo,
+    TestTracker, ValidateResult,
+};
+
+pub(crate) const TESTS: &[TestInfo] = &[
+    TestInfo {
+        task: 1,
+        test: 1,
+        description: "POST /22/integers with a single number should return one present",
+    },
+    TestInfo {
+        task: 1,
+        test: 2,
+        description: "POST /22/integers should find the number without a pair",
+    },
+    TestInfo {
+        task: 1,
+        test: 3,
+        description: "POST /22/integers should find the unpaired number in any order",
+    },
+    TestInfo {
+        task: 1,
+        test: 4,
+        description: "POST /22/integers should handle large numbers",
+    },
+    TestInfo {
+        task: 1,
+        test: 5,
+        description: "POST /22/integers should handle a long list of numbers",
+    },
+    TestInfo {
+        task: 2,
+        test: 1,
+        description: "POST /22/rocket with two stars should find a path of 1 portal",
+    },
+    TestInfo {
+        task: 2,
+        test: 2,
+        description: "POST /22/rocket should follow a chain of portals",
+    },
+    TestInfo {
+        task: 2,
+        test: 3,
+        description: "POST /22/rocket should find the shortest path of portals",
+    },
+    TestInfo {
+        task: 2,
+        test: 4,
+        description: "POST /22/rocket should take a direct portal",
+    },
+    TestInfo {
+        task: 2,
+        test: 5,
+        description: "POST /22/rocket should take a direct portal among others",
+    },
+    TestInfo {
+        task: 2,
+        test: 6,
+        description: "POST /22/rocket with 21 stars should find the shortest path",
+    },
+    TestInfo {
+        task: 2,
+        test: 7,
+        description: "POST /22/rocket with 75 stars should find the shortest path",
+    },
+    TestInfo {
+        task: 2,
+        test: 8,
+        description: "POST /22/rocket with 70 stars should find the shortest path",
+    },
+];
+
+pub(crate) const CHALLENGE: ChallengeInfo = ChallengeInfo {
+    challenge: 22,
+    description: "Finding the odd present and plotting a rocket route",
+    core: 1,
+    bonus: &[0, 600],
+    scored: true,
+    exclusive: false,
+    time_scaled: false,
+    tests: TESTS,
+    timeout: 60,
+    validator: &Day,
+};
+
+#[derive(Debug)]
+struct Day;
+
+impl Challenge for Day {
+    fn validate<'a>(
+        &'a self,
+        url: &'a str,
+        task: i32,
+        tx: Sender<SubmissionUpdate>,
+        tests: &'a mut TestTracker,
+    ) -> BoxFuture<'a, Option<ValidateResult>> {
+        Box::pin(async move {
+            Some(match task {
+                1 => task1(url, tx, tests).await,
+                2 => task2(url, tx, tests).await,
+                _ => return None,
+            })
+        })
+    }
+}
+
+async fn task1(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    // TASK 1
+    let t = TextTester::new(format!("{}/22/integers", base_url));
+    t.test(
+        tests.start((1, 1)).await,
+        "\
+1
+",
+        StatusCode::OK,
+        "🎁",
+    )
+    .await?;
+    t.test(
+        tests.start((1, 2)).await,
+        "\
+1
+1
+2
+2
+3
+3
+4
+",
+        StatusCode::OK,
+        "🎁".repeat(4).as_str(),
+    )
+    .await?;
+    t.test(
+        tests.start((1, 3)).await,
+        "\
+1
+3
+1
+2
+4
+2
+3
+",
+        StatusCode::OK,
+        "🎁".repeat(4).as_str(),
+    )
+    .await?;
+    t.test(
+        tests.start((1, 4)).await,
+        "\
+11111111111111111111
+555555555555555
+33333333
+68
+555555555555555
+33333333
+4444
+11111111111111111111
+4444
+",
+        StatusCode::OK,
+        "🎁".repeat(68).as_str(),
+    )
+    .await?;
+    let test = tests.start((1, 5)).await;
+    t.test_upload(
+        test,
+        NUMBERS.upload(test, &tx).await?,
+        StatusCode::OK,
+        "🎁".repeat(120003).as_str(),
+    )
+    .await?;
+    // TASK 1 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(1)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task2(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    // TASK 2
+    let t = TextTester::new(format!("{}/22/rocket", base_url));
+    t.test(
+        tests.start((2, 1)).await,
+        "\
+2
+0 0 0
+0 0 1
+1
+0 1
+",
+        StatusCode::OK,
+        "1 1.000",
+    )
+    .await?;
+    t.test(
+        tests.start((2, 2)).await,
+        "\
+5
+0 1 0
+-2 2 3
+3 -3 -5
+1 1 5
+4 3 5
+4
+0 1
+2 4
+3 4
+1 2
+",
+        StatusCode::OK,
+        "3 26.123",
+    )
+    .await?;
+    t.test(
+        tests.start((2, 3)).await,
+        "\
+5
+0 1 0
+-2 2 3
+3 -3 -5
+1 1 5
+4 3 5
+5
+0 1
+1 3
+3 4
+0 2
+2 4
+",
+        StatusCode::OK,
+        "2 18.776",
+    )
+    .await?;
+    t.test(
+        tests.start((2, 4)).await,
+        "\
+5
+0 1 0
+-2 2 3
+3 -3 -5
+1 1 5
+4 3 5
+1
+0 4
+",
+        StatusCode::OK,
+        "1 6.708",
+    )
+    .await?;
+    t.test(
+        tests.start((2, 5)).await,
+        "\
+5
+0 1 0
+-2 2 3
+3 -3 -5
+1 1 5
+4 3 5
+5
+0 4
+0 1
+1 2
+2 0
+0 3
+",
+        StatusCode::OK,
+        "1 6.708",
+    )
+    .await?;
+    t.test(
+        tests.start((2, 6)).await,
+        "\
+21
+570 -435 923
+672 -762 -218
+707 16 640
+311 902 47
+-963 -399 -773
+788 532 -704
+703 475 -145
+-303 -394 -369
+699 -640 952
+-341 -221 743
+740 -146 544
+-424 655 179
+-630 161 690
+789 -848 -517
+-14 -893 551
+-48 815 962
+528 552 -96
+337 983 165
+-565 459 -90
+81 -476 301
+-685 -319 698
+24
+0 2
+2 4
+4 6
+6 10
+10 17
+17 20
+20 18
+18 11
+11 7
+7 5
+5 3
+3 0
+0 1
+1 12
+12 13
+13 19
+19 20
+20 16
+16 14
+14 15
+15 9
+9 8
+8 6
+11 16
+",
+        StatusCode::OK,
+        "5 7167.055",
+    )
+    .await?;
+    t.test(
+        tests.start((2, 7)).await,
+        "\
+75
+570 -435 923
+672 -762 -218
+707 16 640
+311 902 47
+-963 -399 -773
+788 532 -704
+703 475 -145
+-303 -394 -369
+699 -640 952
+-341 -221 743
+740 -146 544
+-424 655 179
+-630 161 690
+789 -848 -517
+-14 -893 551
+-48 815 962
+528 552 -96
+337 983 165
+-565 459 -90
+81 -476 301
+-685 -319 698
+-264 96 361
+796 94 402
+983 763 -953
+711 -221 -866
+-578 128 -178
+-464 117 304
+426 -433 -961
+-626 -779 -596
+-117 -88 349
+880 -286 -527
+941 -451 177
+627 -832 286
+593 370 -436
+609 431 -681
+-549 -690 447
+957 849 -162
+189 290 -485
+-914 -447 -61
+367 731 825
+-177 432 -675
+-926 -811 198
+-379 345 831
+-669 -134 804
+956 380 -427
+213 -954 -357
+-806 -663 583
+7 -460 374
+-384 -797 -404
+-793 -333 196
+402 175 329
+703 9 -926
+599 559 -844
+64 343 885
+-865 -49 -373
+-728 880 -164
+830 528 -394
+931 -782 -365
+661 -528 931
+-764 34 -289
+442 298 983
+-899 382 -967
+662 361 -85
+775 98 -519
+202 335 60
+474 823 -677
+-708 41 127
+-974 718 81
+443 -526 -945
+-279 778 -271
+896 26 -902
+-977 -233 837
+151 -22 -454
+824 -472 471
+702 871 -244
+73
+0 1
+0 2
+0 4
+0 5
+0 7
+1 10
+10 11
+11 25
+12 13
+13 27
+14 29
+15 30
+16 17
+17 35
+18 36
+19 6
+2 3
+20 22
+21 19
+22 40
+23 60
+24 42
+25 26
+26 43
+27 28
+28 45
+29 47
+3 12
+30 31
+31 68
+32 50
+34 16
+35 52
+36 54
+37 38
+38 57
+39 21
+4 14
+40 59
+41 23
+42 61
+43 44
+44 63
+45 64
+46 65
+47 46
+49 32
+49 68
+5 15
+50 33
+51 34
+52 70
+54 73
+55 37
+56 74
+57 56
+58 39
+59 58
+6 18
+63 62
+65 66
+66 67
+67 48
+69 51
+7 20
+70 71
+71 72
+72 53
+73 55
+8 1
+8 9
+9 23
+9 24
+",
+        StatusCode::OK,
+        "20 27826.439",
+    )
+    .await?;
+    t.test(
+        tests.start((2, 8)).await,
+        "\
+70
+788 532 -704
+703 475 -145
+-303 -394 -369
+699 -640 952
+-341 -221 743
+740 -146 544
+-424 655 179
+-630 161 690
+789 -848 -517
+-14 -893 551
+-48 815 962
+528 552 -96
+337 983 165
+-565 459 -90
+81 -476 301
+-685 -319 698
+-264 96 361
+796 94 402
+983 763 -953
+711 -221 -866
+-578 128 -178
+-464 117 304
+426 -433 -961
+-626 -779 -596
+-117 -88 349
+880 -286 -527
+941 -451 177
+627 -832 286
+593 370 -436
+609 431 -681
+-549 -690 447
+957 849 -162
+189 290 -485
+-914 -447 -61
+367 731 825
+-177 432 -675
+-926 -811 198
+-379 345 831
+-669 -134 804
+956 380 -427
+213 -954 -357
+-806 -663 583
+7 -460 374
+-384 -797 -404
+-793 -333 196
+402 175 329
+703 9 -926
+599 559 -844
+64 343 885
+-865 -49 -373
+-728 880 -164
+830 528 -394
+931 -782 -365
+661 -528 931
+-764 34 -289
+442 298 983
+-899 382 -967
+662 361 -85
+775 98 -519
+202 335 60
+474 823 -677
+-708 41 127
+-974 718 81
+443 -526 -945
+-279 778 -271
+896 26 -902
+-977 -233 837
+151 -22 -454
+824 -472 471
+702 871 -244
+70
+0 10
+0 2
+0 3
+1 22
+10 21
+11 1
+12 11
+13 27
+14 15
+15 4
+16 31
+17 33
+18 19
+19 7
+2 12
+20 53
+21 37
+22 39
+23 40
+24 23
+25 24
+26 25
+27 26
+27 6
+28 14
+29 30
+3 13
+30 46
+30 69
+31 47
+33 18
+33 48
+34 33
+35 34
+37 20
+38 55
+39 56
+39 57
+4 16
+40 59
+41 60
+42 62
+43 63
+44 28
+44 65
+45 29
+46 68
+47 32
+48 49
+49 50
+5 17
+50 51
+51 35
+53 36
+55 54
+56 38
+57 58
+59 41
+6 5
+60 61
+61 42
+62 43
+63 64
+64 44
+65 45
+67 66
+68 67
+7 9
+8 0
+9 8
+",
+        StatusCode::OK,
+        "23 34029.320",
+    )
+    .await?;
+    // TASK 2 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(2)).await?;
+
+    Ok(())
+}
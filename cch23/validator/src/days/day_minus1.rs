@@ -0,0 +1,129 @@
+use reqwest::StatusCode;
+use shuttlings::{try_update, SubmissionUpdate};
+use tokio::sync::mpsc::Sender;
+
+use crate::{
+    helpers::{expect_status, shared_client, SendWithRetry},
+    BoxFuture, Challenge, ChallengeInfo, TestInfo, TestTracker, ValidateResult,
+};
+
+pub(crate) const TESTS: &[TestInfo] = &[
+    TestInfo {
+        task: 1,
+        test: 1,
+        description: "GET / should return 200 OK",
+    },
+    TestInfo {
+        task: 2,
+        test: 1,
+        description: "GET /-1/error should return 500 Internal Server Error",
+    },
+];
+
+pub(crate) const CHALLENGE: ChallengeInfo = ChallengeInfo {
+    challenge: -1,
+    description: "Warmup with a hello world and an error endpoint",
+    core: 1,
+    bonus: &[0, 0],
+    // the warmup does not count towards the score
+    scored: false,
+    exclusive: false,
+    time_scaled: false,
+    tests: TESTS,
+    timeout: 20,
+    validator: &Day,
+};
+
+#[derive(Debug)]
+struct Day;
+
+impl Challenge for Day {
+    fn validate<'a>(
+        &'a self,
+        url: &'a str,
+        task: i32,
+        tx: Sender<SubmissionUpdate>,
+        tests: &'a mut TestTracker,
+    ) -> BoxFuture<'a, Option<ValidateResult>> {
+        Box::pin(async move {
+            Some(match task {
+                1 => task1(url, tx, tests).await,
+                2 => task2(url, tx, tests).await,
+                _ => return None,
+            })
+        })
+    }
+}
+
+async fn task1(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    // TASK 1: respond 200
+    let test = tests.start((1, 1)).await;
+    let url = &format!("{}/", base_url);
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    expect_status(test, res.status(), StatusCode::OK)?;
+    // TASK 1 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(1)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task2(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    // TASK 2: respond 500
+    let test = tests.start((2, 1)).await;
+    let url = &format!("{}/-1/error", base_url);
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    expect_status(test, res.status(), StatusCode::INTERNAL_SERVER_ERROR)?;
+    // TASK 2 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(2)).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::Method;
+    use shuttlings::transport::MockTransport;
+
+    use super::*;
+    use crate::{tests::run_mocked, RunOutcome};
+
+    #[tokio::test]
+    async fn task1_expects_ok() {
+        let mock = MockTransport::new().canned(Method::GET, "/", StatusCode::OK, "");
+        assert_eq!(run_mocked(-1, 1, mock).await, RunOutcome::Completed);
+        let mock = MockTransport::new().canned(Method::GET, "/", StatusCode::NOT_FOUND, "");
+        assert_eq!(
+            run_mocked(-1, 1, mock).await,
+            RunOutcome::Failed { task: 1, test: 1 }
+        );
+    }
+
+    #[tokio::test]
+    async fn task2_expects_an_error() {
+        let mock = MockTransport::new().canned(
+            Method::GET,
+            "/-1/error",
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "",
+        );
+        assert_eq!(run_mocked(-1, 2, mock).await, RunOutcome::Completed);
+        let mock = MockTransport::new().canned(Method::GET, "/-1/error", StatusCode::OK, "");
+        assert_eq!(
+            run_mocked(-1, 2, mock).await,
+            RunOutcome::Failed { task: 2, test: 1 }
+        );
+    }
+}
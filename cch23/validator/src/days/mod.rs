@@ -0,0 +1,17 @@
+pub(crate) mod day01;
+pub(crate) mod day04;
+pub(crate) mod day05;
+pub(crate) mod day06;
+pub(crate) mod day07;
+pub(crate) mod day08;
+pub(crate) mod day11;
+pub(crate) mod day12;
+pub(crate) mod day13;
+pub(crate) mod day14;
+pub(crate) mod day15;
+pub(crate) mod day18;
+pub(crate) mod day19;
+pub(crate) mod day20;
+pub(crate) mod day21;
+pub(crate) mod day22;
+pub(crate) mod day_minus1;
@@ -0,0 +1,559 @@
+use std::path::Path;
+
+use reqwest::{
+    header::{HeaderMap, HeaderName, CONTENT_ENCODING, CONTENT_LENGTH},
+    ResponseBuilderExt, StatusCode,
+};
+use shuttlings::{
+    client::{self, resolved},
+    har::{self, Har},
+    testing::{self, Strictness, TestError, Tolerance, DEFAULT_MAX_RESPONSE_SIZE},
+    transport::{Transport, TransportError},
+};
+use tokio::time::{sleep, Duration, Instant};
+use tracing::info;
+
+use crate::{
+    assets::{Upload, WithUpload},
+    proxy, tls, HttpVersion, Load, Proxy, Redirects, Resolve, TaskTest, TestFailure,
+    TlsVerification, ValidateResult, DEFAULT_RETRIES, RUN,
+};
+
+pub(crate) trait SendWithRetry {
+    /// Send the request, retrying with backoff if the connection could not be established,
+    /// or was dropped while an idempotent request like a GET was on it.
+    /// Responses are never retried, whatever their status.
+    /// The body of the response is read before it is returned, and the time it all took counts towards the current test.
+    /// A body longer than [`crate::ValidationConfig::max_response_size`] is an error, which the failure of the test reports.
+    /// A run with its own transport sends the request through that instead, see [`crate::ValidationConfig::transport`].
+    async fn send_with_retry(self) -> Result<reqwest::Response, TestError>;
+}
+
+impl SendWithRetry for reqwest::RequestBuilder {
+    async fn send_with_retry(self) -> Result<reqwest::Response, TestError> {
+        let started = Instant::now();
+        let res = match send(self).await {
+            Ok((res, recording, requested)) => {
+                let redirected = match requested {
+                    Some(url) => check_redirect(&url, res.url()),
+                    None => Ok(()),
+                };
+                buffered(res, recording)
+                    .await
+                    .and_then(|res| redirected.map(|()| res))
+            }
+            // the test only sees that the request failed
+            Err(TransportError::Unknown(e)) => {
+                let _ = RUN.try_with(|r| r.request_error.set(Some(e.clone())));
+                Err(TestError::Request(e))
+            }
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e @ (TestError::TooLarge { .. } | TestError::Decompression { .. })) = &res {
+            let _ = RUN.try_with(|r| r.request_error.set(Some(e.to_string())));
+        }
+        let _ = RUN.try_with(|r| r.request_time.set(r.request_time.get() + started.elapsed()));
+        res
+    }
+}
+
+/// Send the request, with the retries of [`SendWithRetry::send_with_retry`].
+/// The response comes with the URL of the request if the client sent it,
+/// since only the client follows redirects, while another transport answers the request itself.
+async fn send(
+    req: reqwest::RequestBuilder,
+) -> Result<(reqwest::Response, Recording, Option<reqwest::Url>), TransportError> {
+    let (client, req) = req.build_split();
+    let mut req = req?;
+    let _ = RUN.try_with(|r| {
+        if let Some(endpoints) = &r.endpoints {
+            endpoints.add(req.method(), req.url().path());
+        }
+    });
+    let transport = RUN.try_with(|r| r.transport.clone()).ok().flatten();
+    let requested = transport.is_none().then(|| req.url().clone());
+    let transport = transport.as_deref().unwrap_or(&client);
+    let with_url =
+        |res: Result<_, _>| res.map(|(res, recording)| (res, recording, requested.clone()));
+    let retries = RUN.try_with(|r| r.retries).unwrap_or(DEFAULT_RETRIES);
+    let mut backoff = Duration::from_millis(250);
+    for _ in 0..retries {
+        // streaming bodies can't be cloned, so those requests are sent only once
+        let Some(next) = req.try_clone() else {
+            break;
+        };
+        match execute(transport, req).await {
+            Err(TransportError::Http(e)) if client::is_transient(&e, next.method()) => {
+                info!(error = %e, "Retrying request in {backoff:?}");
+                sleep(backoff).await;
+                backoff *= 2;
+                req = next;
+            }
+            res => return with_url(res),
+        }
+    }
+    let res = execute(transport, req).await;
+    if matches!(&res, Err(TransportError::Http(e)) if e.is_connect()) {
+        let _ = RUN.try_with(|r| r.connection_failed.set(true));
+    }
+    with_url(res)
+}
+
+/// Check that the response to a request of `url`, which came from `to`, did not arrive through a redirect.
+/// One that did is a warning, and fails the test in a strict run, see [`crate::Redirects::Follow`].
+fn check_redirect(url: &reqwest::Url, to: &reqwest::Url) -> Result<(), TestError> {
+    if url == to {
+        return Ok(());
+    }
+    let e = TestError::Mismatch {
+        expected: format!("a response from {}", url.path()),
+        actual: format!("a redirect to {to}"),
+    };
+    if strictness() == Strictness::Strict {
+        let _ = RUN.try_with(|r| r.request_error.set(Some(e.to_string())));
+        return Err(e);
+    }
+    let _ = RUN.try_with(|r| r.warnings.borrow_mut().push(e.to_string()));
+    Ok(())
+}
+
+/// Send a probe of [`crate::ValidationConfig::probe`] once, without retries, and read its response
+pub(crate) async fn send_probe(req: reqwest::RequestBuilder) -> Result<StatusCode, TransportError> {
+    let (client, req) = req.build_split();
+    let transport = RUN.try_with(|r| r.transport.clone()).ok().flatten();
+    let transport = transport.as_deref().unwrap_or(&client);
+    let (res, recording) = execute(transport, req?).await?;
+    let status = res.status();
+    // a body that is too long is not a problem of a probe
+    let _ = buffered(res, recording).await;
+    Ok(status)
+}
+
+/// Send `req` once. A failed attempt is recorded right away, a response once its body is read.
+async fn execute(
+    transport: &dyn Transport,
+    req: reqwest::Request,
+) -> Result<(reqwest::Response, Recording), TransportError> {
+    let recording = Recording::request(&req);
+    match transport.execute(req).await {
+        Ok(res) => Ok((res, recording)),
+        Err(e) => {
+            recording.finish(Err(e.to_string()));
+            Err(e)
+        }
+    }
+}
+
+/// The entry of a request in the HAR file of the run, if there is one, see [`crate::ValidationConfig::har`]
+struct Recording(Option<(Har, har::Pending)>);
+
+impl Recording {
+    fn request(req: &reqwest::Request) -> Self {
+        let har = RUN.try_with(|r| r.har.clone()).ok().flatten();
+        Self(har.map(|har| (har, har::Pending::request(req))))
+    }
+
+    fn finish(self, res: Result<har::Response<'_>, String>) {
+        if let Some((har, pending)) = self.0 {
+            har.record(pending, res);
+        }
+    }
+}
+
+/// The same response, with its body already read
+async fn buffered(
+    res: reqwest::Response,
+    recording: Recording,
+) -> Result<reqwest::Response, TestError> {
+    let (status, version, headers) = (res.status(), res.version(), res.headers().clone());
+    let url = res.url().clone();
+    let limit = RUN
+        .try_with(|r| r.max_response_size)
+        .unwrap_or(DEFAULT_MAX_RESPONSE_SIZE);
+    let compression = testing::compression(&headers);
+    let body = match testing::read_body(res, limit).await {
+        Ok(body) => match compression {
+            Some(encoding) => testing::decompress(encoding, &body, limit),
+            None => Ok(body),
+        },
+        e => e,
+    };
+    // with the headers as they were sent, and the body decompressed like the content of a HAR file is
+    recording.finish(match &body {
+        Ok(body) => Ok(har::Response {
+            status,
+            version,
+            headers: &headers,
+            body,
+        }),
+        Err(e) => Err(e.to_string()),
+    });
+    let mut buffered = http::Response::builder()
+        .status(status)
+        .version(version)
+        .url(url);
+    if let Some(h) = buffered.headers_mut() {
+        *h = headers;
+    }
+    let body = body?;
+    // the response is checked as if it was not compressed
+    if let (Some(encoding), Some(h)) = (compression, buffered.headers_mut()) {
+        h.remove(CONTENT_ENCODING);
+        h.insert(CONTENT_LENGTH, body.len().into());
+        let _ = RUN.try_with(|r| r.compressed.set(Some(encoding)));
+    }
+    Ok(buffered
+        .body(body)
+        .expect("the parts of a response to be valid")
+        .into())
+}
+
+/// The client of the current run, so that the requests of a challenge share its connection pool.
+/// Outside of a run, a new client.
+pub(crate) fn shared_client() -> reqwest::Client {
+    RUN.try_with(|r| r.client.clone()).unwrap_or_else(|_| {
+        new_client(
+            &TlsVerification::Default,
+            &Proxy::FromEnv,
+            HttpVersion::Http1,
+            Redirects::default(),
+            None,
+            &[],
+        )
+        .expect("a client with the default settings")
+    })
+}
+
+/// The factor that the pauses of the current run are multiplied by, see [`crate::ValidationConfig::time_scale`]
+pub(crate) fn time_scale() -> f64 {
+    RUN.try_with(|r| r.time_scale).unwrap_or(1.0)
+}
+
+/// The load test of challenge 19 in the current run, see [`crate::ValidationConfig::load`]
+pub(crate) fn load() -> Load {
+    RUN.try_with(|r| r.load).unwrap_or_default()
+}
+
+/// A generator of the data of the current run, forked from the one that its seed started,
+/// so that a run with the same [`crate::ValidationConfig::seed`] generates the same data.
+/// Outside of a run, a randomly seeded one.
+pub(crate) fn rng() -> fastrand::Rng {
+    RUN.try_with(|r| r.rng.borrow_mut().fork())
+        .unwrap_or_else(|_| fastrand::Rng::new())
+}
+
+/// How closely the responses of the current run have to follow the challenge text, see [`crate::ValidationConfig::strictness`]
+pub(crate) fn strictness() -> Strictness {
+    RUN.try_with(|r| r.strictness).unwrap_or_default()
+}
+
+/// `pause` multiplied by the time scale of the current run
+pub(crate) fn scaled(pause: Duration) -> Duration {
+    pause.mul_f64(time_scale())
+}
+
+/// How long the request before a run may take, see [`preflight`]
+const PREFLIGHT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Send a GET request to the base URL. Any response will do, whatever its status.
+/// On a transport error, returns why the request could not be sent, e.g. `connection refused`.
+pub(crate) async fn preflight(url: &str) -> Result<(), String> {
+    let e = match shared_client()
+        .get(url)
+        .timeout(PREFLIGHT_TIMEOUT)
+        .send()
+        .await
+    {
+        Ok(_) => return Ok(()),
+        Err(e) if e.is_timeout() => {
+            return Err(format!("no response within {PREFLIGHT_TIMEOUT:?}"))
+        }
+        Err(e) => e,
+    };
+    Err(testing::failure_reason(&e))
+}
+
+/// The websocket URL of `base_url`: ws for http and wss for https, with the same host, port and path prefix
+pub(crate) fn ws_url(base_url: &str) -> Result<String, String> {
+    let mut url =
+        url::Url::parse(base_url).map_err(|e| format!("`{base_url}` is not a valid URL: {e}"))?;
+    let scheme = match url.scheme() {
+        "http" => "ws",
+        "https" => "wss",
+        other => {
+            return Err(format!(
+                "`{base_url}` has the scheme `{other}`, which has no websocket equivalent"
+            ))
+        }
+    };
+    url.set_scheme(scheme)
+        .map_err(|()| format!("`{base_url}` can't be turned into a websocket URL"))?;
+    Ok(url.as_str().trim_end_matches('/').to_owned())
+}
+
+/// A client with these settings, see [`client::builder`]
+pub(crate) fn new_client(
+    tls: &TlsVerification,
+    proxy: &Proxy,
+    http_version: HttpVersion,
+    redirects: Redirects,
+    unix_socket: Option<&Path>,
+    resolve: &[Resolve],
+) -> Result<reqwest::Client, String> {
+    client::builder(tls, proxy, http_version, redirects, unix_socket, resolve)?
+        .build()
+        .map_err(|e| format!("Failed to build the client: {e}"))
+}
+
+pub(crate) fn expect_status(
+    test: TaskTest,
+    actual: StatusCode,
+    expected: StatusCode,
+) -> ValidateResult {
+    testing::check_status(actual, expected).map_err(|e| TestFailure::failed(test, e))
+}
+
+pub(crate) fn expect_headers(
+    test: TaskTest,
+    actual: &HeaderMap,
+    expected: &[(HeaderName, &str)],
+) -> ValidateResult {
+    testing::check_headers(actual, expected).map_err(|e| TestFailure::failed(test, e))
+}
+
+/// Expect the response to have the media type that the challenge requires.
+/// A different one only fails the test in a strict run, and is a warning otherwise, see [`crate::ValidationConfig::strictness`].
+pub(crate) fn expect_content_type(
+    test: TaskTest,
+    actual: &HeaderMap,
+    expected: &str,
+) -> ValidateResult {
+    let Err(e) = testing::check_content_type(actual, expected) else {
+        return Ok(());
+    };
+    if strictness() == Strictness::Strict {
+        return Err(TestFailure::failed(test, e));
+    }
+    let _ = RUN.try_with(|r| r.warnings.borrow_mut().push(e.to_string()));
+    Ok(())
+}
+
+/// Expect the text `actual`, compared as the strictness of the run says
+pub(crate) fn expect_text(test: TaskTest, actual: &str, expected: &str) -> ValidateResult {
+    testing::check_text(strictness().text(actual, expected), expected)
+        .map_err(|e| TestFailure::failed(test, e))
+}
+
+pub(crate) fn expect_json(
+    test: TaskTest,
+    actual: &serde_json::Value,
+    expected: &serde_json::Value,
+) -> ValidateResult {
+    testing::check_json(actual, expected).map_err(|e| TestFailure::failed(test, e))
+}
+
+/// Expect `actual` to be a number within `tol` of `expected`, which a lenient run widens
+pub(crate) fn expect_number(
+    test: TaskTest,
+    actual: &str,
+    expected: f64,
+    tol: Tolerance,
+) -> ValidateResult {
+    let s = strictness();
+    // a number never ends with a newline
+    testing::check_float_near(s.text(actual, ""), expected, s.tolerance(tol))
+        .map_err(|e| TestFailure::failed(test, e))
+}
+
+/// A [`testing::JSONTester`] that sends its requests with the client and retries of the run
+pub(crate) struct JSONTester(testing::JSONTester);
+
+impl JSONTester {
+    pub(crate) fn new(url: String) -> Self {
+        Self(testing::JSONTester::new(url).client(shared_client()))
+    }
+    pub(crate) async fn test(
+        &self,
+        test: TaskTest,
+        i: &serde_json::Value,
+        code: StatusCode,
+        o: &serde_json::Value,
+    ) -> ValidateResult {
+        let res = self
+            .0
+            .request(i)
+            .send_with_retry()
+            .await
+            .map_err(|_| test)?;
+        testing::JSONTester::check(res, code, &[], o)
+            .await
+            .map_err(|e| TestFailure::failed(test, e))
+    }
+}
+
+/// A [`testing::WS`] that connects like the run says, and fails `test` when a check fails
+pub(crate) struct WS {
+    pub(crate) test: TaskTest,
+    ws: testing::WS,
+}
+
+impl WS {
+    pub(crate) async fn new(test: TaskTest, url: String) -> Result<Self, TaskTest> {
+        let (connector, proxy, unix_socket, resolved) = RUN
+            .try_with(|r| {
+                let (proxy, resolved) =
+                    (proxy::for_url(&url, &r.proxy), resolved(&url, &r.resolve));
+                let unix_socket = r.unix_socket.clone();
+                (tls::ws_connector(&r.tls), proxy, unix_socket, resolved)
+            })
+            .unwrap_or_default();
+        if RUN.try_with(|r| r.transport.is_some()).unwrap_or_default() {
+            let e = "websocket connections can't go through the transport of this run".to_owned();
+            let _ = RUN.try_with(|r| r.request_error.set(Some(e)));
+            return Err(test);
+        }
+        let har = RUN.try_with(|r| r.har.clone()).ok().flatten();
+        let pending = har::Pending::websocket(&url);
+        let res = match (unix_socket, proxy, resolved) {
+            // normalize_url only takes Unix sockets on Unix
+            #[cfg(unix)]
+            (Some(path), _, _) => match tokio::net::UnixStream::connect(&path).await {
+                Ok(stream) => {
+                    tokio_tungstenite::client_async_tls_with_config(url, stream, None, connector)
+                        .await
+                        .map(|(s, res)| (testing::WS::from_stream(s), res))
+                        .map_err(|e| e.to_string())
+                }
+                Err(e) => Err(format!("Failed to connect to {}: {e}", path.display())),
+            },
+            // tokio-tungstenite can't use a proxy, so the connection goes through a tunnel
+            (_, Some(proxy), _) => match proxy::tunnel(&proxy, &url).await {
+                Ok(stream) => {
+                    tokio_tungstenite::client_async_tls_with_config(url, stream, None, connector)
+                        .await
+                        .map(|(s, res)| (testing::WS::from_stream(s), res))
+                        .map_err(|e| e.to_string())
+                }
+                Err(e) => Err(e.to_string()),
+            },
+            // the TLS server name and the Host header stay the host of the URL
+            (_, None, Some(addr)) => match tokio::net::TcpStream::connect(addr).await {
+                Ok(stream) => {
+                    tokio_tungstenite::client_async_tls_with_config(url, stream, None, connector)
+                        .await
+                        .map(|(s, res)| (testing::WS::from_stream(s), res))
+                        .map_err(|e| e.to_string())
+                }
+                Err(e) => Err(format!("Failed to connect to {addr}: {e}")),
+            },
+            (_, None, None) => {
+                tokio_tungstenite::connect_async_tls_with_config(url, None, false, connector)
+                    .await
+                    .map(|(s, res)| (testing::WS::from_stream(s), res))
+                    .map_err(|e| e.to_string())
+            }
+        };
+        if let Some(har) = har {
+            har.record(
+                pending,
+                match &res {
+                    Ok((_, handshake)) => Ok(har::Response {
+                        status: handshake.status(),
+                        version: handshake.version(),
+                        headers: handshake.headers(),
+                        body: handshake.body().as_deref().unwrap_or_default(),
+                    }),
+                    Err(e) => Err(e.clone()),
+                },
+            );
+        }
+        let (ws, _) = res.map_err(|_| test)?;
+
+        Ok(Self { test, ws })
+    }
+
+    fn failed(&self, e: TestError) -> TestFailure {
+        TestFailure::failed(self.test, e)
+    }
+
+    pub(crate) async fn send(&mut self, msg: impl Into<String>) -> ValidateResult {
+        self.ws.send(msg).await.map_err(|e| self.failed(e))
+    }
+
+    pub(crate) async fn send_tweet(&mut self, msg: impl Into<String>) -> ValidateResult {
+        self.ws
+            .send_json(&serde_json::json!({"message": msg.into()}))
+            .await
+            .map_err(|e| self.failed(e))
+    }
+
+    /// Fail if anything arrives within `duration`
+    pub(crate) async fn expect_silence(&mut self, duration: Duration) -> ValidateResult {
+        self.ws
+            .expect_silence(duration)
+            .await
+            .map_err(|e| self.failed(e))
+    }
+
+    pub(crate) async fn recv_str(&mut self, exp: &str) -> ValidateResult {
+        self.ws.recv_str(exp).await.map_err(|e| self.failed(e))
+    }
+
+    pub(crate) async fn recv_json(&mut self, exp: &serde_json::Value) -> ValidateResult {
+        self.ws.recv_json(exp).await.map_err(|e| self.failed(e))
+    }
+
+    /// Close the connection, and fail if the server does not complete the close handshake
+    pub(crate) async fn close(self) -> ValidateResult {
+        let test = self.test;
+        match self.ws.close().await {
+            Ok(_) => Ok(()),
+            Err(e) => Err(TestFailure::failed(test, e)),
+        }
+    }
+}
+
+/// A [`testing::TextTester`] that sends its requests with the client and retries of the run
+pub(crate) struct TextTester(testing::TextTester);
+
+impl TextTester {
+    pub(crate) fn new(url: String) -> Self {
+        Self(testing::TextTester::new(url).client(shared_client()))
+    }
+    pub(crate) async fn test(
+        &self,
+        test: TaskTest,
+        i: &str,
+        code: StatusCode,
+        o: &str,
+    ) -> ValidateResult {
+        let res = self
+            .0
+            .request(i)
+            .send_with_retry()
+            .await
+            .map_err(|_| test)?;
+        testing::TextTester::check_as(res, code, &[], o, strictness())
+            .await
+            .map_err(|e| TestFailure::failed(test, e))
+    }
+    /// Like [`TextTester::test`], but sends an asset that may be streamed
+    pub(crate) async fn test_upload(
+        &self,
+        test: TaskTest,
+        i: Upload,
+        code: StatusCode,
+        o: &str,
+    ) -> ValidateResult {
+        let res = self
+            .0
+            .builder()
+            .upload(i)
+            .send_with_retry()
+            .await
+            .map_err(|_| test)?;
+        testing::TextTester::check_as(res, code, &[], o, strictness())
+            .await
+            .map_err(|e| TestFailure::failed(test, e))
+    }
+}
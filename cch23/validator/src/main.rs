@@ -1,92 +1,850 @@
+use std::{io::Write, path::PathBuf, sync::Arc};
+
+use anstream::{eprintln, println};
+use anstyle::{AnsiColor, Style};
 use cch23_validator::{
-    args::ValidatorArgs,
-    run,
-    shuttlings::{SubmissionState, SubmissionUpdate},
-    SUPPORTED_CHALLENGES,
+    args::{
+        expand_negative_selections, ConfigFile, OutputFormat, ReportFormat, SummaryFormat,
+        ValidatorArgs, Verbosity,
+    },
+    challenge_info, discover, run_with_progress,
+    shuttlings::{
+        compare_runs,
+        doctor::{self, Status},
+        har::{Har, Replay},
+        markdown_summary,
+        output::{Output, PROGRESS_INTERVAL},
+        report::ReportBuilder,
+        serve,
+        tap::TapWriter,
+        transport::Transport,
+        update,
+        webhook::send_webhook,
+        ChallengeSummary, ComparedRun, LogLevel, RunSummary, ScoreStatus, ScoreTotal,
+        SubmissionState, SubmissionUpdate, SummaryRow, SCORE_HEADER,
+    },
+    task_count, Cch23, Extras, Load, Proxy, RunOutcome, Timeout, TlsVerification, ValidationConfig,
+    CHALLENGES, SUPPORTED_CHALLENGES,
+};
+use clap::{error::ErrorKind, CommandFactory, FromArgMatches};
+use tokio::{
+    sync::{
+        mpsc::{Receiver, UnboundedSender},
+        Semaphore,
+    },
+    task::JoinSet,
+    time::Duration,
 };
-use clap::{CommandFactory, FromArgMatches};
-use uuid::Uuid;
+use tokio_util::sync::CancellationToken;
+
+/// How many of the slowest tests `--timings` shows
+const SLOWEST_TESTS: usize = 10;
+const RED: Style = AnsiColor::Red.on_default();
+const GREEN: Style = AnsiColor::Green.on_default();
+const YELLOW: Style = AnsiColor::Yellow.on_default();
 
 #[tokio::main]
 async fn main() {
-    let c = ValidatorArgs::command();
-    let m = c
+    let c = ValidatorArgs::command()
         .mut_arg("numbers", |a| a.allow_negative_numbers(true))
-        .get_matches();
-    let args = ValidatorArgs::from_arg_matches(&m).unwrap();
+        .mut_arg("skip", |a| a.allow_negative_numbers(true));
+    // --config has to be known before the other options are parsed, as the file changes their defaults
+    let config_path = c
+        .clone()
+        .ignore_errors(true)
+        .disable_help_flag(true)
+        .disable_version_flag(true)
+        .get_matches()
+        .get_one::<PathBuf>("config")
+        .cloned()
+        .or_else(ConfigFile::find);
+    let config_file = match config_path.as_deref().map(ConfigFile::load) {
+        Some(Ok(f)) => f,
+        Some(Err(e)) => exit_with(c.clone().error(ErrorKind::Io, e)),
+        None => ConfigFile::default(),
+    };
+    let mut c = config_file.apply(c);
+    // annotate the workflow run in GitHub Actions, unless another format is asked for
+    if std::env::var("GITHUB_ACTIONS").is_ok_and(|v| v == "true") {
+        c = c.mut_arg("format", |a| a.default_value("github"));
+    }
+    let m = c
+        .try_get_matches_from_mut(expand_negative_selections(std::env::args_os()))
+        .unwrap_or_else(|e| exit_with(e));
+    let mut args = ValidatorArgs::from_arg_matches(&m).unwrap();
+    if args.challenge.numbers.is_empty()
+        && !args.challenge.all
+        && !args.challenge.list
+        && args.serve.is_none()
+        && !args.doctor
+    {
+        match config_file.selections() {
+            Some(Ok(selections)) => args.challenge.numbers = selections,
+            Some(Err(e)) => exit_with(c.error(ErrorKind::InvalidValue, e)),
+            None => (),
+        }
+    }
 
-    println!(
-        "\
+    if let Some(task) = args.challenge.task {
+        let selected = args.challenge.selected();
+        let [num] = selected.as_slice() else {
+            exit_with(c.error(
+                ErrorKind::ArgumentConflict,
+                "--task can only be used with a single challenge number",
+            ));
+        };
+        if let Some(count) = task_count(*num) {
+            if !(1..=count).contains(&task) {
+                let tasks = (1..=count).map(|t| t.to_string()).collect::<Vec<_>>();
+                exit_with(c.error(
+                    ErrorKind::InvalidValue,
+                    format!(
+                        "Challenge {num} does not have a task {task}. Valid tasks are: {}",
+                        tasks.join(", ")
+                    ),
+                ));
+            }
+        }
+    }
+
+    let json = args.format == OutputFormat::Json;
+    // stdout is reserved for the JSON or CSV report, or the TAP lines
+    let machine = matches!(
+        args.format,
+        OutputFormat::Json | OutputFormat::Csv | OutputFormat::Tap
+    );
+    if args.no_color {
+        anstream::ColorChoice::Never.write_global();
+    }
+
+    if args.challenge.list {
+        if json {
+            println!("{}", serde_json::to_string_pretty(CHALLENGES).unwrap());
+        } else {
+            for c in CHALLENGES {
+                println!(
+                    "Challenge {}: {} — {} tasks, up to {} bonus{}{}",
+                    c.challenge,
+                    c.description,
+                    c.tasks(),
+                    c.max_bonus(),
+                    if c.scored { "" } else { " (not scored)" },
+                    if c.time_scaled {
+                        " (honors --time-scale)"
+                    } else {
+                        ""
+                    }
+                );
+            }
+        }
+        return;
+    }
+    let tls = if args.insecure {
+        eprintln!(
+            "{RED}WARNING: --insecure is set, so TLS certificates are not checked \
+            and anyone on the network can impersonate the server.{RED:#}"
+        );
+        TlsVerification::Insecure
+    } else if let Some(path) = &args.cacert {
+        TlsVerification::ca_cert(path).unwrap_or_else(|e| exit_with(c.error(ErrorKind::Io, e)))
+    } else {
+        TlsVerification::Default
+    };
+    let verbosity = args.verbosity();
+    let mut config = ValidationConfig {
+        timeout: match args.timeout {
+            Some(0) => Timeout::Disabled,
+            Some(secs) => Timeout::Fixed(Duration::from_secs(secs)),
+            None => Timeout::Scaled(args.timeout_scale),
+        },
+        task: args.challenge.task,
+        retries: args.retries,
+        max_response_size: args.max_response_size,
+        har: args.har.as_ref().map(|path| {
+            Har::create(path, env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")).unwrap_or_else(
+                |e| {
+                    exit_with(c.error(
+                        ErrorKind::Io,
+                        format!("Failed to create HAR file {}: {e}", path.display()),
+                    ))
+                },
+            )
+        }),
+        transport: args.replay.as_deref().map(|path| -> Arc<dyn Transport> {
+            Arc::new(Replay::open(path).unwrap_or_else(|e| {
+                exit_with(c.error(
+                    ErrorKind::Io,
+                    format!("Failed to read HAR file {}: {e}", path.display()),
+                ))
+            }))
+        }),
+        probe: args.probe,
+        seed: args.seed,
+        strictness: args.strictness(),
+        keep_going: args.keep_going,
+        cancel: CancellationToken::new(),
+        time_scale: args.time_scale,
+        preflight: !args.skip_preflight,
+        tls,
+        proxy: match args.proxy {
+            Some(url) => Proxy::Url(url),
+            None if args.no_proxy => Proxy::Disabled,
+            None => Proxy::FromEnv,
+        },
+        http_version: args.http_version,
+        redirects: args.redirects,
+        resolve: args.resolve.clone(),
+        extras: Extras {
+            load: Load {
+                users: args.load_users,
+                messages: args.load_messages,
+            },
+            assets_dir: args.assets_dir,
+        },
+        save_debounce: None,
+    };
+    // the JSON or CSV report goes to stdout, so progress is written to stderr, while the TAP lines are the progress
+    let mut out = Output::new(json || args.format == OutputFormat::Csv);
+    if let Some(path) = &args.output {
+        out = out.with_file(path).unwrap_or_else(|e| {
+            exit_with(c.error(
+                ErrorKind::Io,
+                format!("Failed to open output file {}: {e}", path.display()),
+            ))
+        });
+    }
+    // human-readable output goes to stderr when stdout is reserved for machine-readable output
+    macro_rules! say {
+        ($($arg:tt)*) => {
+            if machine {
+                eprintln!($($arg)*);
+            } else {
+                println!($($arg)*);
+            }
+        };
+    }
+
+    if verbosity == Verbosity::Normal {
+        say!(
+            "\
 ⋆｡°✩ ⋆⁺｡˚⋆˙‧₊✩₊‧˙⋆˚｡⁺⋆ ✩°｡⋆°✩ ⋆⁺｡˚⋆˙‧₊✩₊‧˙⋆˚｡⁺⋆ ✩°｡⋆
 .・゜゜・・゜゜・．                .・゜゜・・゜゜・．
 ｡･ﾟﾟ･          SHUTTLE CCH23 VALIDATOR          ･ﾟﾟ･｡
 .・゜゜・・゜゜・．                .・゜゜・・゜゜・．
 ⋆｡°✩ ⋆⁺｡˚⋆˙‧₊✩₊‧˙⋆˚｡⁺⋆ ✩°｡⋆°✩ ⋆⁺｡˚⋆˙‧₊✩₊‧˙⋆˚｡⁺⋆ ✩°｡⋆
 "
-    );
+        );
+    }
 
-    let (tx, mut rx) = tokio::sync::mpsc::channel::<SubmissionUpdate>(32);
+    if let Some(addr) = args.serve {
+        say!("Serving the validation API on http://{addr}");
+        if let Err(e) = serve::serve::<Cch23>(addr, config, args.concurrency as usize).await {
+            exit_with(c.error(ErrorKind::Io, format!("Failed to serve on {addr}: {e}")));
+        }
+        return;
+    }
 
-    let get_printer = |summary: bool| async move {
-        let mut tasks_completed = 0;
-        let mut days_completed = 0;
-        let mut bonus = 0;
-        while let Some(s) = rx.recv().await {
+    if args.discover {
+        match discover(&args.url).await {
+            Ok(None) => (),
+            Ok(Some(url)) => {
+                say!(
+                    "Could not connect to {}, validating the server at {url} instead",
+                    args.url
+                );
+                args.url = url;
+            }
+            Err(e) => exit_with(c.error(ErrorKind::Io, e)),
+        }
+    }
+
+    if args.doctor {
+        let findings = doctor::doctor::<Cch23>(&args.url, &config).await;
+        if json {
+            println!("{}", serde_json::to_string_pretty(&findings).unwrap());
+        } else {
+            for f in &findings {
+                let (mark, style) = match f.status {
+                    Status::Ok => ("✅", GREEN),
+                    Status::Skipped => ("➖", Style::new()),
+                    Status::Warning => ("⚠️", YELLOW),
+                    Status::Failed => ("❌", RED),
+                };
+                say!("{mark} {style}{}{style:#}: {}", f.check, f.detail);
+                if let Some(advice) = &f.advice {
+                    say!("   {advice}");
+                }
+            }
+        }
+        if findings.iter().any(|f| f.status == Status::Failed) {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // the check runs alongside the validation, and is only waited for once it is done
+    let update_check = (!args.offline && std::env::var_os("SHUTTLINGS_NO_UPDATE_CHECK").is_none())
+        .then(|| {
+            tokio::spawn(update::newer_version(
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_VERSION"),
+            ))
+        });
+
+    // one seed for all challenges, which reproduces the run when it is passed again
+    let seed = *config.seed.get_or_insert_with(|| fastrand::u64(..));
+    if verbosity != Verbosity::Quiet {
+        say!("Seed: {seed}");
+    }
+
+    let (tx, rx) = tokio::sync::mpsc::channel::<SubmissionUpdate>(32);
+    // tells the main loop when all updates of a challenge are handled
+    let (printed_tx, mut printed_rx) = tokio::sync::mpsc::unbounded_channel::<()>();
+
+    let get_printer = |mut rx: Receiver<SubmissionUpdate>,
+                       mut report: ReportBuilder<Cch23>,
+                       mut out: Output,
+                       printed: UnboundedSender<()>,
+                       verbosity: Verbosity,
+                       total: usize,
+                       first_task: i32,
+                       timestamps: bool| async move {
+        let quiet = verbosity == Verbosity::Quiet;
+        let summary = total > 1;
+        let show_progress = summary && !quiet;
+        let mut days_started = 0;
+        // time of the last task that finished, relative to the start of the challenge
+        let mut elapsed = Duration::ZERO;
+        // lines of a completed task, waiting for its timing
+        let mut pending = Vec::new();
+        let stamp = |elapsed: Duration, line: String| {
+            if timestamps {
+                format!("[+{:.1}s] {line}", elapsed.as_secs_f64())
+            } else {
+                line
+            }
+        };
+        let mut ticks = tokio::time::interval(PROGRESS_INTERVAL);
+        loop {
+            let s = tokio::select! {
+                s = rx.recv() => match s {
+                    Some(s) => s,
+                    None => break,
+                },
+                _ = ticks.tick() => {
+                    out.refresh_progress();
+                    continue;
+                }
+            };
+            report.update(&s);
             match s {
+                SubmissionUpdate::State(SubmissionState::Running) => {
+                    days_started += 1;
+                    elapsed = Duration::ZERO;
+                    if let Some(c) = report.last().filter(|_| !quiet) {
+                        out.line("");
+                        out.line(format!("Validating Challenge {}...", c.challenge));
+                        out.line("");
+                    }
+                }
                 SubmissionUpdate::State(SubmissionState::Done) => {
-                    tasks_completed = 0;
+                    out.clear_progress();
+                    for line in pending.drain(..) {
+                        out.line(stamp(elapsed, line));
+                    }
+                    let _ = printed.send(());
+                    continue;
+                }
+                SubmissionUpdate::State(SubmissionState::Error) => {
+                    out.clear_progress();
+                    for line in pending.drain(..) {
+                        out.line(stamp(elapsed, line));
+                    }
+                    if let Some(c) = report.last() {
+                        out.line(stamp(
+                            elapsed,
+                            format!(
+                                "{RED}Challenge {} could not be validated ⚠️{RED:#}",
+                                c.challenge
+                            ),
+                        ));
+                    }
+                    let _ = printed.send(());
+                    continue;
                 }
                 SubmissionUpdate::TaskCompleted(completed, bp) => {
-                    tasks_completed += 1;
-                    println!("Task {}: completed 🎉", tasks_completed);
+                    if quiet {
+                        continue;
+                    }
+                    let task = report
+                        .last()
+                        .and_then(|c| c.tasks.last())
+                        .map_or(first_task, |t| t.task);
+                    let passed = report.last().map_or(0, |c| {
+                        c.tests
+                            .iter()
+                            .filter(|t| t.task == task && t.passed)
+                            .count()
+                    });
+                    let tests = if passed == 1 { "test" } else { "tests" };
+                    let mut lines = vec![format!(
+                        "{GREEN}Task {task}: completed 🎉 ({passed} {tests} passed){GREEN:#}"
+                    )];
                     if bp > 0 {
-                        bonus += bp;
-                        println!("Bonus points: {} ✨", bp);
+                        lines.push(format!("{YELLOW}Bonus points: {} ✨{YELLOW:#}", bp));
                     }
                     if completed {
-                        days_completed += 1;
-                        println!("Core tasks completed ✅");
+                        lines.push(format!("{GREEN}Core tasks completed ✅{GREEN:#}"));
+                    }
+                    if timestamps {
+                        // the timing of the task follows its completion
+                        pending.extend(lines);
+                    } else {
+                        for line in lines {
+                            out.line(line);
+                        }
                     }
                 }
-                SubmissionUpdate::LogLine(line) => {
-                    println!("{line}");
+                SubmissionUpdate::Timing { elapsed_ms, .. } => {
+                    elapsed = Duration::from_millis(elapsed_ms);
+                    for line in pending.drain(..) {
+                        out.line(stamp(elapsed, line));
+                    }
+                }
+                SubmissionUpdate::LogLine {
+                    level: LogLevel::Info,
+                    ..
+                } if quiet => (),
+                SubmissionUpdate::LogLine { level, message } => {
+                    let style = log_style(level);
+                    // there is no "Validating Challenge" header to give failures context in quiet mode
+                    let line = match report.last() {
+                        Some(c) if quiet => {
+                            format!("{style}Challenge {}: {message}{style:#}", c.challenge)
+                        }
+                        _ => format!("{style}{message}{style:#}"),
+                    };
+                    out.line(stamp(elapsed, line));
                 }
                 _ => (),
             }
+            if let Some(c) = report.last().filter(|_| show_progress) {
+                let tasks = task_count(c.challenge).unwrap_or_default();
+                out.progress(format!(
+                    "Challenge {} ({days_started}/{total}), task {}/{tasks}",
+                    c.challenge,
+                    c.tasks.last().map_or(first_task, |t| t.task + 1).min(tasks),
+                ));
+            }
         }
-        if summary {
-            println!();
-            println!();
-            println!(
-                "Completed {} challenges and gathered a total of {} bonus points.",
-                days_completed, bonus
-            );
+        out.clear_progress();
+        let report = report.finish();
+        // the output file always ends with the summary, even if it isn't printed
+        let rows = report.score_rows();
+        let score = ScoreTotal::new(&rows);
+        let mut summary_lines = vec![String::new(), SCORE_HEADER.to_owned()];
+        for row in &rows {
+            let style = match row.status {
+                ScoreStatus::Completed => GREEN,
+                ScoreStatus::Failed => RED,
+                ScoreStatus::Skipped => Style::new(),
+            };
+            summary_lines.push(format!("{style}{}{style:#}", row.line()));
+        }
+        summary_lines.push(score.line());
+        for line in summary_lines {
+            if summary || quiet {
+                out.line(line);
+            } else {
+                out.log(&line);
+            }
         }
+        let total = score.to_string();
+        let total = if report.skipped.is_empty() {
+            total
+        } else {
+            let skipped = report.skipped.iter().map(|n| n.to_string());
+            format!(
+                "{total} Skipped {}.",
+                skipped.collect::<Vec<_>>().join(", ")
+            )
+        };
+        // for bug reports
+        let version = format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+        if summary || quiet {
+            out.line("");
+            out.line("");
+            out.line(total);
+            out.line(version);
+        } else {
+            out.log("");
+            out.log(&total);
+            out.log(&version);
+        }
+        // only challenges that ran more than once, so there is a summary above
+        if !report.flakiness.is_empty() {
+            out.line("");
+            for f in &report.flakiness {
+                out.line(f.to_string());
+            }
+        }
+        report
     };
 
-    let nums = if !args.challenge.numbers.is_empty() {
-        args.challenge.numbers.as_ref()
+    let get_reporter = |mut rx: Receiver<SubmissionUpdate>,
+                        mut report: ReportBuilder<Cch23>,
+                        mut out: Output,
+                        printed: UnboundedSender<()>,
+                        verbosity: Verbosity,
+                        format: OutputFormat| async move {
+        while let Some(s) = rx.recv().await {
+            report.update(&s);
+            match &s {
+                SubmissionUpdate::State(SubmissionState::Running) => {
+                    if let Some(c) = report.last().filter(|_| verbosity == Verbosity::Normal) {
+                        out.line("");
+                        out.line(format!("Validating Challenge {}...", c.challenge));
+                        out.line("");
+                    }
+                }
+                SubmissionUpdate::State(SubmissionState::Done | SubmissionState::Error) => {
+                    let _ = printed.send(());
+                }
+                SubmissionUpdate::LogLine { level, message }
+                    if *level > LogLevel::Info || verbosity == Verbosity::Normal =>
+                {
+                    let style = log_style(*level);
+                    out.line(format!("{style}{message}{style:#}"))
+                }
+                _ => (),
+            }
+        }
+        let report = report.finish();
+        if format == OutputFormat::Csv {
+            print!("{}", report.to_csv());
+        } else {
+            println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        }
+        report
+    };
+
+    let get_tap_writer = |mut rx: Receiver<SubmissionUpdate>,
+                          mut report: ReportBuilder<Cch23>,
+                          mut tap: TapWriter<Cch23>,
+                          mut out: Output,
+                          printed: UnboundedSender<()>| async move {
+        out.line(tap.plan());
+        while let Some(s) = rx.recv().await {
+            report.update(&s);
+            for line in tap.update(&s) {
+                out.line(line);
+            }
+            if let SubmissionUpdate::State(SubmissionState::Done | SubmissionState::Error) = s {
+                let _ = printed.send(());
+            }
+        }
+        report.finish()
+    };
+
+    let selected = args.challenge.selected();
+    let nums = if !selected.is_empty() {
+        selected.as_ref()
     } else {
         SUPPORTED_CHALLENGES
     };
+    let (nums, skipped): (Vec<i32>, Vec<i32>) =
+        nums.iter().partition(|n| !args.challenge.skip.contains(n));
+    if nums.is_empty() {
+        exit_with(c.error(
+            ErrorKind::ArgumentConflict,
+            "--skip leaves no challenges to validate",
+        ));
+    }
+
+    let report = ReportBuilder::new(
+        nums.repeat(args.repeat as usize),
+        skipped,
+        args.challenge.task,
+    );
+    let printer = match args.format {
+        OutputFormat::Json | OutputFormat::Csv => tokio::task::spawn(get_reporter(
+            rx,
+            report,
+            out,
+            printed_tx,
+            verbosity,
+            args.format,
+        )),
+        OutputFormat::Tap => {
+            let tap = TapWriter::new(nums.repeat(args.repeat as usize), args.challenge.task);
+            tokio::task::spawn(get_tap_writer(rx, report, tap, out, printed_tx))
+        }
+        OutputFormat::Text | OutputFormat::Github => tokio::task::spawn(get_printer(
+            rx,
+            report,
+            out,
+            printed_tx,
+            verbosity,
+            nums.len() * args.repeat as usize,
+            args.challenge.task.unwrap_or(1),
+            args.timestamps,
+        )),
+    };
+    // the first Ctrl-C stops the validation and prints what was validated so far, a second one exits
+    let cancel = config.cancel.clone();
+    tokio::spawn({
+        let cancel = cancel.clone();
+        async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                cancel.cancel();
+                if tokio::signal::ctrl_c().await.is_ok() {
+                    std::process::exit(130);
+                }
+            }
+        }
+    });
 
-    let printer = tokio::task::spawn(get_printer(nums.len() > 1));
+    // challenges run in the background, in order and at most `concurrency` at a time, once per round.
+    // their updates are buffered so that the printer receives them one challenge at a time.
+    let concurrency = args.concurrency;
+    let mut scheduled = Vec::new();
+    let mut runs = Vec::new();
+    for _ in 0..args.repeat {
+        let mut round = Vec::new();
+        for num in &nums {
+            let (buffer_tx, buffer_rx) = tokio::sync::mpsc::unbounded_channel();
+            let (outcome_tx, outcome_rx) = tokio::sync::oneshot::channel();
+            round.push((*num, buffer_tx, outcome_tx));
+            runs.push((num, buffer_rx, outcome_rx));
+        }
+        scheduled.push(round);
+    }
+    let compare_config = args.compare.as_ref().map(|_| config.clone());
+    let url = args.url.clone();
+    let scheduler = tokio::spawn(async move {
+        let permits = Arc::new(Semaphore::new(concurrency as usize));
+        for round in scheduled {
+            let mut set = JoinSet::new();
+            for (num, buffer_tx, outcome_tx) in round {
+                // wait for all other challenges to finish before starting an exclusive one
+                let n = match challenge_info(num) {
+                    Some(c) if c.exclusive => concurrency,
+                    _ => 1,
+                };
+                let permit = permits.clone().acquire_many_owned(n).await.unwrap();
+                let url = url.clone();
+                let config = config.clone();
+                set.spawn(async move {
+                    // the printer gets the updates through the buffer of the challenge
+                    let ended = run_with_progress(url, num, &config, |s| {
+                        let _ = buffer_tx.send(s.clone());
+                    })
+                    .await;
+                    drop(permit);
+                    let _ = outcome_tx.send(ended);
+                });
+            }
+            // the next round starts when this one is done, so that runs of a challenge never overlap
+            while set.join_next().await.is_some() {}
+        }
+    });
 
-    for num in nums {
-        println!();
-        println!("Validating Challenge {num}...");
-        println!();
-        run(
-            args.url.trim_end_matches('/').to_owned(),
-            Uuid::nil(),
-            *num,
-            tx.clone(),
-        )
-        .await;
-        // give the receiver time to print everything from the previous challenge
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+    // the most severe exit code so far, and why
+    let mut exit: Option<(i32, String)> = None;
+    // every run of a challenge, with the number of tasks it validates
+    let mut submissions = Vec::new();
+    for (num, mut updates, outcome) in runs {
+        while let Some(s) = updates.recv().await {
+            if tx.send(s).await.is_err() {
+                break;
+            }
+        }
+        let tasks = match args.challenge.task {
+            Some(_) => 1,
+            None => task_count(*num).unwrap_or_default() as usize,
+        };
+        let ended = outcome
+            .await
+            .expect("validation of the challenge stopped unexpectedly");
+        submissions.push((num.to_string(), tasks, ended.report));
+        let outcome = ended.reason;
+        // wait for the receiver to print everything from the challenge
+        printed_rx.recv().await;
+        if outcome == RunOutcome::Completed {
+            continue;
+        }
+        let code = exit_code(outcome);
+        if exit.as_ref().is_none_or(|(c, _)| code > *c) {
+            exit = Some((code, exit_reason(outcome, &num.to_string(), &args.url)));
+        }
+        if cancel.is_cancelled() {
+            scheduler.abort();
+            say!();
+            say!("Interrupted, skipping the remaining challenges.");
+            break;
+        }
+        if args.fail_fast {
+            scheduler.abort();
+            say!();
+            say!("Challenge {num} failed, skipping the remaining challenges.");
+            break;
+        }
     }
 
     drop(tx);
-    printer.await.unwrap();
+    let report = printer.await.unwrap();
+    let rows: Vec<SummaryRow> = submissions
+        .iter()
+        .map(|(challenge, tasks, report)| SummaryRow {
+            challenge,
+            tasks: *tasks,
+            report,
+        })
+        .collect();
+    let summary = markdown_summary(&rows);
+
+    if args.timings {
+        say!();
+        for line in report.timing_lines(SLOWEST_TESTS) {
+            say!("{line}");
+        }
+    }
+
+    if args.summary == Some(SummaryFormat::Markdown) {
+        say!();
+        say!("{}", summary.trim_end());
+    }
+
+    if let (Some(other), Some(config)) = (&args.compare, &compare_config) {
+        say!();
+        say!("Validating the same challenges against {other} to compare...");
+        // the first run of each challenge is compared, and runs one at a time
+        let mut compared = Vec::new();
+        for num in &nums {
+            let challenge = num.to_string();
+            let Some((_, _, a)) = submissions.iter().find(|(c, ..)| *c == challenge) else {
+                continue;
+            };
+            if cancel.is_cancelled() {
+                break;
+            }
+            let ended = run_with_progress(other.clone(), *num, config, |_| ()).await;
+            compared.push((challenge, a, ended.report));
+        }
+        let runs: Vec<ComparedRun> = compared
+            .iter()
+            .map(|(challenge, a, b)| ComparedRun { challenge, a, b })
+            .collect();
+        say!();
+        for line in compare_runs(&args.url, other, &runs) {
+            say!("{line}");
+        }
+    }
+
+    if args.format == OutputFormat::Github {
+        for line in report.to_github_annotations() {
+            println!("{line}");
+        }
+        if let Some(path) = std::env::var_os("GITHUB_STEP_SUMMARY") {
+            let written = std::fs::OpenOptions::new()
+                .append(true)
+                .create(true)
+                .open(&path)
+                .and_then(|mut f| f.write_all(summary.as_bytes()));
+            if let Err(e) = written {
+                eprintln!(
+                    "Failed to write the job summary to {}: {e}",
+                    PathBuf::from(path).display()
+                );
+            }
+        }
+    }
+
+    if let Some(webhook) = &args.webhook {
+        let challenges = submissions
+            .iter()
+            .map(|(challenge, _, report)| ChallengeSummary {
+                challenge: challenge.clone(),
+                summary: report.clone().finalize(),
+            })
+            .collect();
+        let summary = RunSummary::new(challenges, Duration::from_secs_f64(report.duration));
+        // the run is what counts for the exit code, not whether anyone was told about it
+        if let Err(e) = send_webhook(webhook, &summary).await {
+            eprintln!("{YELLOW}WARNING: Failed to call the webhook {webhook}: {e}{YELLOW:#}");
+        }
+    }
+
+    if let Some(path) = args.report_file {
+        let contents = match args.report {
+            Some(ReportFormat::Junit) => report.to_junit(),
+            Some(ReportFormat::Csv) => report.to_csv(),
+            None => unreachable!("--report-file requires --report"),
+        };
+        if let Err(e) = std::fs::write(&path, contents) {
+            eprintln!("Failed to write report to {}: {e}", path.display());
+            std::process::exit(1);
+        }
+    }
+
+    if let Some(Ok(Some(version))) = match update_check {
+        Some(check) => Some(check.await),
+        None => None,
+    } {
+        say!();
+        say!(
+            "{YELLOW}{} {version} is available, this is {}. Update with `cargo install {0}`{YELLOW:#}",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION")
+        );
+    }
+
+    if let Some((code, reason)) = exit {
+        eprintln!("Exiting with code {code}: {reason}");
+        std::process::exit(code);
+    }
+}
+
+/// Errors are red and warnings yellow, notes keep the default color
+fn log_style(level: LogLevel) -> Style {
+    match level {
+        LogLevel::Info => Style::new(),
+        LogLevel::Warn => YELLOW,
+        LogLevel::Error => RED,
+    }
+}
+
+/// Print a command line error and exit with [`EXIT_USAGE`], or with 0 for --help and --version
+fn exit_with(e: clap::Error) -> ! {
+    let _ = e.print();
+    std::process::exit(if e.use_stderr() { EXIT_USAGE } else { 0 });
+}
+
+/// Exit code for a bad command line or a challenge that can't be validated
+const EXIT_USAGE: i32 = 3;
+
+fn exit_code(outcome: RunOutcome) -> i32 {
+    match outcome {
+        RunOutcome::Completed => 0,
+        RunOutcome::Failed { .. } => 1,
+        RunOutcome::TimedOut
+        | RunOutcome::ConnectionFailed
+        | RunOutcome::Cancelled
+        | RunOutcome::InternalError => 2,
+        RunOutcome::Unsupported => EXIT_USAGE,
+    }
+}
+
+fn exit_reason(outcome: RunOutcome, num: &str, url: &str) -> String {
+    match outcome {
+        RunOutcome::Completed => format!("Challenge {num} passed"),
+        RunOutcome::Failed { task, test } => {
+            format!("Challenge {num} failed at task {task}, test #{test}")
+        }
+        RunOutcome::TimedOut => format!("Challenge {num} timed out"),
+        RunOutcome::ConnectionFailed => format!("Could not connect to {url}"),
+        RunOutcome::Unsupported => format!("Challenge {num} can't be validated"),
+        RunOutcome::Cancelled => format!("Validation of challenge {num} was cancelled"),
+        RunOutcome::InternalError => format!("The validator failed on challenge {num}"),
+    }
 }
@@ -0,0 +1,193 @@
+//! Proxies for the websocket connections, which tokio-tungstenite makes without reqwest
+
+use std::io;
+
+use base64::{engine::general_purpose, Engine};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+use url::Url;
+
+use crate::Proxy;
+
+/// The most a proxy may send before the tunnel is open
+const MAX_RESPONSE_HEAD: usize = 8192;
+
+/// The proxy that a connection to the websocket `url` goes through, if any
+pub(crate) fn for_url(url: &str, proxy: &Proxy) -> Option<String> {
+    match proxy {
+        Proxy::FromEnv => from_env(url, |var| std::env::var(var).ok()),
+        Proxy::Url(proxy) => Some(proxy.clone()),
+        Proxy::Disabled => None,
+    }
+}
+
+/// The proxy for `url` in the environment variables that `var` looks up,
+/// in the same places as reqwest looks for the proxy of an http(s) URL
+fn from_env(url: &str, var: impl Fn(&str) -> Option<String>) -> Option<String> {
+    let url = Url::parse(url).ok()?;
+    let host = url
+        .host_str()?
+        .trim_start_matches('[')
+        .trim_end_matches(']');
+    let var = |name: &str| {
+        var(name)
+            .or_else(|| var(&name.to_lowercase()))
+            .filter(|v| !v.is_empty())
+    };
+    if var("NO_PROXY").is_some_and(|no_proxy| excludes(&no_proxy, host)) {
+        return None;
+    }
+    let scheme_var = match url.scheme() {
+        "wss" => "HTTPS_PROXY",
+        _ => "HTTP_PROXY",
+    };
+    var(scheme_var).or_else(|| var("ALL_PROXY"))
+}
+
+/// Whether a `NO_PROXY` list like `localhost,.example.com` has `host`
+fn excludes(no_proxy: &str, host: &str) -> bool {
+    no_proxy.split(',').map(str::trim).any(|entry| {
+        let domain = entry.trim_start_matches('.');
+        entry == "*"
+            || host.eq_ignore_ascii_case(domain)
+            || host
+                .to_lowercase()
+                .ends_with(&format!(".{}", domain.to_lowercase()))
+    })
+}
+
+/// Open a connection to the host of `target` through a CONNECT tunnel of the http `proxy`
+pub(crate) async fn tunnel(proxy: &str, target: &str) -> io::Result<TcpStream> {
+    let invalid = |e| io::Error::new(io::ErrorKind::InvalidInput, e);
+    let proxy = if proxy.contains("://") {
+        Url::parse(proxy)
+    } else {
+        Url::parse(&format!("http://{proxy}"))
+    }
+    .map_err(invalid)?;
+    let target = Url::parse(target).map_err(invalid)?;
+    let authority = |url: &Url| {
+        let host = url.host_str().unwrap_or_default();
+        format!("{host}:{}", url.port_or_known_default().unwrap_or(80))
+    };
+    let target_authority = authority(&target);
+    let mut stream = TcpStream::connect(authority(&proxy)).await?;
+    let mut request =
+        format!("CONNECT {target_authority} HTTP/1.1\r\nHost: {target_authority}\r\n");
+    if !proxy.username().is_empty() {
+        let credentials = format!(
+            "{}:{}",
+            proxy.username(),
+            proxy.password().unwrap_or_default()
+        );
+        request.push_str(&format!(
+            "Proxy-Authorization: Basic {}\r\n",
+            general_purpose::STANDARD.encode(credentials)
+        ));
+    }
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes()).await?;
+
+    // read byte by byte, so that nothing that comes through the tunnel is read
+    let mut head = Vec::new();
+    while !head.ends_with(b"\r\n\r\n") {
+        if head.len() == MAX_RESPONSE_HEAD {
+            return Err(io::Error::other("the response of the proxy is too long"));
+        }
+        head.push(stream.read_u8().await?);
+    }
+    let head = String::from_utf8_lossy(&head);
+    let status = head.lines().next().unwrap_or_default();
+    if status.split(' ').nth(1) != Some("200") {
+        return Err(io::Error::other(format!(
+            "the proxy did not open a tunnel: {status}"
+        )));
+    }
+    Ok(stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    #[test]
+    fn proxy_comes_from_the_environment() {
+        let env = HashMap::from([
+            ("http_proxy", "http://plain:3128"),
+            ("HTTPS_PROXY", "http://secure:3128"),
+            ("NO_PROXY", "localhost, .internal"),
+        ]);
+        let proxy = |url| from_env(url, |var| env.get(var).map(|v| v.to_string()));
+        assert_eq!(
+            proxy("ws://example.com/19/ws"),
+            Some("http://plain:3128".into())
+        );
+        assert_eq!(
+            proxy("wss://example.com/19/ws"),
+            Some("http://secure:3128".into())
+        );
+        assert_eq!(proxy("ws://localhost:8000/19/ws"), None);
+        assert_eq!(proxy("wss://app.internal/19/ws"), None);
+        assert_eq!(proxy("wss://internal/19/ws"), None);
+        assert_eq!(
+            proxy("wss://notinternal/19/ws"),
+            Some("http://secure:3128".into())
+        );
+
+        let all = |var: &str| (var == "ALL_PROXY").then(|| "http://all:3128".to_owned());
+        assert_eq!(
+            from_env("ws://example.com", all),
+            Some("http://all:3128".into())
+        );
+        assert_eq!(from_env("ws://example.com", |_| None), None);
+    }
+
+    /// A proxy that answers a CONNECT request with `status`, and then sends `hello`
+    async fn proxy_stub(status: &'static str) -> (String, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://user:secret@{}", listener.local_addr().unwrap());
+        let request = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            while !request.ends_with(b"\r\n\r\n") {
+                request.push(stream.read_u8().await.unwrap());
+            }
+            let response = format!("HTTP/1.1 {status}\r\n\r\nhello");
+            stream.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8(request).unwrap()
+        });
+        (url, request)
+    }
+
+    #[tokio::test]
+    async fn tunnel_connects_through_the_proxy() {
+        let (proxy, request) = proxy_stub("200 Connection established").await;
+        let mut stream = tunnel(&proxy, "wss://example.com/myapp/19/ws/ping")
+            .await
+            .unwrap();
+        let mut hello = [0; 5];
+        stream.read_exact(&mut hello).await.unwrap();
+        assert_eq!(&hello, b"hello");
+        assert_eq!(
+            request.await.unwrap(),
+            "CONNECT example.com:443 HTTP/1.1\r\nHost: example.com:443\r\n\
+            Proxy-Authorization: Basic dXNlcjpzZWNyZXQ=\r\n\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn refused_tunnel_is_an_error() {
+        let (proxy, _) = proxy_stub("407 Proxy Authentication Required").await;
+        let e = tunnel(&proxy, "ws://example.com:8000").await.unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "the proxy did not open a tunnel: HTTP/1.1 407 Proxy Authentication Required"
+        );
+    }
+}
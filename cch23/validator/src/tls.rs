@@ -0,0 +1,88 @@
+//! TLS for the websocket connections, which tokio-tungstenite makes without reqwest
+
+use std::sync::Arc;
+
+use rustls::{
+    client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+    crypto::{ring, verify_tls12_signature, verify_tls13_signature, CryptoProvider},
+    pki_types::{pem::PemObject, CertificateDer, ServerName, UnixTime},
+    ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme,
+};
+use tokio_tungstenite::Connector;
+
+use crate::TlsVerification;
+
+/// The connector for websockets that checks certificates like `tls` says,
+/// or `None` for the default one of tokio-tungstenite.
+/// Neither offers HTTP/2 through ALPN, so the upgrade is HTTP/1.1 whatever [`crate::HttpVersion`] says.
+pub(crate) fn ws_connector(tls: &TlsVerification) -> Option<Connector> {
+    let provider = Arc::new(ring::default_provider());
+    let builder = ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .expect("ring to support the default protocol versions");
+    let config = match tls {
+        TlsVerification::Default => return None,
+        TlsVerification::CaCert(pem) => {
+            // the same roots as the default connector, and the given certificates
+            let mut roots = RootCertStore::empty();
+            roots.add_parsable_certificates(rustls_native_certs::load_native_certs().certs);
+            roots.add_parsable_certificates(
+                CertificateDer::pem_slice_iter(pem).filter_map(Result::ok),
+            );
+            builder.with_root_certificates(roots)
+        }
+        TlsVerification::Insecure => builder
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(AcceptAnyCert(provider))),
+    };
+    Some(Connector::Rustls(Arc::new(config.with_no_client_auth())))
+}
+
+/// Accepts any certificate, but still checks that the handshake was signed with it
+#[derive(Debug)]
+struct AcceptAnyCert(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for AcceptAnyCert {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
@@ -0,0 +1,332 @@
+//! Reference solutions of some challenges, served on a random port, that the validator must accept.
+//! Each challenge also has a solution with one wrong endpoint, that must fail at the test it breaks.
+
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, Query},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use cch23_validator::{run_with_progress, RunOutcome, ValidationConfig};
+use serde::Deserialize;
+use serde_json::json;
+use shuttlings::{SubmissionUpdate, TestResult};
+
+/// Serve `app` on a random port, and validate the challenge `number` against it
+async fn validate(
+    app: Router,
+    number: i32,
+    task: Option<i32>,
+) -> (RunOutcome, Vec<SubmissionUpdate>) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+    let config = ValidationConfig {
+        task,
+        ..Default::default()
+    };
+    let mut updates = Vec::new();
+    let ended = run_with_progress(url, number, &config, |update| match update {
+        SubmissionUpdate::TestResult(result) => {
+            updates.push(SubmissionUpdate::TestResult(TestResult {
+                duration_ms: 0,
+                ..result.clone()
+            }))
+        }
+        u @ SubmissionUpdate::TaskCompleted(..) => updates.push(u.clone()),
+        _ => (),
+    })
+    .await;
+    server.abort();
+    (ended.reason, updates)
+}
+
+/// The updates of a task whose `tests` all passed, then completed it
+fn passed(task: i32, tests: i32, completed: (bool, i32)) -> Vec<SubmissionUpdate> {
+    (1..=tests)
+        .map(|test| {
+            SubmissionUpdate::TestResult(TestResult {
+                task,
+                test,
+                passed: true,
+                duration_ms: 0,
+                detail: None,
+            })
+        })
+        .chain([completed.into()])
+        .collect()
+}
+
+fn day_minus1(wrong: bool) -> Router {
+    let error = match wrong {
+        false => StatusCode::INTERNAL_SERVER_ERROR,
+        true => StatusCode::OK,
+    };
+    Router::new()
+        .route("/", get(|| async { "Hello, world!" }))
+        .route("/-1/error", get(move || async move { error }))
+}
+
+#[tokio::test]
+async fn day_minus1_is_solved() {
+    let (outcome, updates) = validate(day_minus1(false), -1, None).await;
+    assert_eq!(outcome, RunOutcome::Completed);
+    assert_eq!(
+        updates,
+        [passed(1, 1, (true, 0)), passed(2, 1, (false, 0))].concat()
+    );
+    let (outcome, _) = validate(day_minus1(true), -1, Some(2)).await;
+    assert_eq!(outcome, RunOutcome::Failed { task: 2, test: 1 });
+}
+
+fn day01(wrong: bool) -> Router {
+    Router::new().route(
+        "/1/{*ids}",
+        get(move |Path(ids): Path<String>| async move {
+            let xor = ids
+                .split('/')
+                .try_fold(0, |xor, id| id.parse::<i32>().map(|id| xor ^ id));
+            match (xor, wrong) {
+                (Ok(xor), false) => xor.pow(3).to_string().into_response(),
+                // the absolute value of the cube
+                (Ok(xor), true) => xor.pow(3).abs().to_string().into_response(),
+                (Err(_), _) => StatusCode::BAD_REQUEST.into_response(),
+            }
+        }),
+    )
+}
+
+#[tokio::test]
+async fn day01_is_solved() {
+    let (outcome, updates) = validate(day01(false), 1, None).await;
+    assert_eq!(outcome, RunOutcome::Completed);
+    assert_eq!(
+        updates,
+        [passed(1, 2, (true, 0)), passed(2, 4, (false, 100))].concat()
+    );
+    let (outcome, _) = validate(day01(true), 1, Some(2)).await;
+    assert_eq!(outcome, RunOutcome::Failed { task: 2, test: 3 });
+}
+
+#[derive(Deserialize)]
+struct Reindeer {
+    name: String,
+    strength: i32,
+    #[serde(default)]
+    speed: f64,
+    #[serde(default)]
+    height: i32,
+    #[serde(default)]
+    antler_width: i32,
+    #[serde(default)]
+    snow_magic_power: i32,
+    #[serde(default)]
+    favorite_food: String,
+    #[serde(default, rename = "cAnD13s_3ATeN-yesT3rdAy")]
+    candies: i32,
+}
+
+fn day04(wrong: bool) -> Router {
+    Router::new()
+        .route(
+            "/4/strength",
+            post(|Json(reindeer): Json<Vec<Reindeer>>| async move {
+                reindeer.iter().map(|r| r.strength).sum::<i32>().to_string()
+            }),
+        )
+        .route(
+            "/4/contest",
+            post(move |Json(reindeer): Json<Vec<Reindeer>>| async move {
+                let Some(fastest) = reindeer.iter().max_by(|a, b| a.speed.total_cmp(&b.speed))
+                else {
+                    return StatusCode::BAD_REQUEST.into_response();
+                };
+                let tallest = match wrong {
+                    false => reindeer.iter().max_by_key(|r| r.height),
+                    // the widest antlers instead
+                    true => reindeer.iter().max_by_key(|r| r.antler_width),
+                };
+                let tallest = tallest.unwrap_or(fastest);
+                let magician = reindeer
+                    .iter()
+                    .max_by_key(|r| r.snow_magic_power)
+                    .unwrap_or(fastest);
+                let consumer = reindeer.iter().max_by_key(|r| r.candies).unwrap_or(fastest);
+                Json(json!({
+                    "fastest": format!(
+                        "Speeding past the finish line with a strength of {} is {}",
+                        fastest.strength, fastest.name
+                    ),
+                    "tallest": format!(
+                        "{} is standing tall with his {} cm wide antlers",
+                        tallest.name, tallest.antler_width
+                    ),
+                    "magician": format!(
+                        "{} could blast you away with a snow magic power of {}",
+                        magician.name, magician.snow_magic_power
+                    ),
+                    "consumer": format!(
+                        "{} ate lots of candies, but also some {}",
+                        consumer.name, consumer.favorite_food
+                    ),
+                }))
+                .into_response()
+            }),
+        )
+}
+
+#[tokio::test]
+async fn day04_is_solved() {
+    let (outcome, updates) = validate(day04(false), 4, None).await;
+    assert_eq!(outcome, RunOutcome::Completed);
+    assert_eq!(
+        updates,
+        [passed(1, 1, (true, 0)), passed(2, 1, (false, 150))].concat()
+    );
+    // the bonus task fails, but the challenge is still completed
+    let (outcome, updates) = validate(day04(true), 4, None).await;
+    assert_eq!(outcome, RunOutcome::Completed);
+    assert!(matches!(
+        updates.last(),
+        Some(SubmissionUpdate::TestResult(TestResult {
+            task: 2,
+            test: 1,
+            passed: false,
+            ..
+        }))
+    ));
+    let (outcome, _) = validate(day04(true), 4, Some(2)).await;
+    assert_eq!(outcome, RunOutcome::Failed { task: 2, test: 1 });
+}
+
+#[derive(Deserialize)]
+struct Page {
+    #[serde(default)]
+    offset: usize,
+    limit: Option<usize>,
+    split: Option<usize>,
+}
+
+fn day05(wrong: bool) -> Router {
+    Router::new().route(
+        "/5",
+        post(
+            move |Query(page): Query<Page>, Json(names): Json<Vec<String>>| async move {
+                let limit = match (page.limit, wrong) {
+                    (Some(limit), _) => limit,
+                    (None, false) => names.len(),
+                    // a default page size
+                    (None, true) => 3,
+                };
+                let names = names
+                    .into_iter()
+                    .skip(page.offset)
+                    .take(limit)
+                    .collect::<Vec<_>>();
+                match page.split {
+                    Some(0) => StatusCode::BAD_REQUEST.into_response(),
+                    Some(split) => {
+                        Json(json!(names.chunks(split).collect::<Vec<_>>())).into_response()
+                    }
+                    None => Json(json!(names)).into_response(),
+                }
+            },
+        ),
+    )
+}
+
+#[tokio::test]
+async fn day05_is_solved() {
+    let (outcome, updates) = validate(day05(false), 5, None).await;
+    assert_eq!(outcome, RunOutcome::Completed);
+    assert_eq!(
+        updates,
+        [passed(1, 2, (true, 0)), passed(2, 8, (false, 150))].concat()
+    );
+    let (outcome, _) = validate(day05(true), 5, Some(2)).await;
+    assert_eq!(outcome, RunOutcome::Failed { task: 2, test: 2 });
+}
+
+/// How many times `needle` is in `haystack`, counting the overlapping ones
+fn count(haystack: &str, needle: &str) -> usize {
+    (0..haystack.len())
+        .filter(|&i| haystack[i..].starts_with(needle))
+        .count()
+}
+
+fn day06(wrong: bool) -> Router {
+    Router::new().route(
+        "/6",
+        post(move |text: String| async move {
+            let on_shelves = count(&text, "elf on a shelf");
+            let shelves = match wrong {
+                false => count(&text, "shelf") - on_shelves,
+                // every shelf, even those with an elf on them
+                true => count(&text, "shelf"),
+            };
+            Json(json!({
+                "elf": count(&text, "elf"),
+                "elf on a shelf": on_shelves,
+                "shelf with no elf on it": shelves,
+            }))
+        }),
+    )
+}
+
+#[tokio::test]
+async fn day06_is_solved() {
+    let (outcome, updates) = validate(day06(false), 6, None).await;
+    assert_eq!(outcome, RunOutcome::Completed);
+    assert_eq!(
+        updates,
+        [passed(1, 2, (true, 0)), passed(2, 3, (false, 200))].concat()
+    );
+    let (outcome, _) = validate(day06(true), 6, Some(2)).await;
+    assert_eq!(outcome, RunOutcome::Failed { task: 2, test: 1 });
+}
+
+fn is_nice(s: &str) -> bool {
+    let vowels = s.chars().filter(|c| "aeiouy".contains(*c)).count();
+    let double = s
+        .as_bytes()
+        .windows(2)
+        .any(|w| w[0] == w[1] && w[0].is_ascii_alphabetic());
+    let banned = ["ab", "cd", "pq", "xy"].iter().any(|b| s.contains(b));
+    vowels >= 3 && double && !banned
+}
+
+fn day15(wrong: bool) -> Router {
+    Router::new().route(
+        "/15/nice",
+        post(
+            move |Json(body): Json<HashMap<String, String>>| async move {
+                let Some(input) = body.get("input") else {
+                    return StatusCode::BAD_REQUEST.into_response();
+                };
+                // digits count as vowels
+                let nice = match wrong {
+                    false => is_nice(input),
+                    true => is_nice(&input.replace(|c: char| c.is_ascii_digit(), "e")),
+                };
+                match nice {
+                    true => Json(json!({"result": "nice"})).into_response(),
+                    false => (StatusCode::BAD_REQUEST, Json(json!({"result": "naughty"})))
+                        .into_response(),
+                }
+            },
+        ),
+    )
+}
+
+/// Only the first task, the password game of the second one has no reference solution here
+#[tokio::test]
+async fn day15_is_solved() {
+    let (outcome, updates) = validate(day15(false), 15, Some(1)).await;
+    assert_eq!(outcome, RunOutcome::Completed);
+    assert_eq!(updates, passed(1, 6, (true, 0)));
+    let (outcome, _) = validate(day15(true), 15, Some(1)).await;
+    assert_eq!(outcome, RunOutcome::Failed { task: 1, test: 2 });
+}
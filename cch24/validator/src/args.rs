@@ -1,21 +1,488 @@
-use clap::{Args, Parser};
+use std::{
+    ffi::OsString,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+};
+
+use clap::{ArgGroup, Args, Command, Parser, ValueEnum};
+use serde::Deserialize;
+use shuttlings::testing::{Strictness, DEFAULT_MAX_RESPONSE_SIZE};
+
+use crate::{
+    did_you_mean, normalize_url, HttpVersion, Redirects, Resolve, DEFAULT_RETRIES,
+    SUPPORTED_CHALLENGES,
+};
 
 #[derive(Debug, Parser)]
-#[command(version)]
+#[command(version, group(ArgGroup::new("challenge").required(true).args(["numbers", "all", "list", "serve", "doctor"])))]
 pub struct ValidatorArgs {
     #[command(flatten)]
     pub challenge: ChallengeArgs,
-    /// The base URL to test against
-    #[arg(long, short, default_value = "http://127.0.0.1:8000")]
+    /// Serve an HTTP API on this address instead, which validates the submissions it is sent
+    #[arg(long, value_name = "ADDR")]
+    pub serve: Option<SocketAddr>,
+    /// Read defaults from this file instead of shuttlings.toml
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+    /// The base URL to test against, or a Unix socket like unix:///run/app.sock
+    #[arg(long, short, default_value = "http://127.0.0.1:8000", value_parser = normalize_url)]
     pub url: String,
+    /// If the default URL can't be connected to, look for the server on the common local ports
+    /// (8000-8010 and 3000), and validate the one that answers like a challenge server
+    #[arg(long, conflicts_with_all = ["url", "replay"])]
+    pub discover: bool,
+    /// Check the environment instead of validating: DNS resolution, connecting, the TLS handshake,
+    /// the round trip time, clock skew, and whether the latency endangers the timing-sensitive challenges
+    #[arg(long, conflicts_with = "replay")]
+    pub doctor: bool,
+    /// Also validate the challenges against this base URL afterwards, e.g. a new deployment,
+    /// and show how its outcomes differ from those of --url
+    #[arg(long, value_name = "URL", value_parser = normalize_url, conflicts_with = "replay")]
+    pub compare: Option<String>,
+    /// Seconds to wait for each challenge to complete, instead of the challenge's own timeout (0 for no timeout)
+    #[arg(long)]
+    pub timeout: Option<u64>,
+    /// Multiply the timeout of each challenge by this factor, e.g. on a slow network. Ignored if --timeout is set.
+    #[arg(long, value_name = "FACTOR", default_value_t = 1.0, value_parser = parse_factor)]
+    pub timeout_scale: f64,
+    /// Multiply the pauses of timing-sensitive challenges by this factor, and the times they expect.
+    /// `--list` shows which challenges honor it.
+    #[arg(long, value_name = "FACTOR", default_value_t = 1.0, value_parser = parse_factor)]
+    pub time_scale: f64,
+    /// Times to retry a request that fails with a connection error (0 to disable)
+    #[arg(long, default_value_t = DEFAULT_RETRIES)]
+    pub retries: u32,
+    /// Bytes of a response body to read at most before the test fails
+    #[arg(long, value_name = "BYTES", default_value_t = DEFAULT_MAX_RESPONSE_SIZE)]
+    pub max_response_size: usize,
+    /// Number of challenges to validate at the same time
+    #[arg(long, short = 'j', default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    pub concurrency: u32,
+    /// Validate the challenges this many times, and show how often each one passed
+    #[arg(long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    pub repeat: u32,
+    /// Stop after the first challenge that fails
+    #[arg(long)]
+    pub fail_fast: bool,
+    /// Accept any TLS certificate, e.g. a self-signed one. Only use this on a network you trust.
+    #[arg(long, conflicts_with = "cacert")]
+    pub insecure: bool,
+    /// Also trust the certificates in this PEM file, e.g. the self-signed certificate of the server
+    #[arg(long, value_name = "PATH")]
+    pub cacert: Option<PathBuf>,
+    /// Send the requests through this proxy instead of the one in HTTP_PROXY, HTTPS_PROXY or ALL_PROXY
+    #[arg(long, value_name = "URL", value_parser = parse_proxy)]
+    pub proxy: Option<String>,
+    /// Don't use a proxy, even if HTTP_PROXY, HTTPS_PROXY or ALL_PROXY is set
+    #[arg(long, conflicts_with = "proxy")]
+    pub no_proxy: bool,
+    /// Which HTTP versions to use: `auto` uses HTTP/2 with https servers that offer it,
+    /// `1` only HTTP/1.1, and `2` only HTTP/2, which is h2c for http servers.
+    /// Websockets always use HTTP/1.1.
+    #[arg(long, value_name = "VERSION", default_value = "auto")]
+    pub http_version: HttpVersion,
+    /// Connect to this address for the host and port, like curl's --resolve, e.g. to validate
+    /// `https://myapp.example.com` before its DNS points to the server. Can be given more than once.
+    #[arg(long, value_name = "HOST:PORT:ADDR")]
+    pub resolve: Vec<Resolve>,
+    /// Follow at most this many redirects of each request, or `none` so that the tests get the
+    /// redirect responses. A response that arrived through a redirect is a warning, and a failure with --strict.
+    #[arg(long, value_name = "N|none", default_value_t = Redirects::default())]
+    pub redirects: Redirects,
+    /// Allow one extra withdrawal in the rate limited tests of challenge 9 when the requests were slow
+    #[arg(long)]
+    pub timing_slack: bool,
+    /// Don't check that the server can be connected to before each challenge, e.g. if it rejects GET /
+    #[arg(long)]
+    pub skip_preflight: bool,
+    /// Don't check crates.io for a newer version of the validator, like setting SHUTTLINGS_NO_UPDATE_CHECK
+    #[arg(long)]
+    pub offline: bool,
+    /// Validate the remaining tasks of a challenge after a task fails
+    #[arg(long, short)]
+    pub keep_going: bool,
+    /// Only print failures, warnings and the final summary
+    #[arg(long, short)]
+    pub quiet: bool,
+    /// Disable colors, which are also off if NO_COLOR is set or the output is not a terminal
+    #[arg(long)]
+    pub no_color: bool,
+    /// Prefix the output of each challenge with the time since it started, e.g. `[+12.3s]`
+    #[arg(long)]
+    pub timestamps: bool,
+    /// Output format. `json` prints a report to stdout and progress to stderr, `csv` does the same with a row per task for spreadsheets,
+    /// `tap` prints a line per test in the Test Anything Protocol,
+    /// `github` adds annotations and a job summary to the text output in GitHub Actions
+    /// (the default when `GITHUB_ACTIONS=true`)
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+    /// Also write the output to this file, with a timestamp on every line
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+    /// Record all HTTP traffic of the run in this HAR file, which is written as the requests are sent
+    #[arg(long, value_name = "PATH")]
+    pub har: Option<PathBuf>,
+    /// Serve the responses of this HAR file, recorded with --har, instead of sending the requests to the server
+    #[arg(long, value_name = "PATH")]
+    pub replay: Option<PathBuf>,
+    /// After the tests of a challenge pass, send malformed requests to the endpoints they used,
+    /// and warn about server errors and dropped connections
+    #[arg(long)]
+    pub probe: bool,
+    /// Seed of the data that the validator generates, e.g. the malformed requests of --probe,
+    /// to generate the same data again. A random one is printed at the start if not set.
+    #[arg(long, value_name = "N")]
+    pub seed: Option<u64>,
+    /// Fail the tests whose response has another content type than the challenge requires,
+    /// instead of warning about it
+    #[arg(long)]
+    pub strict: bool,
+    /// Ignore a trailing newline of text responses unless the challenge expects one, and allow numbers ten times further off
+    #[arg(long, conflicts_with = "strict")]
+    pub lenient: bool,
+    /// Also write a report in this format to the report file
+    #[arg(long, value_enum, requires = "report_file")]
+    pub report: Option<ReportFormat>,
+    /// The file to write the report to
+    #[arg(long, requires = "report")]
+    pub report_file: Option<PathBuf>,
+    /// Print the slowest tests and the time spent on each challenge at the end
+    #[arg(long)]
+    pub timings: bool,
+    /// POST a JSON summary of the run to this URL when it finishes, e.g. a chat webhook
+    #[arg(long, value_name = "URL")]
+    pub webhook: Option<String>,
+    /// Print a summary of the run in this format at the end, e.g. to paste it into a chat
+    #[arg(long, value_enum)]
+    pub summary: Option<SummaryFormat>,
 }
 
 #[derive(Debug, Clone, Args)]
-#[group(required = true, multiple = false)]
+#[group(skip)]
 pub struct ChallengeArgs {
-    /// The challenge numbers to validate
-    pub numbers: Vec<String>,
+    /// The challenge numbers to validate, e.g. `5`, `1,2,5`, or a range like `1..8` or `1-12`
+    #[arg(value_parser = parse_selection)]
+    pub numbers: Vec<Selection>,
     /// Validate all challenges
     #[arg(long)]
     pub all: bool,
+    /// Challenges to leave out, e.g. `--all --skip 19,23`
+    #[arg(
+        long,
+        value_name = "NUMBERS",
+        value_delimiter = ',',
+        conflicts_with = "list"
+    )]
+    pub skip: Vec<String>,
+    /// List the supported challenges and their tasks
+    #[arg(long)]
+    pub list: bool,
+    /// Only validate this task of the challenge
+    #[arg(long, requires = "numbers")]
+    pub task: Option<i32>,
+}
+
+/// The challenges selected by one `NUMBERS` argument
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selection(pub Vec<String>);
+
+impl ChallengeArgs {
+    /// The selected challenges, in the order they were given and without duplicates
+    pub fn selected(&self) -> Vec<String> {
+        let mut selected = Vec::new();
+        for number in self.numbers.iter().flat_map(|s| &s.0) {
+            if !selected.contains(number) {
+                selected.push(number.clone());
+            }
+        }
+        selected
+    }
+}
+
+impl ValidatorArgs {
+    pub fn verbosity(&self) -> Verbosity {
+        if self.quiet {
+            Verbosity::Quiet
+        } else {
+            Verbosity::Normal
+        }
+    }
+
+    pub fn strictness(&self) -> Strictness {
+        if self.strict {
+            Strictness::Strict
+        } else if self.lenient {
+            Strictness::Lenient
+        } else {
+            Strictness::Default
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verbosity {
+    /// Failures and the summary
+    Quiet,
+    /// Progress of every task
+    Normal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Csv,
+    Tap,
+    Github,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    Junit,
+    Csv,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SummaryFormat {
+    Markdown,
+}
+
+/// Files in the current directory that defaults are read from, in order of preference
+pub const CONFIG_FILES: &[&str] = &["shuttlings.toml", ".shuttlings.toml"];
+
+/// Defaults for the command line options. Options given on the command line take precedence.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    pub url: Option<String>,
+    pub timeout: Option<u64>,
+    pub timeout_scale: Option<f64>,
+    pub time_scale: Option<f64>,
+    pub proxy: Option<String>,
+    pub retries: Option<u32>,
+    /// Challenges to validate when none are given on the command line
+    pub challenges: Option<Vec<i32>>,
+}
+
+impl ConfigFile {
+    /// The first of [`CONFIG_FILES`] that exists
+    pub fn find() -> Option<PathBuf> {
+        CONFIG_FILES.iter().map(PathBuf::from).find(|p| p.is_file())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read config file {}: {e}", path.display()))?;
+        toml::from_str(&contents)
+            .map_err(|e| format!("Invalid config file {}: {e}", path.display()))
+    }
+
+    /// The challenges to validate when none are given on the command line,
+    /// checked against the supported challenges like the `NUMBERS` argument is
+    pub fn selections(&self) -> Option<Result<Vec<Selection>, String>> {
+        let challenges = self.challenges.as_ref()?;
+        let selections = challenges.iter().map(|n| {
+            parse_selection(&n.to_string()).map_err(|e| {
+                format!("invalid value '{n}' for `challenges` in the config file: {e}")
+            })
+        });
+        Some(selections.collect())
+    }
+
+    /// Use the values in the file as the default values of `cmd`
+    pub fn apply(&self, mut cmd: Command) -> Command {
+        if let Some(url) = &self.url {
+            cmd = cmd.mut_arg("url", |a| a.default_value(url.clone()));
+        }
+        if let Some(timeout) = self.timeout {
+            cmd = cmd.mut_arg("timeout", |a| a.default_value(timeout.to_string()));
+        }
+        if let Some(scale) = self.timeout_scale {
+            cmd = cmd.mut_arg("timeout_scale", |a| a.default_value(scale.to_string()));
+        }
+        if let Some(scale) = self.time_scale {
+            cmd = cmd.mut_arg("time_scale", |a| a.default_value(scale.to_string()));
+        }
+        if let Some(proxy) = &self.proxy {
+            cmd = cmd.mut_arg("proxy", |a| a.default_value(proxy.clone()));
+        }
+        if let Some(retries) = self.retries {
+            cmd = cmd.mut_arg("retries", |a| a.default_value(retries.to_string()));
+        }
+        if self.challenges.is_some() {
+            cmd = cmd.mut_group("challenge", |g| g.required(false));
+        }
+        cmd
+    }
+}
+
+/// Clap only takes a value that starts with `-` if it is a number, so selections like `-1..5`
+/// are replaced by the numbers they select before the command line is parsed
+pub fn expand_negative_selections(args: impl IntoIterator<Item = OsString>) -> Vec<OsString> {
+    let mut args = args.into_iter();
+    let mut expanded = Vec::new();
+    for arg in args.by_ref() {
+        if arg == "--" {
+            expanded.push(arg);
+            break;
+        }
+        let selection = arg
+            .to_str()
+            .filter(|a| {
+                a.strip_prefix('-')
+                    .is_some_and(|n| n.starts_with(|c: char| c.is_ascii_digit()))
+            })
+            .filter(|a| a.parse::<i32>().is_err())
+            .and_then(|a| parse_selection(a).ok());
+        match selection {
+            Some(Selection(numbers)) => {
+                expanded.extend(numbers.iter().map(|n| OsString::from(n.to_string())))
+            }
+            None => expanded.push(arg),
+        }
+    }
+    expanded.extend(args);
+    expanded
+}
+
+/// Parse single numbers, comma separated lists and inclusive ranges into the supported challenges they select
+fn parse_selection(s: &str) -> Result<Selection, String> {
+    let mut numbers = Vec::new();
+    for part in s.split(',').map(str::trim) {
+        match split_range(part) {
+            Some((start, end)) => {
+                let (start, end) = (parse_number(start)?, parse_number(end)?);
+                if start > end {
+                    return Err(format!("`{part}` is an empty range"));
+                }
+                let len = numbers.len();
+                numbers.extend(
+                    SUPPORTED_CHALLENGES
+                        .iter()
+                        .filter(|n| n.parse().is_ok_and(|n| (start..=end).contains(&n)))
+                        .map(|n| n.to_string()),
+                );
+                if numbers.len() == len {
+                    return Err(format!(
+                        "no challenge from {start} to {end} is supported, {}",
+                        supported()
+                    ));
+                }
+            }
+            None => {
+                let number = parse_number(part)?;
+                let Some(supported) = SUPPORTED_CHALLENGES
+                    .iter()
+                    .find(|n| n.parse() == Ok(number))
+                else {
+                    return Err(match did_you_mean(&number.to_string()) {
+                        Some(suggestion) => format!(
+                            "challenge {number} is not supported ({suggestion}), {}",
+                            supported()
+                        ),
+                        None => format!("challenge {number} is not supported, {}", supported()),
+                    });
+                };
+                numbers.push(supported.to_string());
+            }
+        }
+    }
+    Ok(Selection(numbers))
+}
+
+/// Split `1..8` or `1-12` into its bounds. A leading `-` is the sign of the start, e.g. in `-1-5`.
+fn split_range(s: &str) -> Option<(&str, &str)> {
+    if let Some(range) = s.split_once("..") {
+        return Some(range);
+    }
+    let (i, _) = s.char_indices().skip(1).find(|&(_, c)| c == '-')?;
+    Some((&s[..i], &s[i + 1..]))
+}
+
+fn parse_number(s: &str) -> Result<i32, String> {
+    s.trim()
+        .parse()
+        .map_err(|_| format!("`{s}` is not a challenge number"))
+}
+
+fn supported() -> String {
+    let supported = SUPPORTED_CHALLENGES.iter().map(|n| n.to_string());
+    format!(
+        "supported challenges are: {}",
+        supported.collect::<Vec<_>>().join(", ")
+    )
+}
+
+fn parse_proxy(s: &str) -> Result<String, String> {
+    reqwest::Proxy::all(s).map_err(|e| format!("`{s}` is not a valid proxy URL: {e}"))?;
+    Ok(s.to_owned())
+}
+
+fn parse_factor(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(factor) if factor.is_finite() && factor > 0.0 => Ok(factor),
+        _ => Err(format!("`{s}` is not a positive number")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selections_expand_against_the_supported_challenges() {
+        assert_eq!(parse_selection("-1"), Ok(Selection(vec!["-1".to_owned()])));
+        assert_eq!(
+            parse_selection("-1,5"),
+            Ok(Selection(vec!["-1".to_owned(), "5".to_owned()]))
+        );
+        assert_eq!(
+            parse_selection("-1..5"),
+            Ok(Selection(vec![
+                "-1".to_owned(),
+                "2".to_owned(),
+                "5".to_owned()
+            ]))
+        );
+        assert_eq!(parse_selection("-1-5"), parse_selection("-1..5"));
+        assert_eq!(parse_selection("3..5"), Ok(Selection(vec!["5".to_owned()])));
+    }
+
+    #[test]
+    fn selections_reject_unsupported_challenges() {
+        assert!(parse_selection("3")
+            .unwrap_err()
+            .starts_with("challenge 3 is not supported (did you mean 2 or 5?)"));
+        assert!(parse_selection("30..40").is_err());
+        assert!(parse_selection("5..1").is_err());
+        assert!(parse_selection("1,x").is_err());
+    }
+
+    #[test]
+    fn config_challenges_are_checked_like_the_argument() {
+        let config = |challenges| ConfigFile {
+            challenges: Some(challenges),
+            ..Default::default()
+        };
+        assert_eq!(
+            config(vec![-1, 5]).selections(),
+            Some(Ok(vec![
+                Selection(vec!["-1".to_owned()]),
+                Selection(vec!["5".to_owned()])
+            ]))
+        );
+        assert!(config(vec![2, 3])
+            .selections()
+            .unwrap()
+            .unwrap_err()
+            .contains("challenge 3 is not supported (did you mean 2 or 5?)"));
+        assert_eq!(ConfigFile::default().selections(), None);
+    }
+
+    #[test]
+    fn negative_selections_are_expanded_before_parsing() {
+        let args = ["validator", "-1..2", "-q", "-1", "--", "-1..2"].map(OsString::from);
+        let expanded = ["validator", "-1", "2", "-q", "-1", "--", "-1..2"].map(OsString::from);
+        assert_eq!(expand_negative_selections(args), expanded);
+    }
 }
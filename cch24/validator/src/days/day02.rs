@@ -0,0 +1,287 @@
+use shuttlings::{try_update, SubmissionUpdate};
+use tokio::sync::mpsc::Sender;
+
+use crate::{
+    helpers::{shared_client, SendWithRetry},
+    BoxFuture, Challenge, ChallengeInfo, TaskTest, TestInfo, TestTracker, ValidateResult,
+};
+
+pub(crate) const TESTS: &[TestInfo] = &[
+    TestInfo {
+        task: 1,
+        test: 1,
+        description: "GET /2/dest?from=10.0.0.0&key=1.2.3.255 should return 11.2.3.255",
+    },
+    TestInfo {
+        task: 1,
+        test: 2,
+        description: "GET /2/dest?from=128.128.33.0&key=255.0.255.33 should return 127.128.32.33",
+    },
+    TestInfo {
+        task: 1,
+        test: 3,
+        description: "GET /2/dest?from=192.168.0.1&key=72.96.8.7 should return 8.8.8.8",
+    },
+    TestInfo {
+        task: 2,
+        test: 1,
+        description: "GET /2/key?from=10.0.0.0&to=11.2.3.255 should return 1.2.3.255",
+    },
+    TestInfo {
+        task: 2,
+        test: 2,
+        description: "GET /2/key?from=128.128.33.0&to=127.128.32.33 should return 255.0.255.33",
+    },
+    TestInfo {
+        task: 2,
+        test: 3,
+        description: "GET /2/key?from=192.168.0.1&to=8.8.8.8 should return 72.96.8.7",
+    },
+    TestInfo {
+        task: 3,
+        test: 1,
+        description: "GET /2/v6/dest?from=fe80::1&key=5:6:7::3333 should return fe85:6:7::3332",
+    },
+    TestInfo {
+        task: 3,
+        test: 2,
+        description: "GET /2/v6/dest with a zero-padded address should return 5555:ffff:c::c:1234:5555",
+    },
+    TestInfo {
+        task: 3,
+        test: 3,
+        description: "GET /2/v6/dest with a trailing :: address should return feed:beef:deaf:bad:c755:bed:ace:dad",
+    },
+    TestInfo {
+        task: 3,
+        test: 4,
+        description: "GET /2/v6/key?from=fe80::1&to=fe85:6:7::3332 should return 5:6:7::3333",
+    },
+    TestInfo {
+        task: 3,
+        test: 5,
+        description: "GET /2/v6/key with a zero-padded address should return ffff:ffff:c::c:1234:ffff",
+    },
+    TestInfo {
+        task: 3,
+        test: 6,
+        description: "GET /2/v6/key with a trailing :: address should return ::dab:bed:ace:dad",
+    },
+];
+
+pub(crate) const CHALLENGE: ChallengeInfo = ChallengeInfo {
+    challenge: "2",
+    description: "IPv4 and IPv6 address encryption",
+    core: 2,
+    bonus: &[0, 0, 50],
+    scored: true,
+    exclusive: false,
+    time_scaled: false,
+    tests: TESTS,
+    prerequisites: &[],
+    timeout: 20,
+    validator: &Day,
+};
+
+#[derive(Debug)]
+struct Day;
+
+impl Challenge for Day {
+    fn validate<'a>(
+        &'a self,
+        url: &'a str,
+        task: i32,
+        tx: Sender<SubmissionUpdate>,
+        tests: &'a mut TestTracker,
+    ) -> BoxFuture<'a, Option<ValidateResult>> {
+        Box::pin(async move {
+            Some(match task {
+                1 => task1(url, tx, tests).await,
+                2 => task2(url, tx, tests).await,
+                3 => task3(url, tx, tests).await,
+                _ => return None,
+            })
+        })
+    }
+}
+
+async fn task1(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    // TASK 1: Ipv4 dest
+    test = tests.start((1, 1)).await;
+    let url = &format!("{}/2/dest?from=10.0.0.0&key=1.2.3.255", base_url);
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    assert_text!(res, test, "11.2.3.255");
+    test = tests.start((1, 2)).await;
+    let url = &format!("{}/2/dest?from=128.128.33.0&key=255.0.255.33", base_url);
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    assert_text!(res, test, "127.128.32.33");
+    test = tests.start((1, 3)).await;
+    let url = &format!("{}/2/dest?from=192.168.0.1&key=72.96.8.7", base_url);
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    assert_text!(res, test, "8.8.8.8");
+    // TASK 1 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(1)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task2(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    // TASK 2: Ipv4 key
+    test = tests.start((2, 1)).await;
+    let url = &format!("{}/2/key?from=10.0.0.0&to=11.2.3.255", base_url);
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    assert_text!(res, test, "1.2.3.255");
+    test = tests.start((2, 2)).await;
+    let url = &format!("{}/2/key?from=128.128.33.0&to=127.128.32.33", base_url);
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    assert_text!(res, test, "255.0.255.33");
+    test = tests.start((2, 3)).await;
+    let url = &format!("{}/2/key?from=192.168.0.1&to=8.8.8.8", base_url);
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    assert_text!(res, test, "72.96.8.7");
+    // TASK 2 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(2)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task3(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    // TASK 3: Ipv6
+    test = tests.start((3, 1)).await;
+    let url = &format!("{}/2/v6/dest?from=fe80::1&key=5:6:7::3333", base_url);
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    assert_text!(res, test, "fe85:6:7::3332");
+    test = tests.start((3, 2)).await;
+    let url = &format!(
+        "{}/2/v6/dest?from=aaaa:0:0:0::aaaa&key=ffff:ffff:c:0:0:c:1234:ffff",
+        base_url
+    );
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    assert_text!(res, test, "5555:ffff:c::c:1234:5555");
+    test = tests.start((3, 3)).await;
+    let url = &format!(
+        "{}/2/v6/dest?from=feed:beef:deaf:bad:cafe::&key=::dab:bed:ace:dad",
+        base_url
+    );
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    assert_text!(res, test, "feed:beef:deaf:bad:c755:bed:ace:dad");
+    test = tests.start((3, 4)).await;
+    let url = &format!("{}/2/v6/key?from=fe80::1&to=fe85:6:7::3332", base_url);
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    assert_text!(res, test, "5:6:7::3333");
+    test = tests.start((3, 5)).await;
+    let url = &format!(
+        "{}/2/v6/key?from=aaaa::aaaa&to=5555:ffff:c:0:0:c:1234:5555",
+        base_url
+    );
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    assert_text!(res, test, "ffff:ffff:c::c:1234:ffff");
+    test = tests.start((3, 6)).await;
+    let url = &format!(
+        "{}/2/v6/key?from=feed:beef:deaf:bad:cafe::&to=feed:beef:deaf:bad:c755:bed:ace:dad",
+        base_url
+    );
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    assert_text!(res, test, "::dab:bed:ace:dad");
+    // TASK 3 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(3)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    use reqwest::{Method, StatusCode};
+    use shuttlings::transport::{response, MockTransport};
+
+    use crate::{tests::run_mocked, RunOutcome};
+
+    /// Answer `path` with `f` of the two addresses in the query parameters `params`
+    fn route<A: std::str::FromStr + 'static, R: ToString + 'static>(
+        mock: MockTransport,
+        path: &str,
+        params: (&'static str, &'static str),
+        f: fn(A, A) -> R,
+    ) -> MockTransport {
+        mock.route(Method::GET, path, move |req| {
+            let param = |name| {
+                let (_, value) = req.url().query_pairs().find(|(k, _)| k == name).unwrap();
+                value.parse::<A>().ok().unwrap()
+            };
+            response(
+                StatusCode::OK,
+                f(param(params.0), param(params.1)).to_string(),
+            )
+        })
+    }
+
+    fn octets(a: Ipv4Addr, b: Ipv4Addr, f: fn(u8, u8) -> u8) -> Ipv4Addr {
+        let (a, b) = (a.octets(), b.octets());
+        Ipv4Addr::from(std::array::from_fn::<u8, 4, _>(|i| f(a[i], b[i])))
+    }
+
+    fn wrapping_add(from: Ipv4Addr, key: Ipv4Addr) -> Ipv4Addr {
+        octets(from, key, u8::wrapping_add)
+    }
+
+    fn saturating_add(from: Ipv4Addr, key: Ipv4Addr) -> Ipv4Addr {
+        octets(from, key, u8::saturating_add)
+    }
+
+    fn wrapping_sub(to: Ipv4Addr, from: Ipv4Addr) -> Ipv4Addr {
+        octets(to, from, u8::wrapping_sub)
+    }
+
+    fn xor(a: Ipv6Addr, b: Ipv6Addr) -> Ipv6Addr {
+        Ipv6Addr::from(a.to_bits() ^ b.to_bits())
+    }
+
+    fn solution(dest: fn(Ipv4Addr, Ipv4Addr) -> Ipv4Addr) -> MockTransport {
+        let mock = route(MockTransport::new(), "/2/dest", ("from", "key"), dest);
+        let mock = route(mock, "/2/key", ("to", "from"), wrapping_sub);
+        let mock = route(mock, "/2/v6/dest", ("from", "key"), xor);
+        route(mock, "/2/v6/key", ("from", "to"), xor)
+    }
+
+    #[tokio::test]
+    async fn wrapping_octets_pass() {
+        for task in 1..=3 {
+            let mock = solution(wrapping_add);
+            assert_eq!(run_mocked("2", task, mock).await, RunOutcome::Completed);
+        }
+    }
+
+    #[tokio::test]
+    async fn saturating_octets_fail() {
+        assert_eq!(
+            run_mocked("2", 1, solution(saturating_add)).await,
+            RunOutcome::Failed { task: 1, test: 2 }
+        );
+    }
+}
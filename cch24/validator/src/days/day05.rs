@@ -0,0 +1,734 @@
+use reqwest::StatusCode;
+use shuttlings::{try_update, SubmissionUpdate};
+use tokio::sync::mpsc::Sender;
+
+use crate::{
+    helpers::{shared_client, SendWithRetry},
+    BoxFuture, Challenge, ChallengeInfo, TaskTest, TestInfo, TestTracker, ValidateResult,
+};
+
+pub(crate) const TESTS: &[TestInfo] = &[
+    TestInfo {
+        task: 1,
+        test: 1,
+        description: "POST /5/manifest with two valid orders should list both",
+    },
+    TestInfo {
+        task: 1,
+        test: 2,
+        description: "POST /5/manifest with a non-integer quantity should return 204 No Content",
+    },
+    TestInfo {
+        task: 1,
+        test: 3,
+        description: "POST /5/manifest with an empty order list should return 204 No Content",
+    },
+    TestInfo {
+        task: 1,
+        test: 4,
+        description: "POST /5/manifest should skip orders with a fractional quantity",
+    },
+    TestInfo {
+        task: 2,
+        test: 1,
+        description: "POST /5/manifest with a non-string package name should return 400 \"Invalid manifest\"",
+    },
+    TestInfo {
+        task: 2,
+        test: 2,
+        description: "POST /5/manifest with an invalid profile setting should return 400 \"Invalid manifest\"",
+    },
+    TestInfo {
+        task: 2,
+        test: 3,
+        description: "POST /5/manifest with an unusual but valid manifest without orders should return 204 No Content",
+    },
+    TestInfo {
+        task: 2,
+        test: 4,
+        description: "POST /5/manifest with an unknown edition should return 400 \"Invalid manifest\"",
+    },
+    TestInfo {
+        task: 2,
+        test: 5,
+        description: "POST /5/manifest with an unknown workspace resolver should return 400 \"Invalid manifest\"",
+    },
+    TestInfo {
+        task: 3,
+        test: 1,
+        description: "POST /5/manifest without the \"Christmas 2024\" keyword should return 400 \"Magic keyword not provided\"",
+    },
+    TestInfo {
+        task: 3,
+        test: 2,
+        description: "POST /5/manifest without any keywords should return 400 \"Magic keyword not provided\"",
+    },
+    TestInfo {
+        task: 3,
+        test: 3,
+        description: "POST /5/manifest with the keyword among others should return \"Milk 🥛: 1\"",
+    },
+    TestInfo {
+        task: 3,
+        test: 4,
+        description: "POST /5/manifest with the keyword inside a longer string should return 400 \"Magic keyword not provided\"",
+    },
+    TestInfo {
+        task: 4,
+        test: 1,
+        description: "POST /5/manifest with an HTML body should return 415 Unsupported Media Type",
+    },
+    TestInfo {
+        task: 4,
+        test: 2,
+        description: "POST /5/manifest with a YAML manifest should list its orders",
+    },
+    TestInfo {
+        task: 4,
+        test: 3,
+        description: "POST /5/manifest with a YAML manifest should skip invalid orders",
+    },
+    TestInfo {
+        task: 4,
+        test: 4,
+        description: "POST /5/manifest with an invalid YAML manifest should return 400 \"Invalid manifest\"",
+    },
+    TestInfo {
+        task: 4,
+        test: 5,
+        description: "POST /5/manifest with a JSON manifest should list its orders",
+    },
+    TestInfo {
+        task: 4,
+        test: 6,
+        description: "POST /5/manifest with a JSON manifest should skip invalid orders",
+    },
+    TestInfo {
+        task: 4,
+        test: 7,
+        description: "POST /5/manifest with a JSON manifest without the keyword should return 400 \"Magic keyword not provided\"",
+    },
+];
+
+pub(crate) const CHALLENGE: ChallengeInfo = ChallengeInfo {
+    challenge: "5",
+    description: "Cargo manifest order parsing",
+    core: 3,
+    bonus: &[0, 0, 0, 70],
+    scored: true,
+    exclusive: false,
+    time_scaled: false,
+    tests: TESTS,
+    prerequisites: &[],
+    timeout: 20,
+    validator: &Day,
+};
+
+#[derive(Debug)]
+struct Day;
+
+impl Challenge for Day {
+    fn validate<'a>(
+        &'a self,
+        url: &'a str,
+        task: i32,
+        tx: Sender<SubmissionUpdate>,
+        tests: &'a mut TestTracker,
+    ) -> BoxFuture<'a, Option<ValidateResult>> {
+        Box::pin(async move {
+            Some(match task {
+                1 => task1(url, tx, tests).await,
+                2 => task2(url, tx, tests).await,
+                3 => task3(url, tx, tests).await,
+                4 => task4(url, tx, tests).await,
+                _ => return None,
+            })
+        })
+    }
+}
+
+async fn task1(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    let url = &format!("{}/5/manifest", base_url);
+    const CT: &str = "Content-Type";
+    const TOML: &str = "application/toml";
+    // TASK 1: order list
+    test = tests.start((1, 1)).await;
+    let res = client
+        .post(url)
+        .header(CT, TOML)
+        .body(
+            r#"
+[package]
+name = "not-a-gift-order"
+authors = ["Not Santa"]
+keywords = ["Christmas 2024"]
+
+[[package.metadata.orders]]
+item = "Toy car"
+quantity = 2
+
+[[package.metadata.orders]]
+item = "Lego brick"
+quantity = 230
+"#,
+        )
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_text!(res, test, "Toy car: 2\nLego brick: 230");
+    test = tests.start((1, 2)).await;
+    let res = client
+        .post(url)
+        .header(CT, TOML)
+        .body(
+            r#"
+[package]
+name = "coal-in-a-bowl"
+authors = ["H4CK3R_13E7"]
+keywords = ["Christmas 2024"]
+
+[[package.metadata.orders]]
+item = "Coal"
+quantity = "Hahaha get rekt"
+"#,
+        )
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::NO_CONTENT);
+    test = tests.start((1, 3)).await;
+    let res = client
+        .post(url)
+        .header(CT, TOML)
+        .body(
+            r#"
+[package]
+name = "coal-in-a-bowl"
+authors = ["H4CK3R_13E7"]
+keywords = ["Christmas 2024"]
+
+package.metadata.orders = []
+"#,
+        )
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::NO_CONTENT);
+    test = tests.start((1, 4)).await;
+    let res = client
+        .post(url)
+        .header(CT, TOML)
+        .body(
+            r#"
+[package]
+name = "not-a-gift-order"
+authors = ["Not Santa"]
+keywords = ["Christmas 2024"]
+
+[[package.metadata.orders]]
+item = "Toy car"
+quantity = 2
+
+[[package.metadata.orders]]
+item = "Lego brick"
+quantity = 1.5
+
+[[package.metadata.orders]]
+item = "Doll"
+quantity = 2
+
+[[package.metadata.orders]]
+quantity = 5
+item = "Cookie:::\n"
+
+[[package.metadata.orders]]
+item = "Thing"
+count = 3
+"#,
+        )
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_text!(res, test, "Toy car: 2\nDoll: 2\nCookie:::\n: 5");
+    // TASK 1 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(1)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task2(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    let url = &format!("{}/5/manifest", base_url);
+    const CT: &str = "Content-Type";
+    const TOML: &str = "application/toml";
+    // TASK 2: manifest parsing
+    test = tests.start((2, 1)).await;
+    let res = client
+        .post(url)
+        .header(CT, TOML)
+        .body(
+            r#"
+[package]
+name = false
+authors = ["Not Santa"]
+keywords = ["Christmas 2024"]
+"#,
+        )
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::BAD_REQUEST);
+    assert_text!(res, test, "Invalid manifest");
+    test = tests.start((2, 2)).await;
+    let res = client
+        .post(url)
+        .header(CT, TOML)
+        .body(
+            r#"
+[package]
+name = "not-a-gift-order"
+authors = ["Not Santa"]
+keywords = ["Christmas 2024"]
+
+[profile.release]
+incremental = "stonks"
+"#,
+        )
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::BAD_REQUEST);
+    assert_text!(res, test, "Invalid manifest");
+    test = tests.start((2, 3)).await;
+    let res = client
+        .post(url)
+        .header(CT, TOML)
+        .body(
+            r#"
+[package]
+name = "big-chungus"
+version = "2.0.24"
+edition = "2024"
+resolver = "2"
+readme.workspace = true
+keywords = ["Christmas 2024"]
+
+[dependencies]
+shuttle-runtime = "1.0.0+when"
+
+[target.shuttlings.dependencies]
+cch24-validator = "5+more"
+
+[profile.release]
+incremental = false
+
+[package.metadata.stuff]
+thing = ["yes", "no"]
+"#,
+        )
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::NO_CONTENT);
+    test = tests.start((2, 4)).await;
+    let res = client
+        .post(url)
+        .header(CT, TOML)
+        .body(
+            r#"
+[package]
+name = "chig-bungus"
+edition = "2023"
+
+[workspace.dependencies]
+shuttle-bring-your-own-cloud = "0.0.0"
+"#,
+        )
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::BAD_REQUEST);
+    assert_text!(res, test, "Invalid manifest");
+    test = tests.start((2, 5)).await;
+    let res = client
+        .post(url)
+        .header(CT, TOML)
+        .body(
+            r#"
+[package]
+name = "chig-bungus"
+
+[workspace]
+resolver = "135"
+
+[workspace.dependencies]
+shuttle-bring-your-own-cloud = "0.0.0"
+"#,
+        )
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::BAD_REQUEST);
+    assert_text!(res, test, "Invalid manifest");
+    // TASK 2 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(2)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task3(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    let url = &format!("{}/5/manifest", base_url);
+    const CT: &str = "Content-Type";
+    const TOML: &str = "application/toml";
+    // TASK 3: keyword
+    test = tests.start((3, 1)).await;
+    let res = client
+        .post(url)
+        .header(CT, TOML)
+        .body(
+            r#"
+[package]
+name = "grass"
+authors = ["A vegan cow"]
+keywords = ["Moooooo"]
+"#,
+        )
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::BAD_REQUEST);
+    assert_text!(res, test, "Magic keyword not provided");
+    test = tests.start((3, 2)).await;
+    let res = client
+        .post(url)
+        .header(CT, TOML)
+        .body(
+            r#"
+[package]
+name = "chig-bungus"
+
+[workspace]
+resolver = "2"
+
+[workspace.dependencies]
+shuttle-bring-your-own-cloud = "0.0.0"
+"#,
+        )
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::BAD_REQUEST);
+    assert_text!(res, test, "Magic keyword not provided");
+    test = tests.start((3, 3)).await;
+    let res = client
+        .post(url)
+        .header(CT, TOML)
+        .body(
+            r#"
+[package]
+name = "slurp"
+authors = ["A crazy cow"]
+keywords = ["MooOooooooOOOOoo00oo=oOooooo", "Mew", "Moh", "Christmas 2024"]
+metadata.orders = [{ item = "Milk 🥛", quantity = 1 }]
+"#,
+        )
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_text!(res, test, "Milk 🥛: 1");
+    test = tests.start((3, 4)).await;
+    let res = client
+        .post(url)
+        .header(CT, TOML)
+        .body(
+            r#"
+[package]
+name = "snow"
+authors = ["The Cow of Christmas"]
+keywords = ["Moooooo Merry Christmas 2024"]
+"#,
+        )
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::BAD_REQUEST);
+    assert_text!(res, test, "Magic keyword not provided");
+    // TASK 3 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(3)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task4(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    let url = &format!("{}/5/manifest", base_url);
+    const CT: &str = "Content-Type";
+    const YAML: &str = "application/yaml";
+    const JSON: &str = "application/json";
+    // TASK 4: Yaml, Json
+    test = tests.start((4, 1)).await;
+    let res = client
+        .post(url)
+        .header(CT, "text/html")
+        .body("<h1>Hello, bird!</h1>")
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    test = tests.start((4, 2)).await;
+    let res = client
+        .post(url)
+        .header(CT, YAML)
+        .body(
+            r#"
+package:
+  name: big-chungus-sleigh
+  version: "2.0.24"
+  metadata:
+    orders:
+      - item: "Toy train"
+        quantity: 5
+      - item: "Toy car"
+        quantity: 3
+  rust-version: "1.69"
+  keywords:
+    - "Christmas 2024"
+"#,
+        )
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_text!(res, test, "Toy train: 5\nToy car: 3");
+    test = tests.start((4, 3)).await;
+    let res = client
+        .post(url)
+        .header(CT, YAML)
+        .body(
+            r#"
+package:
+  name: big-chungus-sleigh
+  metadata:
+    orders:
+      - item: "Toy train"
+        quantity: 5
+      - item: "Coal"
+      - item: "Horse"
+        quantity: 2
+  keywords:
+    - "Christmas 2024"
+"#,
+        )
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_text!(res, test, "Toy train: 5\nHorse: 2");
+    test = tests.start((4, 4)).await;
+    let res = client
+        .post(url)
+        .header(CT, YAML)
+        .body(
+            r#"
+package:
+  name: big-chungus-sleigh
+  metadata:
+    orders:
+      - item: "Toy train"
+        quantity: 5
+  rust-version: true
+  keywords:
+    - "Christmas 2024"
+"#,
+        )
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::BAD_REQUEST);
+    assert_text!(res, test, "Invalid manifest");
+    test = tests.start((4, 5)).await;
+    let res = client
+        .post(url)
+        .header(CT, JSON)
+        .body(
+            r#"
+{
+  "package": {
+    "name": "big-chungus-sleigh",
+    "version": "2.0.24",
+    "metadata": {
+      "orders": [
+        {
+          "item": "Toy train",
+          "quantity": 5
+        },
+        {
+          "item": "Toy car",
+          "quantity": 3
+        }
+      ]
+    },
+    "rust-version": "1.69",
+    "keywords": [
+      "Christmas 2024"
+    ]
+  }
+
+    Ok(())
+}
+"#,
+        )
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_text!(res, test, "Toy train: 5\nToy car: 3");
+    test = tests.start((4, 6)).await;
+    let res = client
+        .post(url)
+        .header(CT, JSON)
+        .body(
+            r#"
+{
+  "package": {
+    "name": "big-chungus-sleigh",
+    "metadata": {
+      "orders": [
+        {
+          "item": "Toy train",
+          "quantity": 5
+        },
+        {
+          "item": "Coal"
+        },
+        {
+          "item": "Horse",
+          "quantity": 2
+        }
+      ]
+    },
+    "keywords": [
+      "Christmas 2024"
+    ]
+  }
+}
+"#,
+        )
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_text!(res, test, "Toy train: 5\nHorse: 2");
+    test = tests.start((4, 7)).await;
+    let res = client
+        .post(url)
+        .header(CT, JSON)
+        .body(
+            r#"
+{
+  "package": {
+    "name": "big-chungus-sleigh",
+    "metadata": {
+      "orders": [
+        {
+          "item": "Toy train",
+          "quantity": 5
+        }
+      ]
+    }
+  }
+}
+"#,
+        )
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::BAD_REQUEST);
+    assert_text!(res, test, "Magic keyword not provided");
+    // TASK 4 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(4)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::Method;
+    use shuttlings::transport::{response, MockTransport};
+
+    use super::*;
+    use crate::{tests::run_mocked, RunOutcome};
+
+    /// A solution that lists the orders whose quantity `counts`
+    fn solution(counts: fn(&toml::Value) -> bool) -> MockTransport {
+        MockTransport::new().route(Method::POST, "/5/manifest", move |req| {
+            let body = req.body().and_then(|b| b.as_bytes()).unwrap_or_default();
+            let manifest: toml::Value = toml::from_str(std::str::from_utf8(body).unwrap()).unwrap();
+            let orders: Vec<String> = manifest
+                .get("package")
+                .and_then(|p| p.get("metadata"))
+                .and_then(|m| m.get("orders"))
+                .and_then(toml::Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|order| {
+                    let item = order.get("item")?.as_str()?;
+                    let quantity = order.get("quantity").filter(|q| counts(q))?;
+                    Some(format!("{item}: {quantity}"))
+                })
+                .collect();
+            match orders.is_empty() {
+                true => response(StatusCode::NO_CONTENT, ""),
+                false => response(StatusCode::OK, orders.join("\n")),
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn integer_quantities_pass() {
+        let mock = solution(toml::Value::is_integer);
+        assert_eq!(run_mocked("5", 1, mock).await, RunOutcome::Completed);
+    }
+
+    #[tokio::test]
+    async fn fractional_quantities_fail() {
+        let mock = solution(|q| q.is_integer() || q.is_float());
+        assert_eq!(
+            run_mocked("5", 1, mock).await,
+            RunOutcome::Failed { task: 1, test: 4 }
+        );
+    }
+}
@@ -0,0 +1,556 @@
+use reqwest::StatusCode;
+use serde_json::json;
+use shuttlings::{
+    testing::{high_latency, Tolerance},
+    try_update, LogLevel, SubmissionUpdate,
+};
+use tokio::{
+    sync::mpsc::Sender,
+    time::{sleep, Duration, Instant},
+};
+use tracing::info;
+
+use crate::{
+    helpers::{scaled, shared_client, timing_slack, SendWithRetry},
+    BoxFuture, Challenge, ChallengeInfo, TaskTest, TestFailure, TestInfo, TestTracker,
+    ValidateResult,
+};
+
+/// Added to the pauses that fill the bucket back up with [`timing_slack`],
+/// since the bucket never holds more than five withdrawals
+const REFILL_MARGIN: Duration = Duration::from_millis(500);
+
+pub(crate) const TESTS: &[TestInfo] = &[
+    TestInfo {
+        task: 1,
+        test: 1,
+        description:
+            "POST /9/milk should withdraw milk five times, then return 429 \"No milk available\"",
+    },
+    TestInfo {
+        task: 2,
+        test: 1,
+        description: "POST /9/milk with {\"liters\": 2} should convert to gallons (within 10 times the tolerance with --lenient)",
+    },
+    TestInfo {
+        task: 2,
+        test: 2,
+        description: "POST /9/milk with {\"gallons\": -2.000000000000001} should convert to liters (within 10 times the tolerance with --lenient)",
+    },
+    TestInfo {
+        task: 2,
+        test: 3,
+        description: "POST /9/milk without JSON should return \"Milk withdrawn\"",
+    },
+    TestInfo {
+        task: 2,
+        test: 4,
+        description: "POST /9/milk with an empty JSON object should return 400 Bad Request",
+    },
+    TestInfo {
+        task: 2,
+        test: 5,
+        description: "POST /9/milk with both liters and gallons should return 400 Bad Request",
+    },
+    TestInfo {
+        task: 2,
+        test: 6,
+        description: "POST /9/milk with an empty bucket should return 429 \"No milk available\"",
+    },
+    TestInfo {
+        task: 2,
+        test: 7,
+        description: "POST /9/milk with an empty JSON body should return 400 Bad Request",
+    },
+    TestInfo {
+        task: 2,
+        test: 8,
+        description: "POST /9/milk with an empty JSON body should still return 400 Bad Request",
+    },
+    TestInfo {
+        task: 2,
+        test: 9,
+        description: "POST /9/milk with invalid JSON should return 400 Bad Request",
+    },
+    TestInfo {
+        task: 2,
+        test: 10,
+        description: "POST /9/milk with a huge number of liters should convert to gallons (within 10 times the tolerance with --lenient)",
+    },
+    TestInfo {
+        task: 2,
+        test: 11,
+        description: "POST /9/milk with a non-JSON content type should return \"Milk withdrawn\"",
+    },
+    TestInfo {
+        task: 3,
+        test: 1,
+        description: "POST /9/milk with {\"litres\": 7.4} should convert to pints (within 10 times the tolerance with --lenient)",
+    },
+    TestInfo {
+        task: 3,
+        test: 2,
+        description: "POST /9/milk with {\"pints\": 32630.25} should convert to litres (within 10 times the tolerance with --lenient)",
+    },
+    TestInfo {
+        task: 3,
+        test: 3,
+        description: "POST /9/milk with {\"litres\": -0.0} should return 0 pints",
+    },
+    TestInfo {
+        task: 3,
+        test: 4,
+        description: "POST /9/milk with both litres and liters should return 400 Bad Request",
+    },
+    TestInfo {
+        task: 3,
+        test: 5,
+        description: "POST /9/milk with a duplicate litres key should return 400 Bad Request",
+    },
+    TestInfo {
+        task: 3,
+        test: 6,
+        description: "POST /9/milk with both gallons and pints should return 400 Bad Request",
+    },
+    TestInfo {
+        task: 3,
+        test: 7,
+        description: "POST /9/milk with an empty bucket should return 429 \"No milk available\"",
+    },
+    TestInfo {
+        task: 4,
+        test: 1,
+        description: "POST /9/refill should return 200 OK",
+    },
+    TestInfo {
+        task: 4,
+        test: 2,
+        description: "POST /9/refill should fill the bucket back up to five withdrawals",
+    },
+];
+
+pub(crate) const CHALLENGE: ChallengeInfo = ChallengeInfo {
+    challenge: "9",
+    description: "Rate limited milk bucket",
+    core: 3,
+    bonus: &[0, 0, 0, 75],
+    scored: true,
+    exclusive: true,
+    time_scaled: true,
+    tests: TESTS,
+    prerequisites: &[],
+    timeout: 90,
+    validator: &Day,
+};
+
+#[derive(Debug)]
+struct Day;
+
+impl Challenge for Day {
+    fn validate<'a>(
+        &'a self,
+        url: &'a str,
+        task: i32,
+        tx: Sender<SubmissionUpdate>,
+        tests: &'a mut TestTracker,
+    ) -> BoxFuture<'a, Option<ValidateResult>> {
+        Box::pin(async move {
+            Some(match task {
+                1 => task1(url, tx, tests).await,
+                2 => task2(url, tx, tests).await,
+                3 => task3(url, tx, tests).await,
+                4 => task4(url, tx, tests).await,
+                _ => return None,
+            })
+        })
+    }
+}
+
+async fn task1(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    // TASK 1: leaky bucket
+    let test = tests.start((1, 1)).await;
+    let url = &format!("{}/9/milk", base_url);
+    let mut milk = Milk::new(&client, url);
+    milk.withdraw(test).await?;
+    milk.withdraw(test).await?;
+    milk.withdraw(test).await?;
+    milk.withdraw(test).await?;
+    milk.withdraw(test).await?;
+    if milk.slow() {
+        try_update(&tx, SubmissionUpdate::LogLine {
+            level: LogLevel::Info,
+            message: "Info: High network latency detected. This test is timing-sensitive and might therefore fail.".to_owned(),
+        }).await?;
+    }
+    milk.expect_empty(test).await?;
+    sleep(Duration::from_secs(1)).await;
+    milk.restart();
+    milk.withdraw(test).await?;
+    milk.expect_empty(test).await?;
+    sleep(Duration::from_secs(2)).await;
+    milk.restart();
+    milk.withdraw(test).await?;
+    milk.withdraw(test).await?;
+    milk.expect_empty(test).await?;
+    milk.expect_empty(test).await?;
+    // TASK 1 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(1)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+/// How long the bucket takes to fill back up
+fn refill_pause() -> Duration {
+    let pause = scaled(Duration::from_secs(5));
+    if timing_slack() {
+        pause + REFILL_MARGIN
+    } else {
+        pause
+    }
+}
+
+async fn task2(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    let url = &format!("{}/9/milk", base_url);
+    let mut milk = Milk::new(&client, url);
+    // reset bucket
+    sleep(refill_pause()).await;
+    // TASK 2: gallons
+    test = tests.start((2, 1)).await;
+    let res = milk
+        .send(test, client.post(url).json(&json!({"liters": 2})))
+        .await?;
+    assert_status!(res, test, StatusCode::OK);
+    let j = res.json::<serde_json::Value>().await.map_err(|_| test)?;
+    assert_!(test, j.as_object().is_some_and(|o| o.len() == 1));
+    assert_json_float_near!(j, test, "/gallons", 0.5283441, Tolerance::Relative(0.0001));
+    test = tests.start((2, 2)).await;
+    let res = milk
+        .send(
+            test,
+            client
+                .post(url)
+                .json(&json!({"gallons": -2.000000000000001})),
+        )
+        .await?;
+    assert_status!(res, test, StatusCode::OK);
+    let j = res.json::<serde_json::Value>().await.map_err(|_| test)?;
+    assert_!(test, j.as_object().is_some_and(|o| o.len() == 1));
+    assert_json_float_near!(j, test, "/liters", -7.5708237, Tolerance::Relative(0.0001));
+    test = tests.start((2, 3)).await;
+    milk.withdraw(test).await?;
+    test = tests.start((2, 4)).await;
+    let res = milk.send(test, client.post(url).json(&json!({}))).await?;
+    assert_status!(res, test, StatusCode::BAD_REQUEST);
+    test = tests.start((2, 5)).await;
+    let res = milk
+        .send(
+            test,
+            client
+                .post(url)
+                .json(&json!({"liters": 0, "gallons": 1337})),
+        )
+        .await?;
+    assert_status!(res, test, StatusCode::BAD_REQUEST);
+    test = tests.start((2, 6)).await;
+    milk.expect_empty(test).await?;
+    test = tests.start((2, 7)).await;
+    sleep(Duration::from_secs(1)).await;
+    let res = milk
+        .send(
+            test,
+            client
+                .post(url)
+                .header("Content-Type", "application/json")
+                .body(""),
+        )
+        .await?;
+    assert_status!(res, test, StatusCode::BAD_REQUEST);
+    test = tests.start((2, 8)).await;
+    sleep(Duration::from_secs(1)).await;
+    let res = milk
+        .send(
+            test,
+            client
+                .post(url)
+                .header("Content-Type", "application/json")
+                .body(""),
+        )
+        .await?;
+    assert_status!(res, test, StatusCode::BAD_REQUEST);
+    test = tests.start((2, 9)).await;
+    sleep(Duration::from_secs(1)).await;
+    let res = milk
+        .send(
+            test,
+            client
+                .post(url)
+                .header("Content-Type", "application/json")
+                .body("{'liters':0}"),
+        )
+        .await?;
+    assert_status!(res, test, StatusCode::BAD_REQUEST);
+    test = tests.start((2, 10)).await;
+    sleep(Duration::from_secs(1)).await;
+    let res = milk
+        .send(
+            test,
+            client
+                .post(url)
+                // (incoming f32 is truncated)
+                .json(&json!({"liters": 123123123123.0})),
+        )
+        .await?;
+    assert_status!(res, test, StatusCode::OK);
+    let j = res.json::<serde_json::Value>().await.map_err(|_| test)?;
+    assert_!(test, j.as_object().is_some_and(|o| o.len() == 1));
+    assert_json_float_near!(
+        j,
+        test,
+        "/gallons",
+        32525687000.0,
+        Tolerance::Relative(0.0001)
+    );
+    test = tests.start((2, 11)).await;
+    sleep(Duration::from_secs(1)).await;
+    let res = milk
+        .send(
+            test,
+            client
+                .post(url)
+                .header("Content-Type", "text/html")
+                .body(r#"{"liters":0}"#),
+        )
+        .await?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_text!(res, test, "Milk withdrawn\n");
+    // TASK 2 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(2)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task3(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    let url = &format!("{}/9/milk", base_url);
+    let mut milk = Milk::new(&client, url);
+    // reset bucket
+    sleep(refill_pause()).await;
+    // TASK 3: litres/pints
+    test = tests.start((3, 1)).await;
+    let res = milk
+        .send(test, client.post(url).json(&json!({"litres": 7.4})))
+        .await?;
+    assert_status!(res, test, StatusCode::OK);
+    let j = res.json::<serde_json::Value>().await.map_err(|_| test)?;
+    assert_!(test, j.as_object().is_some_and(|o| o.len() == 1));
+    assert_json_float_near!(j, test, "/pints", 13.02218, Tolerance::Relative(0.0001));
+    test = tests.start((3, 2)).await;
+    let res = milk
+        .send(test, client.post(url).json(&json!({"pints": 32630.25})))
+        .await?;
+    assert_status!(res, test, StatusCode::OK);
+    let j = res.json::<serde_json::Value>().await.map_err(|_| test)?;
+    assert_!(test, j.as_object().is_some_and(|o| o.len() == 1));
+    assert_json_float_near!(j, test, "/litres", 18542.508, Tolerance::Relative(0.0001));
+    test = tests.start((3, 3)).await;
+    let res = milk
+        .send(test, client.post(url).json(&json!({"litres": -0.0})))
+        .await?;
+    assert_status!(res, test, StatusCode::OK);
+    let j = res.json::<serde_json::Value>().await.map_err(|_| test)?;
+    assert_!(test, j.as_object().is_some_and(|o| o.len() == 1));
+    assert_json_float_near!(j, test, "/pints", 0.0, Tolerance::Absolute(0.0));
+    test = tests.start((3, 4)).await;
+    let res = milk
+        .send(
+            test,
+            client
+                .post(url)
+                .json(&json!({"litres": 7.4, "liters": 7.4})),
+        )
+        .await?;
+    assert_status!(res, test, StatusCode::BAD_REQUEST);
+    test = tests.start((3, 5)).await;
+    let res = milk
+        .send(
+            test,
+            client.post(url).json(r#"{"litres": 7.4, "litres": 7.6}"#),
+        )
+        .await?;
+    assert_status!(res, test, StatusCode::BAD_REQUEST);
+    test = tests.start((3, 6)).await;
+    sleep(Duration::from_secs(1)).await;
+    let res = milk
+        .send(
+            test,
+            client.post(url).json(&json!({"gallons": 2, "pints": 0})),
+        )
+        .await?;
+    assert_status!(res, test, StatusCode::BAD_REQUEST);
+    test = tests.start((3, 7)).await;
+    milk.expect_empty(test).await?;
+    // TASK 3 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(3)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task4(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    // TASK 4: refill
+    test = tests.start((4, 1)).await;
+    let url = &format!("{}/9/milk", base_url);
+    let refill_url = &format!("{}/9/refill", base_url);
+    let mut milk = Milk::new(&client, url);
+    let res = milk.send(test, client.post(refill_url)).await?;
+    assert_status!(res, test, StatusCode::OK);
+    test = tests.start((4, 2)).await;
+    milk.restart();
+    milk.withdraw(test).await?;
+    milk.withdraw(test).await?;
+    milk.withdraw(test).await?;
+    milk.withdraw(test).await?;
+    milk.withdraw(test).await?;
+    milk.expect_empty(test).await?;
+    let res = milk.send(test, client.post(refill_url)).await?;
+    assert_status!(res, test, StatusCode::OK);
+    milk.restart();
+    milk.withdraw(test).await?;
+    milk.withdraw(test).await?;
+    milk.withdraw(test).await?;
+    milk.withdraw(test).await?;
+    milk.withdraw(test).await?;
+    milk.expect_empty(test).await?;
+    milk.expect_empty(test).await?;
+    // TASK 4 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(4)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+/// The requests of a rate limited sequence, timed so that a failure can tell slow requests from a wrong rate limit
+struct Milk<'a> {
+    client: &'a reqwest::Client,
+    url: &'a str,
+    /// How long each request since the last [`Milk::restart`] took
+    latencies: Vec<Duration>,
+    /// Whether the sequence already withdrew once more than the bucket held
+    slack_used: bool,
+}
+
+impl<'a> Milk<'a> {
+    fn new(client: &'a reqwest::Client, url: &'a str) -> Self {
+        Self {
+            client,
+            url,
+            latencies: Vec::new(),
+            slack_used: false,
+        }
+    }
+
+    /// Start a new sequence, after the bucket was refilled
+    fn restart(&mut self) {
+        self.latencies.clear();
+        self.slack_used = false;
+    }
+
+    /// Whether the requests of the sequence took long enough for the bucket to refill during them
+    fn slow(&self) -> bool {
+        high_latency(&self.latencies)
+    }
+
+    async fn send(
+        &mut self,
+        test: TaskTest,
+        req: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, TestFailure> {
+        let sent = Instant::now();
+        let res = req.send_with_retry().await.map_err(|_| test)?;
+        self.latencies.push(sent.elapsed());
+        Ok(res)
+    }
+
+    async fn withdraw(&mut self, test: TaskTest) -> ValidateResult {
+        let res = self.send(test, self.client.post(self.url)).await?;
+        self.check(test, res, StatusCode::OK, "Milk withdrawn\n")
+            .await
+    }
+
+    /// Expect the bucket to be empty. With [`timing_slack`], a slow sequence may withdraw once more first.
+    async fn expect_empty(&mut self, test: TaskTest) -> ValidateResult {
+        let mut res = self.send(test, self.client.post(self.url)).await?;
+        if res.status() == StatusCode::OK && timing_slack() && self.slow() && !self.slack_used {
+            info!(
+                latencies = self.latencies(),
+                "Allowing one more withdrawal after slow requests"
+            );
+            self.slack_used = true;
+            res = self.send(test, self.client.post(self.url)).await?;
+        }
+        self.check(
+            test,
+            res,
+            StatusCode::TOO_MANY_REQUESTS,
+            "No milk available\n",
+        )
+        .await
+    }
+
+    /// Check the response, with the latencies of the sequence in the failure
+    async fn check(
+        &self,
+        test: TaskTest,
+        res: reqwest::Response,
+        status: StatusCode,
+        text: &str,
+    ) -> ValidateResult {
+        let checked = async {
+            assert_status!(res, test, status);
+            assert_text!(res, test, text);
+            Ok::<_, TestFailure>(())
+        };
+        checked.await.map_err(|f| TestFailure {
+            actual: f
+                .actual
+                .map(|actual| format!("{actual} after requests that took {}", self.latencies())),
+            ..f
+        })
+    }
+
+    fn latencies(&self) -> String {
+        self.latencies
+            .iter()
+            .map(|l| format!("{}ms", l.as_millis()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
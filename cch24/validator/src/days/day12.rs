@@ -0,0 +1,588 @@
+use reqwest::{Client, StatusCode};
+use shuttlings::{try_update, SubmissionUpdate};
+use tokio::{sync::mpsc::Sender, time::Duration};
+
+use crate::{
+    helpers::{scaled, shared_client, SendWithRetry},
+    BoxFuture, Challenge, ChallengeInfo, TaskTest, TestInfo, TestTracker, ValidateResult,
+};
+
+pub(crate) const TESTS: &[TestInfo] = &[
+    TestInfo {
+        task: 1,
+        test: 1,
+        description: "POST /12/reset should return the empty board",
+    },
+    TestInfo {
+        task: 1,
+        test: 2,
+        description: "GET /12/board should return the empty board",
+    },
+    TestInfo {
+        task: 2,
+        test: 1,
+        description: "Placing four 🍪 in one column should report that 🍪 wins",
+    },
+    TestInfo {
+        task: 2,
+        test: 2,
+        description: "Placing four 🍪 in a row should report that 🍪 wins",
+    },
+    TestInfo {
+        task: 2,
+        test: 3,
+        description: "Filling the board without four in a row should report no winner",
+    },
+    TestInfo {
+        task: 2,
+        test: 4,
+        description: "Placing four 🥛 diagonally should report that 🥛 wins",
+    },
+    TestInfo {
+        task: 2,
+        test: 5,
+        description: "POST /12/place with an invalid team or column should return 400 Bad Request",
+    },
+    TestInfo {
+        task: 3,
+        test: 1,
+        description: "GET /12/random-board should return the seeded boards after a reset",
+    },
+];
+
+pub(crate) const CHALLENGE: ChallengeInfo = ChallengeInfo {
+    challenge: "12",
+    description: "Connect four on a shared board",
+    core: 2,
+    bonus: &[0, 0, 75],
+    scored: true,
+    exclusive: false,
+    time_scaled: true,
+    tests: TESTS,
+    prerequisites: &[],
+    timeout: 30,
+    validator: &Day,
+};
+
+#[derive(Debug)]
+struct Day;
+
+impl Challenge for Day {
+    fn validate<'a>(
+        &'a self,
+        url: &'a str,
+        task: i32,
+        tx: Sender<SubmissionUpdate>,
+        tests: &'a mut TestTracker,
+    ) -> BoxFuture<'a, Option<ValidateResult>> {
+        Box::pin(async move {
+            Some(match task {
+                1 => task1(url, tx, tests).await,
+                2 => task2(url, tx, tests).await,
+                3 => task3(url, tx, tests).await,
+                _ => return None,
+            })
+        })
+    }
+}
+
+async fn task1(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    // TASK 1: board and reset
+    test = tests.start((1, 1)).await;
+    let reset_url = &format!("{}/12/reset", base_url);
+    let res = client
+        .post(reset_url)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_text!(
+        res,
+        test,
+        "\
+⬜⬛⬛⬛⬛⬜
+⬜⬛⬛⬛⬛⬜
+⬜⬛⬛⬛⬛⬜
+⬜⬛⬛⬛⬛⬜
+⬜⬜⬜⬜⬜⬜
+"
+    );
+    test = tests.start((1, 2)).await;
+    let board_url = &format!("{}/12/board", base_url);
+    let res = client
+        .get(board_url)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_text!(
+        res,
+        test,
+        "\
+⬜⬛⬛⬛⬛⬜
+⬜⬛⬛⬛⬛⬜
+⬜⬛⬛⬛⬛⬜
+⬜⬛⬛⬛⬛⬜
+⬜⬜⬜⬜⬜⬜
+"
+    );
+    // TASK 1 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(1)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task2(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    // TASK 2: gameplay
+    test = tests.start((2, 1)).await;
+    let reset_url = &format!("{}/12/reset", base_url);
+    let board_url = &format!("{}/12/board", base_url);
+    let res = client
+        .post(reset_url)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_text!(
+        res,
+        test,
+        "\
+⬜⬛⬛⬛⬛⬜
+⬜⬛⬛⬛⬛⬜
+⬜⬛⬛⬛⬛⬜
+⬜⬛⬛⬛⬛⬜
+⬜⬜⬜⬜⬜⬜
+"
+    );
+    async fn place(
+        client: &Client,
+        base_url: &str,
+        test: TaskTest,
+        team: &str,
+        col: i32,
+    ) -> Result<reqwest::Response, TaskTest> {
+        client
+            .post(format!("{}/12/place/{}/{}", base_url, team, col))
+            .send_with_retry()
+            .await
+            .map_err(|_| test)
+    }
+    let res = place(&client, base_url, test, "cookie", 1).await?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_text!(
+        res,
+        test,
+        "\
+⬜⬛⬛⬛⬛⬜
+⬜⬛⬛⬛⬛⬜
+⬜⬛⬛⬛⬛⬜
+⬜🍪⬛⬛⬛⬜
+⬜⬜⬜⬜⬜⬜
+"
+    );
+    let res = place(&client, base_url, test, "cookie", 1).await?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_text!(
+        res,
+        test,
+        "\
+⬜⬛⬛⬛⬛⬜
+⬜⬛⬛⬛⬛⬜
+⬜🍪⬛⬛⬛⬜
+⬜🍪⬛⬛⬛⬜
+⬜⬜⬜⬜⬜⬜
+"
+    );
+    let res = place(&client, base_url, test, "cookie", 1).await?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_text!(
+        res,
+        test,
+        "\
+⬜⬛⬛⬛⬛⬜
+⬜🍪⬛⬛⬛⬜
+⬜🍪⬛⬛⬛⬜
+⬜🍪⬛⬛⬛⬜
+⬜⬜⬜⬜⬜⬜
+"
+    );
+    let res = place(&client, base_url, test, "cookie", 1).await?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_text!(
+        res,
+        test,
+        "\
+⬜🍪⬛⬛⬛⬜
+⬜🍪⬛⬛⬛⬜
+⬜🍪⬛⬛⬛⬜
+⬜🍪⬛⬛⬛⬜
+⬜⬜⬜⬜⬜⬜
+🍪 wins!
+"
+    );
+    let res = place(&client, base_url, test, "cookie", 1).await?;
+    assert_status!(res, test, StatusCode::SERVICE_UNAVAILABLE);
+    assert_text!(
+        res,
+        test,
+        "\
+⬜🍪⬛⬛⬛⬜
+⬜🍪⬛⬛⬛⬜
+⬜🍪⬛⬛⬛⬜
+⬜🍪⬛⬛⬛⬜
+⬜⬜⬜⬜⬜⬜
+🍪 wins!
+"
+    );
+    let res = place(&client, base_url, test, "milk", 2).await?;
+    assert_status!(res, test, StatusCode::SERVICE_UNAVAILABLE);
+    assert_text!(
+        res,
+        test,
+        "\
+⬜🍪⬛⬛⬛⬜
+⬜🍪⬛⬛⬛⬜
+⬜🍪⬛⬛⬛⬜
+⬜🍪⬛⬛⬛⬜
+⬜⬜⬜⬜⬜⬜
+🍪 wins!
+"
+    );
+    let res = client
+        .get(board_url)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_text!(
+        res,
+        test,
+        "\
+⬜🍪⬛⬛⬛⬜
+⬜🍪⬛⬛⬛⬜
+⬜🍪⬛⬛⬛⬜
+⬜🍪⬛⬛⬛⬜
+⬜⬜⬜⬜⬜⬜
+🍪 wins!
+"
+    );
+    test = tests.start((2, 2)).await;
+    let res = client
+        .post(reset_url)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_text!(
+        res,
+        test,
+        "\
+⬜⬛⬛⬛⬛⬜
+⬜⬛⬛⬛⬛⬜
+⬜⬛⬛⬛⬛⬜
+⬜⬛⬛⬛⬛⬜
+⬜⬜⬜⬜⬜⬜
+"
+    );
+    let res = place(&client, base_url, test, "cookie", 1).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "milk", 2).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "cookie", 2).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "milk", 3).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "milk", 3).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "cookie", 3).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "milk", 4).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "milk", 4).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "milk", 4).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "cookie", 4).await?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_text!(
+        res,
+        test,
+        "\
+⬜⬛⬛⬛🍪⬜
+⬜⬛⬛🍪🥛⬜
+⬜⬛🍪🥛🥛⬜
+⬜🍪🥛🥛🥛⬜
+⬜⬜⬜⬜⬜⬜
+🍪 wins!
+"
+    );
+    tokio::time::sleep(scaled(Duration::from_millis(1000))).await;
+    test = tests.start((2, 3)).await;
+    let res = client
+        .post(reset_url)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_text!(
+        res,
+        test,
+        "\
+⬜⬛⬛⬛⬛⬜
+⬜⬛⬛⬛⬛⬜
+⬜⬛⬛⬛⬛⬜
+⬜⬛⬛⬛⬛⬜
+⬜⬜⬜⬜⬜⬜
+"
+    );
+    let res = place(&client, base_url, test, "cookie", 1).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "cookie", 1).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "cookie", 1).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "milk", 1).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "milk", 2).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "milk", 2).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "milk", 2).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "cookie", 2).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "cookie", 3).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "cookie", 3).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "cookie", 3).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "milk", 3).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "milk", 4).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "milk", 4).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "milk", 4).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "cookie", 4).await?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_text!(
+        res,
+        test,
+        "\
+⬜🥛🍪🥛🍪⬜
+⬜🍪🥛🍪🥛⬜
+⬜🍪🥛🍪🥛⬜
+⬜🍪🥛🍪🥛⬜
+⬜⬜⬜⬜⬜⬜
+No winner.
+"
+    );
+    tokio::time::sleep(scaled(Duration::from_millis(1000))).await;
+    test = tests.start((2, 4)).await;
+    let res = client
+        .post(reset_url)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_text!(
+        res,
+        test,
+        "\
+⬜⬛⬛⬛⬛⬜
+⬜⬛⬛⬛⬛⬜
+⬜⬛⬛⬛⬛⬜
+⬜⬛⬛⬛⬛⬜
+⬜⬜⬜⬜⬜⬜
+"
+    );
+    let res = place(&client, base_url, test, "cookie", 1).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "milk", 2).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "cookie", 2).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "milk", 2).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "milk", 1).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "cookie", 1).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "cookie", 3).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "milk", 3).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "milk", 2).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "milk", 4).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "cookie", 3).await?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "milk", 1).await?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_text!(
+        res,
+        test,
+        "\
+⬜🥛🥛⬛⬛⬜
+⬜🍪🥛🍪⬛⬜
+⬜🥛🍪🥛⬛⬜
+⬜🍪🥛🍪🥛⬜
+⬜⬜⬜⬜⬜⬜
+🥛 wins!
+"
+    );
+    test = tests.start((2, 5)).await;
+    let res = place(&client, base_url, test, "milk", 4).await?;
+    assert_status!(res, test, StatusCode::SERVICE_UNAVAILABLE);
+    let res = client
+        .post(reset_url)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = place(&client, base_url, test, "cookie", 0).await?;
+    assert_status!(res, test, StatusCode::BAD_REQUEST);
+    let res = place(&client, base_url, test, "cookie", 5).await?;
+    assert_status!(res, test, StatusCode::BAD_REQUEST);
+    let res = place(&client, base_url, test, "cookie", -2).await?;
+    assert_status!(res, test, StatusCode::BAD_REQUEST);
+    let res = client
+        .post(format!("{}/12/place/cookie/one", base_url))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::BAD_REQUEST);
+    let res = place(&client, base_url, test, "plastic", 1).await?;
+    assert_status!(res, test, StatusCode::BAD_REQUEST);
+    // TASK 2 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(2)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task3(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    // TASK 3: random
+    let test = tests.start((3, 1)).await;
+    let reset_url = &format!("{}/12/reset", base_url);
+    let url = &format!("{}/12/random-board", base_url);
+    let res = client
+        .post(reset_url)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_text_starts_with!(
+        res,
+        test,
+        "\
+⬜🍪🍪🍪🍪⬜
+⬜🥛🍪🍪🥛⬜
+⬜🥛🥛🥛🥛⬜
+⬜🍪🥛🍪🥛⬜
+⬜⬜⬜⬜⬜⬜
+"
+    );
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_text_starts_with!(
+        res,
+        test,
+        "\
+⬜🍪🥛🍪🍪⬜
+⬜🥛🍪🥛🍪⬜
+⬜🥛🍪🍪🍪⬜
+⬜🍪🥛🥛🥛⬜
+⬜⬜⬜⬜⬜⬜
+"
+    );
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_text_starts_with!(
+        res,
+        test,
+        "\
+⬜🍪🍪🥛🍪⬜
+⬜🍪🥛🍪🍪⬜
+⬜🥛🍪🍪🥛⬜
+⬜🍪🥛🍪🍪⬜
+⬜⬜⬜⬜⬜⬜
+"
+    );
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_text_starts_with!(
+        res,
+        test,
+        "\
+⬜🥛🍪🍪🥛⬜
+⬜🥛🍪🍪🍪⬜
+⬜🍪🥛🥛🥛⬜
+⬜🍪🥛🍪🥛⬜
+⬜⬜⬜⬜⬜⬜
+"
+    );
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_text_starts_with!(
+        res,
+        test,
+        "\
+⬜🥛🥛🥛🍪⬜
+⬜🍪🍪🍪🥛⬜
+⬜🥛🍪🍪🥛⬜
+⬜🍪🥛🥛🍪⬜
+⬜⬜⬜⬜⬜⬜
+"
+    );
+    let res = client
+        .post(reset_url)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_text_starts_with!(
+        res,
+        test,
+        "\
+⬜🍪🍪🍪🍪⬜
+⬜🥛🍪🍪🥛⬜
+⬜🥛🥛🥛🥛⬜
+⬜🍪🥛🍪🥛⬜
+⬜⬜⬜⬜⬜⬜
+"
+    );
+    // TASK 3 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(3)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
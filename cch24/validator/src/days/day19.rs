@@ -0,0 +1,516 @@
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use serde_json::json;
+use shuttlings::{try_update, SubmissionUpdate};
+use tokio::sync::mpsc::Sender;
+use uuid::Uuid;
+
+use crate::{
+    helpers::{shared_client, SendWithRetry},
+    BoxFuture, Challenge, ChallengeInfo, TaskTest, TestFailure, TestInfo, TestTracker,
+    ValidateResult,
+};
+
+pub(crate) const TESTS: &[TestInfo] = &[
+    TestInfo {
+        task: 1,
+        test: 1,
+        description: "POST /19/draft should create a quote that GET /19/cite returns",
+    },
+    TestInfo {
+        task: 1,
+        test: 2,
+        description: "PUT /19/undo should update a quote and bump its version",
+    },
+    TestInfo {
+        task: 1,
+        test: 3,
+        description: "Unknown or invalid quote ids should return 404 Not Found or 400 Bad Request",
+    },
+    TestInfo {
+        task: 2,
+        test: 1,
+        description: "GET /19/list should return the only page of quotes without a token",
+    },
+    TestInfo {
+        task: 2,
+        test: 2,
+        description: "DELETE /19/remove should return the quote and drop it from the list",
+    },
+    TestInfo {
+        task: 2,
+        test: 3,
+        description: "GET /19/list should page through the quotes with the returned tokens",
+    },
+    TestInfo {
+        task: 2,
+        test: 4,
+        description: "GET /19/list with an unknown token should return 400 Bad Request",
+    },
+    TestInfo {
+        task: 2,
+        test: 5,
+        description: "GET /19/list should keep separate tokens independent",
+    },
+];
+
+pub(crate) const CHALLENGE: ChallengeInfo = ChallengeInfo {
+    challenge: "19",
+    description: "Paginated quote database",
+    core: 1,
+    bonus: &[0, 75],
+    scored: true,
+    exclusive: true,
+    time_scaled: false,
+    tests: TESTS,
+    prerequisites: &[(2, 1)],
+    timeout: 180,
+    validator: &Day,
+};
+
+#[derive(Debug)]
+struct Day;
+
+impl Challenge for Day {
+    fn validate<'a>(
+        &'a self,
+        url: &'a str,
+        task: i32,
+        tx: Sender<SubmissionUpdate>,
+        tests: &'a mut TestTracker,
+    ) -> BoxFuture<'a, Option<ValidateResult>> {
+        Box::pin(async move {
+            Some(match task {
+                1 => task1(url, tx, tests).await,
+                2 => task2(url, tx, tests).await,
+                _ => return None,
+            })
+        })
+    }
+}
+
+async fn task1(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    // TASK 1: CRUD
+    test = tests.start((1, 1)).await;
+    let reset_url = &format!("{}/19/reset", base_url);
+    let cite_url = &format!("{}/19/cite", base_url);
+    let remove_url = &format!("{}/19/remove", base_url);
+    let undo_url = &format!("{}/19/undo", base_url);
+    let draft_url = &format!("{}/19/draft", base_url);
+    let res = client
+        .post(reset_url)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+
+    let quote1 = json!({"author":"Santa","quote":"Ho ho ho! Spread cheer and kindness, for that's the true magic of the season!"});
+    let quote2 = json!({"author":"Santa's best elf","quote":"In the glow of snow and twinkling light, dreams take flight on a magical night!"});
+    let quote3 = json!({"author":"Dasher","quote":"Whoosh and clatter, my hooves pitter-patter!"});
+    let quote4 = json!({"author":"Polar Bear","quote":"Roar!"});
+    let res = client
+        .post(draft_url)
+        .json(&quote1)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::CREATED);
+    let id = validate_quote(res, test, &quote1, 1).await?;
+
+    let res = client
+        .get(format!("{}/{}", cite_url, id))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    validate_quote(res, test, &quote1, 1).await?;
+
+    let res = client
+        .put(format!("{}/{}", undo_url, id))
+        .json(&quote2)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    let id2 = validate_quote(res, test, &quote2, 2).await?;
+    assert_eq_!(test, id, id2);
+
+    let res = client
+        .delete(format!("{}/{}", remove_url, id))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    validate_quote(res, test, &quote2, 2).await?;
+
+    let res = client
+        .get(format!("{}/{}", cite_url, id))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::NOT_FOUND);
+
+    test = tests.start((1, 2)).await;
+    let res = client
+        .post(draft_url)
+        .json(&quote1)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::CREATED);
+    let id = validate_quote(res, test, &quote1, 1).await?;
+    let res = client
+        .post(draft_url)
+        .json(&quote1)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::CREATED);
+    let id2 = validate_quote(res, test, &quote1, 1).await?;
+    assert_neq_!(test, id, id2);
+
+    let res = client
+        .put(format!("{}/{}", undo_url, id))
+        .json(&quote2)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    validate_quote(res, test, &quote2, 2).await?;
+    let res = client
+        .get(format!("{}/{}", cite_url, id2))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    validate_quote(res, test, &quote1, 1).await?;
+    let res = client
+        .get(format!("{}/{}", cite_url, id))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    validate_quote(res, test, &quote2, 2).await?;
+
+    let res = client
+        .put(format!("{}/{}", undo_url, id))
+        .json(&quote3)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    validate_quote(res, test, &quote3, 3).await?;
+    let res = client
+        .put(format!("{}/{}", undo_url, id))
+        .json(&quote1)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    validate_quote(res, test, &quote1, 4).await?;
+
+    test = tests.start((1, 3)).await;
+    let res = client
+        .put(format!(
+            "{}/{}",
+            undo_url, "00000000-0000-0000-0000-000000000000"
+        ))
+        .json(&quote4)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::NOT_FOUND);
+    let res = client
+        .delete(format!(
+            "{}/{}",
+            remove_url, "00000000-0000-0000-0000-000000000000"
+        ))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::NOT_FOUND);
+    let res = client
+        .get(format!(
+            "{}/{}",
+            cite_url, "00000000-0000-0000-0000-000000000000"
+        ))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::NOT_FOUND);
+    let res = client
+        .put(format!("{}/{}", undo_url, "1234"))
+        .json(&quote4)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::BAD_REQUEST);
+
+    // TASK 1 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(1)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task2(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let mut test: TaskTest;
+    // TASK 2: paginator
+    test = tests.start((2, 1)).await;
+    let remove_url = &format!("{}/19/remove", base_url);
+    let draft_url = &format!("{}/19/draft", base_url);
+    let list_url = &format!("{}/19/list", base_url);
+    let quote1 = json!({"author":"Santa","quote":"Ho ho ho! Spread cheer and kindness, for that's the true magic of the season!"});
+    let quote2 = json!({"author":"Santa's best elf","quote":"In the glow of snow and twinkling light, dreams take flight on a magical night!"});
+    let quote3 = json!({"author":"Dasher","quote":"Whoosh and clatter, my hooves pitter-patter!"});
+    let res = client
+        .get(list_url)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    let n = validate_quotes(res, test, &[(&quote1, 4), (&quote1, 1)], 1).await?;
+    assert_!(test, n.is_none());
+
+    let res = client
+        .post(draft_url)
+        .json(&quote3)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::CREATED);
+    let id3 = validate_quote(res, test, &quote3, 1).await?;
+    let res = client
+        .post(draft_url)
+        .json(&quote3)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::CREATED);
+    validate_quote(res, test, &quote3, 1).await?;
+
+    let res = client
+        .get(list_url)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    let n = validate_quotes(res, test, &[(&quote1, 4), (&quote1, 1), (&quote3, 1)], 1).await?;
+    assert_!(test, n.is_some());
+    let res = client
+        .get(format!("{}?token={}", list_url, n.unwrap()))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    let n = validate_quotes(res, test, &[(&quote3, 1)], 2).await?;
+    assert_!(test, n.is_none());
+
+    test = tests.start((2, 2)).await;
+    let res = client
+        .delete(format!("{}/{}", remove_url, id3))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    validate_quote(res, test, &quote3, 1).await?;
+    let res = client
+        .get(list_url)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    let n = validate_quotes(res, test, &[(&quote1, 4), (&quote1, 1), (&quote3, 1)], 1).await?;
+    assert_!(test, n.is_none());
+
+    test = tests.start((2, 3)).await;
+    let page1 = &[(&quote1, 4), (&quote1, 1), (&quote3, 1)];
+    let page2 = &[(&quote2, 1), (&quote2, 1), (&quote3, 1)];
+    let page3 = &[(&quote2, 1), (&quote3, 1), (&quote1, 1)];
+    for &(q, v) in page2.iter().chain(page3.iter()) {
+        let res = client
+            .post(draft_url)
+            .json(q)
+            .send_with_retry()
+            .await
+            .map_err(|_| test)?;
+        assert_status!(res, test, StatusCode::CREATED);
+        validate_quote(res, test, q, v).await?;
+    }
+
+    let res = client
+        .get(list_url)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    let n = validate_quotes(res, test, page1, 1).await?;
+    assert_!(test, n.is_some());
+    let res = client
+        .get(format!("{}?token={}", list_url, n.unwrap()))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    let n = validate_quotes(res, test, page2, 2).await?;
+    assert_!(test, n.is_some());
+    let res = client
+        .get(format!("{}?token={}", list_url, n.unwrap()))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    let n = validate_quotes(res, test, page3, 3).await?;
+    assert_!(test, n.is_none());
+
+    test = tests.start((2, 4)).await;
+    let res = client
+        .get(format!("{}?token=asd987f69as87d6q", list_url))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::BAD_REQUEST);
+
+    test = tests.start((2, 5)).await;
+    let res = client
+        .get(list_url)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    let n1 = validate_quotes(res, test, page1, 1).await?;
+    assert_!(test, n1.is_some());
+
+    let res = client
+        .get(list_url)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    let n2 = validate_quotes(res, test, page1, 1).await?;
+    assert_!(test, n2.is_some());
+
+    let res = client
+        .get(format!("{}?token={}", list_url, n1.unwrap()))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    let n1 = validate_quotes(res, test, page2, 2).await?;
+    assert_!(test, n1.is_some());
+    let res = client
+        .get(format!("{}?token={}", list_url, n1.unwrap()))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    let n1 = validate_quotes(res, test, page3, 3).await?;
+    assert_!(test, n1.is_none());
+
+    let res = client
+        .get(format!("{}?token={}", list_url, n2.unwrap()))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    let n2 = validate_quotes(res, test, page2, 2).await?;
+    assert_!(test, n2.is_some());
+    let res = client
+        .get(format!("{}?token={}", list_url, n2.unwrap()))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    let n2 = validate_quotes(res, test, page3, 3).await?;
+    assert_!(test, n2.is_none());
+
+    // TASK 2 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(2)).await?;
+
+    Ok(())
+}
+
+async fn validate_quote(
+    res: reqwest::Response,
+    test: (i32, i32),
+    sent: &serde_json::Value,
+    version: i64,
+) -> Result<Uuid, TestFailure> {
+    quote_matches(
+        test,
+        sent,
+        &res.json::<serde_json::Value>().await.map_err(|_| test)?,
+        version,
+    )
+    .await
+}
+
+async fn quote_matches(
+    test: (i32, i32),
+    exp: &serde_json::Value,
+    act: &serde_json::Value,
+    version: i64,
+) -> Result<Uuid, TestFailure> {
+    assert_eq_!(test, act.as_object().ok_or(test)?.len(), 5);
+    assert_!(test, act.get("author") == exp.get("author"));
+    assert_!(test, act.get("quote") == exp.get("quote"));
+    assert_!(
+        test,
+        act.get("version")
+            .is_some_and(|v| v.as_i64().is_some_and(|v| v == version))
+    );
+    act.get("created_at")
+        .ok_or(test)?
+        .as_str()
+        .ok_or(test)?
+        .parse::<DateTime<Utc>>()
+        .map_err(|_| test)?;
+    let id: Uuid = act
+        .get("id")
+        .ok_or(test)?
+        .as_str()
+        .ok_or(test)?
+        .parse()
+        .map_err(|_| test)?;
+
+    Ok(id)
+}
+
+async fn validate_quotes(
+    res: reqwest::Response,
+    test: (i32, i32),
+    sent: &[(&serde_json::Value, i64)],
+    page: i64,
+) -> Result<Option<String>, TestFailure> {
+    let json = res.json::<serde_json::Value>().await.map_err(|_| test)?;
+    assert_!(
+        test,
+        json.get("page")
+            .is_some_and(|v| v.as_i64().is_some_and(|v| v == page))
+    );
+    let quotes = json.get("quotes").ok_or(test)?.as_array().ok_or(test)?;
+    for ((v, version), quote) in sent.iter().zip(quotes.iter()) {
+        quote_matches(test, v, quote, *version).await?;
+    }
+    let next_token: Option<String> =
+        serde_json::from_value(json.get("next_token").ok_or(test)?.clone()).map_err(|_| test)?;
+    if let Some(t) = next_token.as_ref() {
+        if t.chars().any(|c| !c.is_ascii_alphanumeric()) || t.len() != 16 {
+            return Err(test.into());
+        }
+    }
+    Ok(next_token)
+}
@@ -0,0 +1,1168 @@
+use html_compare_rs::{HtmlCompareOptions, HtmlComparer};
+use reqwest::{
+    multipart::{Form, Part},
+    StatusCode,
+};
+use shuttlings::{try_update, SubmissionUpdate};
+use tokio::sync::mpsc::Sender;
+
+use crate::{
+    helpers::{shared_client, SendWithRetry},
+    BoxFuture, Challenge, ChallengeInfo, TaskTest, TestFailure, TestInfo, TestTracker,
+    ValidateResult,
+};
+
+pub(crate) const TESTS: &[TestInfo] = &[
+    TestInfo {
+        task: 1,
+        test: 1,
+        description: "GET /assets/23.html should serve the page (as text/html with --strict)",
+    },
+    TestInfo {
+        task: 2,
+        test: 1,
+        description: "GET /23/star should return a lit star (as text/html with --strict)",
+    },
+    TestInfo {
+        task: 3,
+        test: 1,
+        description: "GET /23/present/{color} should cycle the present colors (as text/html with --strict)",
+    },
+    TestInfo {
+        task: 3,
+        test: 2,
+        description: "GET /23/present/green should return 418 I'm a teapot",
+    },
+    TestInfo {
+        task: 4,
+        test: 1,
+        description: "GET /23/ornament/{state}/1 should toggle the ornament (as text/html with --strict)",
+    },
+    TestInfo {
+        task: 4,
+        test: 2,
+        description: "GET /23/ornament/on/{n} should accept any ornament name (as text/html with --strict)",
+    },
+    TestInfo {
+        task: 4,
+        test: 3,
+        description: "GET /23/ornament/maybe-on/1 should return 418 I'm a teapot",
+    },
+    TestInfo {
+        task: 5,
+        test: 1,
+        description: "GET /23/ornament/on/{n} should escape HTML in the ornament name (as text/html with --strict)",
+    },
+    TestInfo {
+        task: 6,
+        test: 1,
+        description: "POST /23/lockfile should draw a square per checksum (as text/html with --strict)",
+    },
+    TestInfo {
+        task: 6,
+        test: 2,
+        description: "POST /23/lockfile with a full Cargo.lock should draw every checksum (as text/html with --strict)",
+    },
+    TestInfo {
+        task: 6,
+        test: 3,
+        description: "POST /23/lockfile without a lockfile part should return 400 Bad Request",
+    },
+    TestInfo {
+        task: 6,
+        test: 4,
+        description: "POST /23/lockfile with an empty form should return 400 Bad Request",
+    },
+    TestInfo {
+        task: 6,
+        test: 5,
+        description: "POST /23/lockfile with an unparsable lockfile should return 400 Bad Request",
+    },
+    TestInfo {
+        task: 6,
+        test: 6,
+        description: "POST /23/lockfile with a package that only has a checksum should draw it (as text/html with --strict)",
+    },
+    TestInfo {
+        task: 6,
+        test: 7,
+        description: "POST /23/lockfile with a non-string checksum should return 400 Bad Request",
+    },
+    TestInfo {
+        task: 6,
+        test: 8,
+        description: "POST /23/lockfile should draw a square for a short checksum (as text/html with --strict)",
+    },
+    TestInfo {
+        task: 6,
+        test: 9,
+        description: "POST /23/lockfile should draw the same squares for the same checksum (as text/html with --strict)",
+    },
+    TestInfo {
+        task: 6,
+        test: 10,
+        description: "POST /23/lockfile should accept an uppercase checksum (as text/html with --strict)",
+    },
+    TestInfo {
+        task: 6,
+        test: 11,
+        description:
+            "POST /23/lockfile with a non-hex checksum should return 422 Unprocessable Entity",
+    },
+    TestInfo {
+        task: 6,
+        test: 12,
+        description:
+            "POST /23/lockfile with a too short checksum should return 422 Unprocessable Entity",
+    },
+];
+
+pub(crate) const CHALLENGE: ChallengeInfo = ChallengeInfo {
+    challenge: "23",
+    description: "HTMX Christmas tree with ornaments and a lockfile",
+    core: 5,
+    bonus: &[0, 0, 0, 0, 0, 100],
+    scored: true,
+    exclusive: false,
+    time_scaled: false,
+    tests: TESTS,
+    prerequisites: &[],
+    timeout: 60,
+    validator: &Day,
+};
+
+#[derive(Debug)]
+struct Day;
+
+impl Challenge for Day {
+    fn validate<'a>(
+        &'a self,
+        url: &'a str,
+        task: i32,
+        tx: Sender<SubmissionUpdate>,
+        tests: &'a mut TestTracker,
+    ) -> BoxFuture<'a, Option<ValidateResult>> {
+        Box::pin(async move {
+            Some(match task {
+                1 => task1(url, tx, tests).await,
+                2 => task2(url, tx, tests).await,
+                3 => task3(url, tx, tests).await,
+                4 => task4(url, tx, tests).await,
+                5 => task5(url, tx, tests).await,
+                6 => task6(url, tx, tests).await,
+                _ => return None,
+            })
+        })
+    }
+}
+
+fn html_comparer() -> HtmlComparer {
+    HtmlComparer::with_options(HtmlCompareOptions {
+        ignore_whitespace: true,
+        ignore_attributes: false,
+        ignored_attributes: Default::default(),
+        ignore_text: false,
+        ignore_comments: true,
+        ignore_sibling_order: false,
+        ignore_style_contents: false,
+    })
+}
+
+macro_rules! assert_html {
+    ($res:expr, $test:expr, $comp:expr, $expected_html:expr) => {
+        assert_content_type!($res, $test, "text/html");
+        let expected = $expected_html;
+        let html = $res.text().await.map_err(|_| $test)?;
+        if !$comp.compare(expected, &html).is_ok_and(|t| t) {
+            return Err(TestFailure::mismatch(
+                $test,
+                format!("{expected:?}"),
+                format!("{html:?}"),
+            ));
+        }
+    };
+}
+
+async fn task1(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    // TASK 1: serve
+    let test = tests.start((1, 1)).await;
+    let url = &format!("{}/assets/23.html", base_url);
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_content_type!(res, test, "text/html");
+    let len = res.text().await.map_err(|_| test)?.len();
+    if len != 7163 {
+        return Err(TestFailure::mismatch(
+            test,
+            "7163 bytes".to_owned(),
+            format!("{len} bytes"),
+        ));
+    }
+    // TASK 1 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(1)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task2(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let comparer = html_comparer();
+    // TASK 2: star
+    let test = tests.start((2, 1)).await;
+    let url = &format!("{}/23/star", base_url);
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_html!(res, test, comparer, r#"<div id="star" class="lit"></div>"#);
+    // TASK 2 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(2)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task3(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let comparer = html_comparer();
+    let mut test: TaskTest;
+    // TASK 3: present
+    test = tests.start((3, 1)).await;
+    let res = client
+        .get(format!("{}/23/present/red", base_url))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_html!(
+        res,
+        test,
+        comparer,
+        r#"<div class="present red" hx-get="/23/present/blue" hx-swap="outerHTML"><div class="ribbon"></div><div class="ribbon"></div><div class="ribbon"></div><div class="ribbon"></div></div>"#
+    );
+    let res = client
+        .get(format!("{}/23/present/blue", base_url))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_html!(
+        res,
+        test,
+        comparer,
+        r#"<div class="present blue" hx-get="/23/present/purple" hx-swap="outerHTML"><div class="ribbon"></div><div class="ribbon"></div><div class="ribbon"></div><div class="ribbon"></div></div>"#
+    );
+    let res = client
+        .get(format!("{}/23/present/purple", base_url))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_html!(
+        res,
+        test,
+        comparer,
+        r#"<div class="present purple" hx-get="/23/present/red" hx-swap="outerHTML"><div class="ribbon"></div><div class="ribbon"></div><div class="ribbon"></div><div class="ribbon"></div></div>"#
+    );
+    test = tests.start((3, 2)).await;
+    let res = client
+        .get(format!("{}/23/present/green", base_url))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::IM_A_TEAPOT);
+    // TASK 3 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(3)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task4(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let comparer = html_comparer();
+    let mut test: TaskTest;
+    // TASK 4: ornament
+    test = tests.start((4, 1)).await;
+    let res = client
+        .get(format!("{}/23/ornament/on/1", base_url))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_html!(
+        res,
+        test,
+        comparer,
+        r#"<div class="ornament on" id="ornament1" hx-trigger="load delay:2s once" hx-get="/23/ornament/off/1" hx-swap="outerHTML"></div>"#
+    );
+    let res = client
+        .get(format!("{}/23/ornament/off/1", base_url))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_html!(
+        res,
+        test,
+        comparer,
+        r#"<div class="ornament" id="ornament1" hx-trigger="load delay:2s once" hx-get="/23/ornament/on/1" hx-swap="outerHTML"></div>"#
+    );
+    let res = client
+        .get(format!("{}/23/ornament/off/100", base_url))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_html!(
+        res,
+        test,
+        comparer,
+        r#"<div class="ornament" id="ornament100" hx-trigger="load delay:2s once" hx-get="/23/ornament/on/100" hx-swap="outerHTML"></div>"#
+    );
+    test = tests.start((4, 2)).await;
+    let res = client
+        .get(format!("{}/23/ornament/on/the_prettiest_one", base_url))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_html!(
+        res,
+        test,
+        comparer,
+        r#"<div class="ornament on" id="ornamentthe_prettiest_one" hx-trigger="load delay:2s once" hx-get="/23/ornament/off/the_prettiest_one" hx-swap="outerHTML"></div>"#
+    );
+    test = tests.start((4, 3)).await;
+    let res = client
+        .get(format!("{}/23/ornament/maybe-on/1", base_url))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::IM_A_TEAPOT);
+    // TASK 4 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(4)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task5(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let comparer = html_comparer();
+    // TASK 5: injection
+    let test = tests.start((5, 1)).await;
+    let res = client
+        .get(format!(
+            "{}/23/ornament/on/%22%3E%3Cscript%3Ealert%28%22Spicy%20soup%21%22%29%3C%2Fscript%3E",
+            base_url
+        ))
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_html!(
+        res,
+        test,
+        comparer,
+        r#"<div class="ornament on" id="ornament&quot;&gt;&lt;script&gt;alert(&quot;Spicy soup!&quot;)&lt;/script&gt;" hx-trigger="load delay:2s once" hx-get="/23/ornament/off/&quot;&gt;&lt;script&gt;alert(&quot;Spicy soup!&quot;)&lt;/script&gt;" hx-swap="outerHTML"></div>"#
+    );
+    // TASK 5 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(5)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task6(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    let comparer = html_comparer();
+    let mut test: TaskTest;
+    // TASK 6: lockfile
+    test = tests.start((6, 1)).await;
+    let url = &format!("{}/23/lockfile", base_url);
+    let form = Form::new().part(
+        "lockfile",
+        Part::bytes(
+            r#"[[package]]
+name = "shuttle-runtime"
+version = "0.49.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "337789faa0372648a8ac286b2f92a53121fe118f12e29009ac504872a5413cc6"
+
+[[package]]
+name = "shuttle-service"
+version = "0.49.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "22ba454b13e4e29b5b892a62c334360a571de5a25c936283416c94328427dd57"
+"#
+            .as_bytes(),
+        )
+        .file_name("Cargo.lock")
+        .mime_str("application/octet-stream")
+        .unwrap(),
+    );
+    let res = client
+        .post(url)
+        .multipart(form)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_html!(
+        res,
+        test,
+        comparer,
+        r#"
+<div style="background-color:#337789;top:250px;left:160px;"></div>
+<div style="background-color:#22ba45;top:75px;left:19px;"></div>
+"#
+    );
+    test = tests.start((6, 2)).await;
+    let form = Form::new().part(
+        "lockfile",
+        Part::bytes(
+            r#"# This file is automatically @generated by Cargo.
+# It is not intended for manual editing.
+version = 4
+
+[[package]]
+name = "addr2line"
+version = "0.24.2"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "dfbe277e56a376000877090da837660b4427aad530e3028d44e0bffe4f89a1c1"
+dependencies = [
+ "gimli",
+]
+
+[[package]]
+name = "adler2"
+version = "2.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "512761e0bb2578dd7380c6baaa0f4ce03e84f95e960231d1dec8bf4d7d6e2627"
+
+[[package]]
+name = "ahash"
+version = "0.8.11"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "e89da841a80418a9b391ebaea17f5c112ffaaa96f621d2c285b5174da76b9011"
+dependencies = [
+ "cfg-if",
+ "once_cell",
+ "version_check",
+ "zerocopy",
+]
+
+[[package]]
+name = "aho-corasick"
+version = "1.1.3"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "8e60d3430d3a69478ad0993f19238d2df97c507009a52b3c10addcd7f6bcb916"
+dependencies = [
+ "memchr",
+]
+
+[[package]]
+name = "allocator-api2"
+version = "0.2.21"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "683d7910e743518b0e34f1186f92494becacb047c7b6bf616c96772180fef923"
+
+[[package]]
+name = "android-tzdata"
+version = "0.1.1"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "e999941b234f3131b00bc13c22d06e8c5ff726d1b6318ac7eb276997bbb4fef0"
+
+[[package]]
+name = "android_system_properties"
+version = "0.1.5"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "819e7219dbd41043ac279b19830f2efc897156490d7fd6ea916720117ee66311"
+dependencies = [
+ "libc",
+]
+
+[[package]]
+name = "anyhow"
+version = "1.0.93"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "4c95c10ba0b00a02636238b814946408b1322d5ac4760326e6fb8ec956d85775"
+
+[[package]]
+name = "askama"
+version = "0.12.1"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "b79091df18a97caea757e28cd2d5fda49c6cd4bd01ddffd7ff01ace0c0ad2c28"
+dependencies = [
+ "askama_derive",
+ "askama_escape",
+ "humansize",
+ "num-traits",
+ "percent-encoding",
+]
+
+[[package]]
+name = "askama_axum"
+version = "0.4.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "a41603f7cdbf5ac4af60760f17253eb6adf6ec5b6f14a7ed830cf687d375f163"
+dependencies = [
+ "askama",
+ "axum-core 0.4.5",
+ "http 1.1.0",
+]
+
+[[package]]
+name = "askama_derive"
+version = "0.12.5"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "19fe8d6cb13c4714962c072ea496f3392015f0989b1a2847bb4b2d9effd71d83"
+dependencies = [
+ "askama_parser",
+ "basic-toml",
+ "mime",
+ "mime_guess",
+ "proc-macro2",
+ "quote",
+ "serde",
+ "syn 2.0.89",
+]
+
+[[package]]
+name = "askama_escape"
+version = "0.10.3"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "619743e34b5ba4e9703bba34deac3427c72507c7159f5fd030aea8cac0cfe341"
+
+[[package]]
+name = "askama_parser"
+version = "0.2.1"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "acb1161c6b64d1c3d83108213c2a2533a342ac225aabd0bda218278c2ddb00c0"
+dependencies = [
+ "nom",
+]
+
+[[package]]
+name = "async-stream"
+version = "0.3.6"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "0b5a71a6f37880a80d1d7f19efd781e4b5de42c88f0722cc13bcb6cc2cfe8476"
+dependencies = [
+ "async-stream-impl",
+ "futures-core",
+ "pin-project-lite",
+]
+
+[[package]]
+name = "async-stream-impl"
+version = "0.3.6"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "c7c24de15d275a1ecfd47a380fb4d5ec9bfe0933f309ed5e705b775596a3574d"
+dependencies = [
+ "proc-macro2",
+ "quote",
+ "syn 2.0.89",
+]
+
+[[package]]
+name = "async-trait"
+version = "0.1.83"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "721cae7de5c34fbb2acd27e21e6d2cf7b886dce0c27388d46c4e6c47ea4318dd"
+dependencies = [
+ "proc-macro2",
+ "quote",
+ "syn 2.0.89",
+]
+
+[[package]]
+name = "atoi"
+version = "2.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "f28d99ec8bfea296261ca1af174f24225171fea9664ba9003cbebee704810528"
+dependencies = [
+ "num-traits",
+]
+
+[[package]]
+name = "autocfg"
+version = "1.4.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "ace50bade8e6234aa140d9a2f552bbee1db4d353f69b8217bc503490fc1a9f26"
+
+[[package]]
+name = "axum"
+version = "0.6.20"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "3b829e4e32b91e643de6eafe82b1d90675f5874230191a4ffbc1b336dec4d6bf"
+dependencies = [
+ "async-trait",
+ "axum-core 0.3.4",
+ "bitflags 1.3.2",
+ "bytes",
+ "futures-util",
+ "http 0.2.12",
+ "http-body 0.4.6",
+ "hyper 0.14.31",
+ "itoa",
+ "matchit",
+ "memchr",
+ "mime",
+ "percent-encoding",
+ "pin-project-lite",
+ "rustversion",
+ "serde",
+ "sync_wrapper 0.1.2",
+ "tower 0.4.13",
+ "tower-layer",
+ "tower-service",
+]
+
+[[package]]
+name = "axum"
+version = "0.7.9"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "edca88bc138befd0323b20752846e6587272d3b03b0343c8ea28a6f819e6e71f"
+dependencies = [
+ "async-trait",
+ "axum-core 0.4.5",
+ "bytes",
+ "futures-util",
+ "http 1.1.0",
+ "http-body 1.0.1",
+ "http-body-util",
+ "hyper 1.5.1",
+ "hyper-util",
+ "itoa",
+ "matchit",
+ "memchr",
+ "mime",
+ "percent-encoding",
+ "pin-project-lite",
+ "rustversion",
+ "serde",
+ "serde_json",
+ "serde_path_to_error",
+ "serde_urlencoded",
+ "sync_wrapper 1.0.2",
+ "tokio",
+ "tower 0.5.1",
+ "tower-layer",
+ "tower-service",
+ "tracing",
+]
+
+[[package]]
+name = "axum-core"
+version = "0.3.4"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "759fa577a247914fd3f7f76d62972792636412fbfd634cd452f6a385a74d2d2c"
+dependencies = [
+ "async-trait",
+ "bytes",
+ "futures-util",
+ "http 0.2.12",
+ "http-body 0.4.6",
+ "mime",
+ "rustversion",
+ "tower-layer",
+ "tower-service",
+]
+
+[[package]]
+name = "axum-core"
+version = "0.4.5"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "09f2bd6146b97ae3359fa0cc6d6b376d9539582c7b4220f041a33ec24c226199"
+dependencies = [
+ "async-trait",
+ "bytes",
+ "futures-util",
+ "http 1.1.0",
+ "http-body 1.0.1",
+ "http-body-util",
+ "mime",
+ "pin-project-lite",
+ "rustversion",
+ "sync_wrapper 1.0.2",
+ "tower-layer",
+ "tower-service",
+ "tracing",
+]
+
+[[package]]
+name = "axum-extra"
+version = "0.9.6"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "c794b30c904f0a1c2fb7740f7df7f7972dfaa14ef6f57cb6178dc63e5dca2f04"
+dependencies = [
+ "axum 0.7.9",
+ "axum-core 0.4.5",
+ "bytes",
+ "cookie",
+ "fastrand",
+ "futures-util",
+ "http 1.1.0",
+ "http-body 1.0.1",
+ "http-body-util",
+ "mime",
+ "multer",
+ "pin-project-lite",
+ "serde",
+ "tower 0.5.1",
+ "tower-layer",
+ "tower-service",
+]
+
+[[package]]
+name = "backtrace"
+version = "0.3.74"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "8d82cb332cdfaed17ae235a638438ac4d4839913cc2af585c3c6746e8f8bee1a"
+dependencies = [
+ "addr2line",
+ "cfg-if",
+ "libc",
+ "miniz_oxide",
+ "object",
+ "rustc-demangle",
+ "windows-targets 0.52.6",
+]
+
+[[package]]
+name = "base64"
+version = "0.21.7"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "9d297deb1925b89f2ccc13d7635fa0714f12c87adce1c75356b39ca9b7178567"
+
+[[package]]
+name = "base64"
+version = "0.22.1"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "72b3254f16251a8381aa12e40e3c4d2f0199f8c6508fbecb9d91f575e0fbb8c6"
+
+[[package]]
+name = "base64ct"
+version = "1.6.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "8c3c1a368f70d6cf7302d78f8f7093da241fb8e8807c05cc9e51a125895a6d5b"
+
+[[package]]
+name = "basic-toml"
+version = "0.1.9"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "823388e228f614e9558c6804262db37960ec8821856535f5c3f59913140558f8"
+dependencies = [
+ "serde",
+]
+
+[[package]]
+name = "bitflags"
+version = "1.3.2"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "bef38d45163c2f1dde094a7dfd33ccf595c92905c8f8f4fdc18d06fb1037718a"
+
+[[package]]
+name = "bitflags"
+version = "2.6.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "b048fb63fd8b5923fc5aa7b340d8e156aec7ec02f0c78fa8a6ddc2613f6f71de"
+dependencies = [
+ "serde",
+]
+
+[[package]]
+name = "block-buffer"
+version = "0.10.4"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "3078c7629b62d3f0439517fa394996acacc5cbc91c5a20d8c658e77abd503a71"
+dependencies = [
+ "generic-array",
+]
+
+[[package]]
+name = "bumpalo"
+version = "3.16.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "79296716171880943b8470b5f8d03aa55eb2e645a4874bdbb28adb49162e012c"
+
+[[package]]
+name = "byteorder"
+version = "1.5.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "1fd0f2584146f6f2ef48085050886acf353beff7305ebd1ae69500e27c67f64b"
+
+[[package]]
+name = "bytes"
+version = "1.8.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "9ac0150caa2ae65ca5bd83f25c7de183dea78d4d366469f148435e2acfbad0da"
+
+[[package]]
+name = "shuttlings-cch24"
+version = "0.1.0"
+dependencies = [
+ "askama",
+ "askama_axum",
+ "axum 0.7.9",
+ "axum-extra",
+ "cargo-manifest",
+ "chrono",
+ "ipnet",
+ "jsonwebtoken",
+ "leaky-bucket",
+ "rand",
+ "serde",
+ "serde_json",
+ "serde_yml",
+ "shuttle-axum",
+ "shuttle-runtime",
+ "shuttle-shared-db",
+ "sqlx",
+ "tokio",
+ "toml",
+ "tower-http",
+ "uuid",
+]
+
+[[package]]
+name = "signal-hook"
+version = "0.3.17"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "8621587d4798caf8eb44879d42e56b9a93ea5dcd315a6487c357130095b62801"
+dependencies = [
+ "libc",
+ "signal-hook-registry",
+]
+"#
+            .as_bytes(),
+        )
+        .file_name("Cargo.lock")
+        .mime_str("application/octet-stream")
+        .unwrap(),
+    );
+    let res = client
+        .post(url)
+        .multipart(form)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_html!(
+        res,
+        test,
+        comparer,
+        r#"
+<div style="background-color:#dfbe27;top:126px;left:86px;"></div>
+<div style="background-color:#512761;top:224px;left:187px;"></div>
+<div style="background-color:#e89da8;top:65px;left:168px;"></div>
+<div style="background-color:#8e60d3;top:67px;left:13px;"></div>
+<div style="background-color:#683d79;top:16px;left:231px;"></div>
+<div style="background-color:#e99994;top:27px;left:35px;"></div>
+<div style="background-color:#819e72;top:25px;left:219px;"></div>
+<div style="background-color:#4c95c1;top:11px;left:160px;"></div>
+<div style="background-color:#b79091;top:223px;left:24px;"></div>
+<div style="background-color:#a41603;top:247px;left:205px;"></div>
+<div style="background-color:#19fe8d;top:108px;left:177px;"></div>
+<div style="background-color:#619743;top:227px;left:75px;"></div>
+<div style="background-color:#acb116;top:28px;left:107px;"></div>
+<div style="background-color:#0b5a71;top:166px;left:243px;"></div>
+<div style="background-color:#c7c24d;top:225px;left:93px;"></div>
+<div style="background-color:#721cae;top:125px;left:229px;"></div>
+<div style="background-color:#f28d99;top:236px;left:139px;"></div>
+<div style="background-color:#ace50b;top:173px;left:232px;"></div>
+<div style="background-color:#3b829e;top:78px;left:50px;"></div>
+<div style="background-color:#edca88;top:188px;left:19px;"></div>
+<div style="background-color:#759fa5;top:119px;left:162px;"></div>
+<div style="background-color:#09f2bd;top:97px;left:70px;"></div>
+<div style="background-color:#c794b3;top:12px;left:144px;"></div>
+<div style="background-color:#8d82cb;top:51px;left:44px;"></div>
+<div style="background-color:#9d297d;top:235px;left:25px;"></div>
+<div style="background-color:#72b325;top:79px;left:22px;"></div>
+<div style="background-color:#8c3c1a;top:54px;left:143px;"></div>
+<div style="background-color:#823388;top:226px;left:40px;"></div>
+<div style="background-color:#bef38d;top:69px;left:22px;"></div>
+<div style="background-color:#b048fb;top:99px;left:253px;"></div>
+<div style="background-color:#3078c7;top:98px;left:155px;"></div>
+<div style="background-color:#792967;top:22px;left:23px;"></div>
+<div style="background-color:#1fd0f2;top:88px;left:65px;"></div>
+<div style="background-color:#9ac015;top:12px;left:170px;"></div>
+<div style="background-color:#862158;top:125px;left:71px;"></div>
+"#
+    );
+    test = tests.start((6, 3)).await;
+    let form = Form::new().part(
+        "blockfile",
+        Part::bytes(r#"MINE DIAMONDS!!!!"#.as_bytes())
+            .file_name("Cargo.block")
+            .mime_str("application/octet-stream")
+            .unwrap(),
+    );
+    let res = client
+        .post(url)
+        .multipart(form)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::BAD_REQUEST);
+    test = tests.start((6, 4)).await;
+    let form = Form::new();
+    let res = client
+        .post(url)
+        .multipart(form)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::BAD_REQUEST);
+    test = tests.start((6, 5)).await;
+    let form = Form::new().part(
+        "lockfile",
+        Part::bytes(
+            "[[package]]
+checksum = \"337789faa0372648a8ac286b2f92a53121fe118f12e29009ac504872a5413cc6\"
+\x00\x00"
+                .as_bytes(),
+        )
+        .file_name("Cargo.lock")
+        .mime_str("application/octet-stream")
+        .unwrap(),
+    );
+    let res = client
+        .post(url)
+        .multipart(form)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::BAD_REQUEST);
+    test = tests.start((6, 5)).await;
+    let form = Form::new().part(
+        "lockfile",
+        Part::bytes(
+            r#"[[package]]
+checksum = "337789faa0372648a8ac286b2f92a53121fe118f12e29009ac504872a5413cc6"
+fn jingle_bells(volume: f32) -> Result<Sound<DingDong>, MusicError> { ... }
+"#
+            .as_bytes(),
+        )
+        .file_name("Cargo.lock")
+        .mime_str("application/octet-stream")
+        .unwrap(),
+    );
+    let res = client
+        .post(url)
+        .multipart(form)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::BAD_REQUEST);
+    test = tests.start((6, 6)).await;
+    let form = Form::new().part(
+        "lockfile",
+        Part::bytes(
+            r#"[[package]]
+checksum = "337789faa0372648a8ac286b2f92a53121fe118f12e29009ac504872a5413cc6"
+"#
+            .as_bytes(),
+        )
+        .file_name("Cargo.lock")
+        .mime_str("application/octet-stream")
+        .unwrap(),
+    );
+    let res = client
+        .post(url)
+        .multipart(form)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_html!(
+        res,
+        test,
+        comparer,
+        r#"
+<div style="background-color:#337789;top:250px;left:160px;"></div>
+"#
+    );
+    test = tests.start((6, 7)).await;
+    let form = Form::new().part(
+        "lockfile",
+        Part::bytes(
+            r#"[[package]]
+checksum = [ "cookie", "milk", "hot cocoa" ]
+"#
+            .as_bytes(),
+        )
+        .file_name("Cargo.lock")
+        .mime_str("application/octet-stream")
+        .unwrap(),
+    );
+    let res = client
+        .post(url)
+        .multipart(form)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::BAD_REQUEST);
+    test = tests.start((6, 8)).await;
+    let form = Form::new().part(
+        "lockfile",
+        Part::bytes(
+            r#"[[package]]
+checksum = "337789faa0"
+"#
+            .as_bytes(),
+        )
+        .file_name("Cargo.lock")
+        .mime_str("application/octet-stream")
+        .unwrap(),
+    );
+    let res = client
+        .post(url)
+        .multipart(form)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_html!(
+        res,
+        test,
+        comparer,
+        r#"
+<div style="background-color:#337789;top:250px;left:160px;"></div>
+"#
+    );
+    test = tests.start((6, 9)).await;
+    let form = Form::new().part(
+        "lockfile",
+        Part::bytes(
+            r#"[[package]]
+checksum = "337789faa0"
+"#
+            .as_bytes(),
+        )
+        .file_name("Cargo.lock")
+        .mime_str("application/octet-stream")
+        .unwrap(),
+    );
+    let res = client
+        .post(url)
+        .multipart(form)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_html!(
+        res,
+        test,
+        comparer,
+        r#"
+<div style="background-color:#337789;top:250px;left:160px;"></div>
+"#
+    );
+    test = tests.start((6, 10)).await;
+    let form = Form::new().part(
+        "lockfile",
+        Part::bytes(
+            r#"[[package]]
+checksum = "337789FAA0"
+"#
+            .as_bytes(),
+        )
+        .file_name("Cargo.lock")
+        .mime_str("application/octet-stream")
+        .unwrap(),
+    );
+    let res = client
+        .post(url)
+        .multipart(form)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_html!(
+        res,
+        test,
+        comparer,
+        r#"
+<div style="background-color:#337789;top:250px;left:160px;"></div>
+"#
+    );
+    test = tests.start((6, 11)).await;
+    let form = Form::new().part(
+        "lockfile",
+        Part::bytes(
+            r#"[[package]]
+checksum = "3377QQFAA0"
+"#
+            .as_bytes(),
+        )
+        .file_name("Cargo.lock")
+        .mime_str("application/octet-stream")
+        .unwrap(),
+    );
+    let res = client
+        .post(url)
+        .multipart(form)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::UNPROCESSABLE_ENTITY);
+    test = tests.start((6, 12)).await;
+    let form = Form::new().part(
+        "lockfile",
+        Part::bytes(
+            r#"[[package]]
+checksum = "BEEF"
+"#
+            .as_bytes(),
+        )
+        .file_name("Cargo.lock")
+        .mime_str("application/octet-stream")
+        .unwrap(),
+    );
+    let res = client
+        .post(url)
+        .multipart(form)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::UNPROCESSABLE_ENTITY);
+
+    // TASK 6 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(6)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
@@ -0,0 +1,149 @@
+use reqwest::{header, StatusCode};
+use shuttlings::{try_update, SubmissionUpdate};
+use tokio::sync::mpsc::Sender;
+
+use crate::{
+    helpers::{new_client_without_redirects, shared_client, SendWithRetry},
+    BoxFuture, Challenge, ChallengeInfo, TestInfo, TestTracker, ValidateResult,
+};
+
+pub(crate) const TESTS: &[TestInfo] = &[
+    TestInfo {
+        task: 1,
+        test: 1,
+        description: "GET / should return \"Hello, bird!\"",
+    },
+    TestInfo {
+        task: 2,
+        test: 1,
+        description: "GET /-1/seek should redirect with 302 Found to the video",
+    },
+];
+
+pub(crate) const CHALLENGE: ChallengeInfo = ChallengeInfo {
+    challenge: "-1",
+    description: "Warmup with a hello world and a redirect",
+    core: 1,
+    bonus: &[0, 0],
+    scored: true,
+    exclusive: false,
+    time_scaled: false,
+    tests: TESTS,
+    prerequisites: &[],
+    timeout: 20,
+    validator: &Day,
+};
+
+#[derive(Debug)]
+struct Day;
+
+impl Challenge for Day {
+    fn validate<'a>(
+        &'a self,
+        url: &'a str,
+        task: i32,
+        tx: Sender<SubmissionUpdate>,
+        tests: &'a mut TestTracker,
+    ) -> BoxFuture<'a, Option<ValidateResult>> {
+        Box::pin(async move {
+            Some(match task {
+                1 => task1(url, tx, tests).await,
+                2 => task2(url, tx, tests).await,
+                _ => return None,
+            })
+        })
+    }
+}
+
+async fn task1(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    let client = shared_client();
+    // TASK 1: respond 200 with Hello, bird!
+    let test = tests.start((1, 1)).await;
+    let url = &format!("{}/", base_url);
+    let res = client.get(url).send_with_retry().await.map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::OK);
+    assert_text!(res, test, "Hello, bird!");
+    // TASK 1 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(1)).await?;
+    try_update(&tx, SubmissionUpdate::Save).await?;
+
+    Ok(())
+}
+
+async fn task2(
+    base_url: &str,
+    tx: Sender<SubmissionUpdate>,
+    tests: &mut TestTracker,
+) -> ValidateResult {
+    // TASK 2: respond 302
+    let test = tests.start((2, 1)).await;
+    let url = &format!("{}/-1/seek", base_url);
+    let client_no_redir = new_client_without_redirects();
+    let res = client_no_redir
+        .get(url)
+        .send_with_retry()
+        .await
+        .map_err(|_| test)?;
+    assert_status!(res, test, StatusCode::FOUND);
+    assert_headers!(
+        res,
+        test,
+        &[(
+            header::LOCATION,
+            "https://www.youtube.com/watch?v=9Gc4QTqslN4"
+        )]
+    );
+    // TASK 2 DONE
+    tests.pass().await;
+    try_update(&tx, CHALLENGE.completed(2)).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::Method;
+    use shuttlings::transport::MockTransport;
+
+    use super::*;
+    use crate::{tests::run_mocked, RunOutcome};
+
+    const VIDEO: &str = "https://www.youtube.com/watch?v=9Gc4QTqslN4";
+
+    #[tokio::test]
+    async fn task1_expects_the_greeting() {
+        let mock = MockTransport::new().canned(Method::GET, "/", StatusCode::OK, "Hello, bird!");
+        assert_eq!(run_mocked("-1", 1, mock).await, RunOutcome::Completed);
+        let mock = MockTransport::new().canned(Method::GET, "/", StatusCode::OK, "Hello, world!");
+        assert_eq!(
+            run_mocked("-1", 1, mock).await,
+            RunOutcome::Failed { task: 1, test: 1 }
+        );
+    }
+
+    #[tokio::test]
+    async fn task2_expects_a_redirect_to_the_video() {
+        let seek = |location: &'static str| {
+            MockTransport::new().route(Method::GET, "/-1/seek", move |_| {
+                http::Response::builder()
+                    .status(StatusCode::FOUND)
+                    .header(header::LOCATION, location)
+                    .body(Vec::new())
+                    .unwrap()
+            })
+        };
+        assert_eq!(
+            run_mocked("-1", 2, seek(VIDEO)).await,
+            RunOutcome::Completed
+        );
+        assert_eq!(
+            run_mocked("-1", 2, seek("https://www.youtube.com/")).await,
+            RunOutcome::Failed { task: 2, test: 1 }
+        );
+    }
+}
@@ -0,0 +1,8 @@
+pub(crate) mod day02;
+pub(crate) mod day05;
+pub(crate) mod day09;
+pub(crate) mod day12;
+pub(crate) mod day16;
+pub(crate) mod day19;
+pub(crate) mod day23;
+pub(crate) mod day_minus1;
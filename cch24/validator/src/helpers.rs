@@ -0,0 +1,432 @@
+use std::path::Path;
+
+use reqwest::{
+    header::{CONTENT_ENCODING, CONTENT_LENGTH},
+    ResponseBuilderExt, StatusCode,
+};
+use shuttlings::{
+    client,
+    har::{self, Har},
+    testing::{self, Strictness, TestError, DEFAULT_MAX_RESPONSE_SIZE},
+    transport::{Transport, TransportError},
+};
+use tokio::time::{sleep, Duration, Instant};
+use tracing::info;
+
+use crate::{
+    HttpVersion, Proxy, Redirects, Resolve, TaskTest, TestFailure, TlsVerification, ValidateResult,
+    DEFAULT_RETRIES, RUN,
+};
+
+pub(crate) trait SendWithRetry {
+    /// Send the request, retrying with backoff if the connection could not be established,
+    /// or was dropped while an idempotent request like a GET was on it.
+    /// Responses are never retried, whatever their status.
+    /// The body of the response is read before it is returned, and the time it all took counts towards the current test.
+    /// A body longer than [`crate::ValidationConfig::max_response_size`] is an error, which the failure of the test reports.
+    /// A run with its own transport sends the request through that instead, see [`crate::ValidationConfig::transport`].
+    async fn send_with_retry(self) -> Result<reqwest::Response, TestError>;
+}
+
+impl SendWithRetry for reqwest::RequestBuilder {
+    async fn send_with_retry(self) -> Result<reqwest::Response, TestError> {
+        let started = Instant::now();
+        let res = match send(self).await {
+            Ok((res, recording, requested)) => {
+                let redirected = match requested {
+                    Some(url) => check_redirect(&url, res.url()),
+                    None => Ok(()),
+                };
+                buffered(res, recording)
+                    .await
+                    .and_then(|res| redirected.map(|()| res))
+            }
+            // the test only sees that the request failed
+            Err(TransportError::Unknown(e)) => {
+                let _ = RUN.try_with(|r| r.request_error.set(Some(e.clone())));
+                Err(TestError::Request(e))
+            }
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e @ (TestError::TooLarge { .. } | TestError::Decompression { .. })) = &res {
+            let _ = RUN.try_with(|r| r.request_error.set(Some(e.to_string())));
+        }
+        let _ = RUN.try_with(|r| r.request_time.set(r.request_time.get() + started.elapsed()));
+        res
+    }
+}
+
+/// Send the request, with the retries of [`SendWithRetry::send_with_retry`].
+/// The response comes with the URL of the request if the client sent it,
+/// since only the client follows redirects, while another transport answers the request itself.
+async fn send(
+    req: reqwest::RequestBuilder,
+) -> Result<(reqwest::Response, Recording, Option<reqwest::Url>), TransportError> {
+    let (client, req) = req.build_split();
+    let mut req = req?;
+    let _ = RUN.try_with(|r| {
+        if let Some(endpoints) = &r.endpoints {
+            endpoints.add(req.method(), req.url().path());
+        }
+    });
+    let transport = RUN.try_with(|r| r.transport.clone()).ok().flatten();
+    let requested = transport.is_none().then(|| req.url().clone());
+    let transport = transport.as_deref().unwrap_or(&client);
+    let with_url =
+        |res: Result<_, _>| res.map(|(res, recording)| (res, recording, requested.clone()));
+    let retries = RUN.try_with(|r| r.retries).unwrap_or(DEFAULT_RETRIES);
+    let mut backoff = Duration::from_millis(250);
+    for _ in 0..retries {
+        // streaming bodies can't be cloned, so those requests are sent only once
+        let Some(next) = req.try_clone() else {
+            break;
+        };
+        match execute(transport, req).await {
+            Err(TransportError::Http(e)) if client::is_transient(&e, next.method()) => {
+                info!(error = %e, "Retrying request in {backoff:?}");
+                sleep(backoff).await;
+                backoff *= 2;
+                req = next;
+            }
+            res => return with_url(res),
+        }
+    }
+    let res = execute(transport, req).await;
+    if matches!(&res, Err(TransportError::Http(e)) if e.is_connect()) {
+        let _ = RUN.try_with(|r| r.connection_failed.set(true));
+    }
+    with_url(res)
+}
+
+/// Check that the response to a request of `url`, which came from `to`, did not arrive through a redirect.
+/// One that did is a warning, and fails the test in a strict run, see [`crate::Redirects::Follow`].
+fn check_redirect(url: &reqwest::Url, to: &reqwest::Url) -> Result<(), TestError> {
+    if url == to {
+        return Ok(());
+    }
+    let e = TestError::Mismatch {
+        expected: format!("a response from {}", url.path()),
+        actual: format!("a redirect to {to}"),
+    };
+    if strictness() == Strictness::Strict {
+        let _ = RUN.try_with(|r| r.request_error.set(Some(e.to_string())));
+        return Err(e);
+    }
+    let _ = RUN.try_with(|r| r.warnings.borrow_mut().push(e.to_string()));
+    Ok(())
+}
+
+/// Send a probe of [`crate::ValidationConfig::probe`] once, without retries, and read its response
+pub(crate) async fn send_probe(req: reqwest::RequestBuilder) -> Result<StatusCode, TransportError> {
+    let (client, req) = req.build_split();
+    let transport = RUN.try_with(|r| r.transport.clone()).ok().flatten();
+    let transport = transport.as_deref().unwrap_or(&client);
+    let (res, recording) = execute(transport, req?).await?;
+    let status = res.status();
+    // a body that is too long is not a problem of a probe
+    let _ = buffered(res, recording).await;
+    Ok(status)
+}
+
+/// Send `req` once. A failed attempt is recorded right away, a response once its body is read.
+async fn execute(
+    transport: &dyn Transport,
+    req: reqwest::Request,
+) -> Result<(reqwest::Response, Recording), TransportError> {
+    let recording = Recording::request(&req);
+    match transport.execute(req).await {
+        Ok(res) => Ok((res, recording)),
+        Err(e) => {
+            recording.finish(Err(e.to_string()));
+            Err(e)
+        }
+    }
+}
+
+/// The entry of a request in the HAR file of the run, if there is one, see [`crate::ValidationConfig::har`]
+struct Recording(Option<(Har, har::Pending)>);
+
+impl Recording {
+    fn request(req: &reqwest::Request) -> Self {
+        let har = RUN.try_with(|r| r.har.clone()).ok().flatten();
+        Self(har.map(|har| (har, har::Pending::request(req))))
+    }
+
+    fn finish(self, res: Result<har::Response<'_>, String>) {
+        if let Some((har, pending)) = self.0 {
+            har.record(pending, res);
+        }
+    }
+}
+
+/// The same response, with its body already read
+async fn buffered(
+    res: reqwest::Response,
+    recording: Recording,
+) -> Result<reqwest::Response, TestError> {
+    let (status, version, headers) = (res.status(), res.version(), res.headers().clone());
+    let url = res.url().clone();
+    let limit = RUN
+        .try_with(|r| r.max_response_size)
+        .unwrap_or(DEFAULT_MAX_RESPONSE_SIZE);
+    let compression = testing::compression(&headers);
+    let body = match testing::read_body(res, limit).await {
+        Ok(body) => match compression {
+            Some(encoding) => testing::decompress(encoding, &body, limit),
+            None => Ok(body),
+        },
+        e => e,
+    };
+    // with the headers as they were sent, and the body decompressed like the content of a HAR file is
+    recording.finish(match &body {
+        Ok(body) => Ok(har::Response {
+            status,
+            version,
+            headers: &headers,
+            body,
+        }),
+        Err(e) => Err(e.to_string()),
+    });
+    let mut buffered = http::Response::builder()
+        .status(status)
+        .version(version)
+        .url(url);
+    if let Some(h) = buffered.headers_mut() {
+        *h = headers;
+    }
+    let body = body?;
+    // the response is checked as if it was not compressed
+    if let (Some(encoding), Some(h)) = (compression, buffered.headers_mut()) {
+        h.remove(CONTENT_ENCODING);
+        h.insert(CONTENT_LENGTH, body.len().into());
+        let _ = RUN.try_with(|r| r.compressed.set(Some(encoding)));
+    }
+    Ok(buffered
+        .body(body)
+        .expect("the parts of a response to be valid")
+        .into())
+}
+
+/// The factor that the pauses of the current run are multiplied by, see [`crate::ValidationConfig::time_scale`]
+pub(crate) fn time_scale() -> f64 {
+    RUN.try_with(|r| r.time_scale).unwrap_or(1.0)
+}
+
+/// Whether the current run allows slack in timing-sensitive sequences, see [`crate::ValidationConfig::timing_slack`]
+pub(crate) fn timing_slack() -> bool {
+    RUN.try_with(|r| r.timing_slack).unwrap_or(false)
+}
+
+/// A generator of the data of the current run, forked from the one that its seed started,
+/// so that a run with the same [`crate::ValidationConfig::seed`] generates the same data.
+/// Outside of a run, a randomly seeded one.
+pub(crate) fn rng() -> fastrand::Rng {
+    RUN.try_with(|r| r.rng.borrow_mut().fork())
+        .unwrap_or_else(|_| fastrand::Rng::new())
+}
+
+/// How closely the responses of the current run have to follow the challenge text, see [`crate::ValidationConfig::strictness`]
+pub(crate) fn strictness() -> Strictness {
+    RUN.try_with(|r| r.strictness).unwrap_or_default()
+}
+
+/// `pause` multiplied by the time scale of the current run
+pub(crate) fn scaled(pause: Duration) -> Duration {
+    pause.mul_f64(time_scale())
+}
+
+/// How long the request before a run may take, see [`preflight`]
+const PREFLIGHT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Send a GET request to the base URL. Any response will do, whatever its status.
+/// On a transport error, returns why the request could not be sent, e.g. `connection refused`.
+pub(crate) async fn preflight(url: &str) -> Result<(), String> {
+    let e = match shared_client()
+        .get(url)
+        .timeout(PREFLIGHT_TIMEOUT)
+        .send()
+        .await
+    {
+        Ok(_) => return Ok(()),
+        Err(e) if e.is_timeout() => {
+            return Err(format!("no response within {PREFLIGHT_TIMEOUT:?}"))
+        }
+        Err(e) => e,
+    };
+    Err(testing::failure_reason(&e))
+}
+
+/// A client with these settings, see [`client::builder`]
+pub(crate) fn new_client(
+    tls: &TlsVerification,
+    proxy: &Proxy,
+    http_version: HttpVersion,
+    redirects: Redirects,
+    unix_socket: Option<&Path>,
+    resolve: &[Resolve],
+) -> Result<reqwest::Client, String> {
+    client::builder(tls, proxy, http_version, redirects, unix_socket, resolve)?
+        .build()
+        .map_err(|e| format!("Failed to build the client: {e}"))
+}
+
+/// The client of the current run, so that the requests of a challenge share its connection pool.
+/// Outside of a run, a new client.
+pub(crate) fn shared_client() -> reqwest::Client {
+    RUN.try_with(|r| r.client.clone()).unwrap_or_else(|_| {
+        new_client(
+            &TlsVerification::Default,
+            &Proxy::FromEnv,
+            HttpVersion::Http1,
+            Redirects::default(),
+            None,
+            &[],
+        )
+        .expect("a client with the default settings")
+    })
+}
+
+/// A new client with its own cookie jar, which is never shared
+pub(crate) fn new_client_with_cookies() -> reqwest::Client {
+    let redirects = RUN.try_with(|r| r.redirects).unwrap_or_default();
+    run_client_base(redirects)
+        .cookie_store(true)
+        .build()
+        .unwrap()
+}
+
+/// A new client that doesn't follow redirects, whatever the run says, which is never shared
+pub(crate) fn new_client_without_redirects() -> reqwest::Client {
+    run_client_base(Redirects::Disabled).build().unwrap()
+}
+
+/// A builder of a client with the settings of the current run, except for its `redirects`
+fn run_client_base(redirects: Redirects) -> reqwest::ClientBuilder {
+    let (tls, proxy, http_version, unix_socket, resolve) = RUN
+        .try_with(|r| {
+            let (unix_socket, resolve) = (r.unix_socket.clone(), r.resolve.clone());
+            (
+                r.tls.clone(),
+                r.proxy.clone(),
+                r.http_version,
+                unix_socket,
+                resolve,
+            )
+        })
+        .unwrap_or_default();
+    client::builder(
+        &tls,
+        &proxy,
+        http_version,
+        redirects,
+        unix_socket.as_deref(),
+        &resolve,
+    )
+    .expect("the settings of the run, which built its client already")
+}
+
+macro_rules! assert_status {
+    ($res:expr, $test:expr, $expected_status:expr) => {
+        shuttlings::testing::check_status($res.status(), $expected_status)
+            .map_err(|e| $crate::TestFailure::failed($test, e))?;
+    };
+}
+
+macro_rules! assert_headers {
+    ($res:expr, $test:expr, $expected_headers:expr) => {
+        shuttlings::testing::check_headers($res.headers(), $expected_headers)
+            .map_err(|e| $crate::TestFailure::failed($test, e))?;
+    };
+}
+
+/// Fail `test` if the response does not have the media type that the challenge requires, in a strict run.
+/// Otherwise a different one is a warning, see [`crate::ValidationConfig::strictness`].
+pub(crate) fn check_content_type(
+    test: TaskTest,
+    actual: &reqwest::header::HeaderMap,
+    expected: &str,
+) -> ValidateResult {
+    let Err(e) = testing::check_content_type(actual, expected) else {
+        return Ok(());
+    };
+    if strictness() == Strictness::Strict {
+        return Err(TestFailure::failed(test, e));
+    }
+    let _ = RUN.try_with(|r| r.warnings.borrow_mut().push(e.to_string()));
+    Ok(())
+}
+
+macro_rules! assert_content_type {
+    ($res:expr, $test:expr, $expected_type:expr) => {
+        $crate::helpers::check_content_type($test, $res.headers(), $expected_type)?;
+    };
+}
+
+macro_rules! assert_text {
+    ($res:expr, $test:expr, $expected_text:expr) => {
+        let expected = $expected_text;
+        let text = $res.text().await.map_err(|_| $test)?;
+        let text = $crate::helpers::strictness().text(&text, &expected);
+        shuttlings::testing::check_text(text, &expected)
+            .map_err(|e| $crate::TestFailure::failed($test, e))?;
+    };
+}
+
+macro_rules! assert_json {
+    ($res:expr, $test:expr, $expected_json:expr) => {
+        let json = $res.json::<serde_json::Value>().await.map_err(|_| $test)?;
+        shuttlings::testing::check_json(&json, &$expected_json)
+            .map_err(|e| $crate::TestFailure::failed($test, e))?;
+    };
+}
+
+macro_rules! assert_json_float_near {
+    ($json:expr, $test:expr, $pointer:expr, $expected:expr, $tol:expr) => {
+        let tol = $crate::helpers::strictness().tolerance($tol);
+        shuttlings::testing::json_float_near(&$json, $pointer, $expected, tol)
+            .map_err(|e| $crate::TestFailure::failed($test, e))?;
+    };
+}
+
+macro_rules! assert_text_starts_with {
+    ($res:expr, $test:expr, $expected_text:expr) => {
+        let expected = $expected_text;
+        let text = $res.text().await.map_err(|_| $test)?;
+        if !text.starts_with(expected) {
+            return Err($crate::TestFailure::mismatch(
+                $test,
+                format!("{expected:?}…"),
+                format!("{text:?}"),
+            ));
+        }
+    };
+}
+
+macro_rules! assert_ {
+    ($test:expr, $expected_true:expr) => {
+        if !$expected_true {
+            return Err($test.into());
+        }
+    };
+}
+
+macro_rules! assert_eq_ {
+    ($test:expr, $left:expr, $right:expr) => {
+        let (left, right) = ($left, $right);
+        if left != right {
+            return Err($crate::TestFailure::mismatch(
+                $test,
+                format!("{right:?}"),
+                format!("{left:?}"),
+            ));
+        }
+    };
+}
+
+macro_rules! assert_neq_ {
+    ($test:expr, $left:expr, $right:expr) => {
+        if $left == $right {
+            return Err($test.into());
+        }
+    };
+}
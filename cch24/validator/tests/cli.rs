@@ -0,0 +1,44 @@
+//! Runs of the validator binary, for what only its `main` does
+
+use std::{
+    process::{Command, Stdio},
+    time::{Duration, Instant},
+};
+
+/// How long a run that needs no server may take before it counts as hanging
+const RUN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Run the validator with `config` as its config file and `args`, and return its exit code and output
+fn validate(name: &str, config: &str, args: &[&str]) -> (Option<i32>, String) {
+    let path = std::env::temp_dir().join(format!("cch24-{name}-{}.toml", std::process::id()));
+    std::fs::write(&path, config).unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_cch24-validator"))
+        .arg("--config")
+        .arg(&path)
+        .args(["--offline", "--no-color", "--url", "http://127.0.0.1:1"])
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let started = Instant::now();
+    while child.try_wait().unwrap().is_none() {
+        if started.elapsed() > RUN_TIMEOUT {
+            child.kill().unwrap();
+            panic!("the validator did not exit within {RUN_TIMEOUT:?}");
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    let output = child.wait_with_output().unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    (output.status.code(), text)
+}
+
+#[test]
+fn unsupported_challenges_in_the_config_file_are_rejected() {
+    let (code, output) = validate("unsupported", "challenges = [3]\n", &[]);
+    assert!(output.contains("challenge 3 is not supported (did you mean 2 or 5?)"));
+    assert_eq!(code, Some(3));
+}
@@ -0,0 +1,311 @@
+//! Reference solutions of some challenges, served on a random port, that the validator must accept.
+//! Each challenge also has a solution with one wrong endpoint, that must fail at the test it breaks.
+
+use std::{
+    collections::HashMap,
+    net::{Ipv4Addr, Ipv6Addr},
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    extract::Query,
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use cch24_validator::{run_with_progress, RunOutcome, ValidationConfig};
+use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use shuttlings::{SubmissionUpdate, TestResult};
+use tokio::time::Instant;
+
+/// Serve `app` on a random port, and validate the challenge `number` against it
+async fn validate(
+    app: Router,
+    number: &str,
+    task: Option<i32>,
+) -> (RunOutcome, Vec<SubmissionUpdate>) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+    let config = ValidationConfig {
+        task,
+        ..Default::default()
+    };
+    let mut updates = Vec::new();
+    let ended = run_with_progress(url, number, &config, |update| match update {
+        SubmissionUpdate::TestResult(result) => {
+            updates.push(SubmissionUpdate::TestResult(TestResult {
+                duration_ms: 0,
+                ..result.clone()
+            }))
+        }
+        u @ SubmissionUpdate::TaskCompleted(..) => updates.push(u.clone()),
+        _ => (),
+    })
+    .await;
+    server.abort();
+    (ended.reason, updates)
+}
+
+/// The updates of a task whose `tests` all passed, then completed it
+fn passed(task: i32, tests: i32, completed: (bool, i32)) -> Vec<SubmissionUpdate> {
+    (1..=tests)
+        .map(|test| {
+            SubmissionUpdate::TestResult(TestResult {
+                task,
+                test,
+                passed: true,
+                duration_ms: 0,
+                detail: None,
+            })
+        })
+        .chain([completed.into()])
+        .collect()
+}
+
+fn day_minus1(wrong: bool) -> Router {
+    let location = match wrong {
+        false => "https://www.youtube.com/watch?v=9Gc4QTqslN4",
+        true => "https://www.youtube.com/",
+    };
+    Router::new()
+        .route("/", get(|| async { "Hello, bird!" }))
+        .route(
+            "/-1/seek",
+            get(move || async move { (StatusCode::FOUND, [(header::LOCATION, location)]) }),
+        )
+}
+
+#[tokio::test]
+async fn day_minus1_is_solved() {
+    let (outcome, updates) = validate(day_minus1(false), "-1", None).await;
+    assert_eq!(outcome, RunOutcome::Completed);
+    assert_eq!(
+        updates,
+        [passed(1, 1, (true, 0)), passed(2, 1, (false, 0))].concat()
+    );
+    let (outcome, _) = validate(day_minus1(true), "-1", Some(2)).await;
+    assert_eq!(outcome, RunOutcome::Failed { task: 2, test: 1 });
+}
+
+fn day02(wrong: bool) -> Router {
+    let add = match wrong {
+        false => u8::wrapping_add,
+        // the octets stop at 255 instead of overflowing
+        true => u8::saturating_add,
+    };
+    let octets = |from: HashMap<String, Ipv4Addr>, f: fn(u8, u8) -> u8, a: &str, b: &str| {
+        let (Some(a), Some(b)) = (from.get(a), from.get(b)) else {
+            return StatusCode::BAD_REQUEST.into_response();
+        };
+        let (a, b) = (a.octets(), b.octets());
+        Ipv4Addr::from(std::array::from_fn::<_, 4, _>(|i| f(a[i], b[i])))
+            .to_string()
+            .into_response()
+    };
+    let xor = |from: HashMap<String, Ipv6Addr>, a: &str, b: &str| {
+        let (Some(a), Some(b)) = (from.get(a), from.get(b)) else {
+            return StatusCode::BAD_REQUEST.into_response();
+        };
+        Ipv6Addr::from(a.to_bits() ^ b.to_bits())
+            .to_string()
+            .into_response()
+    };
+    Router::new()
+        .route(
+            "/2/dest",
+            get(move |Query(q)| async move { octets(q, add, "from", "key") }),
+        )
+        .route(
+            "/2/key",
+            get(move |Query(q)| async move { octets(q, u8::wrapping_sub, "to", "from") }),
+        )
+        .route(
+            "/2/v6/dest",
+            get(move |Query(q)| async move { xor(q, "from", "key") }),
+        )
+        .route(
+            "/2/v6/key",
+            get(move |Query(q)| async move { xor(q, "from", "to") }),
+        )
+}
+
+#[tokio::test]
+async fn day02_is_solved() {
+    let (outcome, updates) = validate(day02(false), "2", None).await;
+    assert_eq!(outcome, RunOutcome::Completed);
+    assert_eq!(
+        updates,
+        [
+            passed(1, 3, (false, 0)),
+            passed(2, 3, (true, 0)),
+            passed(3, 6, (false, 50)),
+        ]
+        .concat()
+    );
+    let (outcome, _) = validate(day02(true), "2", None).await;
+    assert_eq!(outcome, RunOutcome::Failed { task: 1, test: 2 });
+}
+
+/// A bucket of milk that holds `capacity` liters, and refills by one liter per second
+struct Bucket {
+    capacity: f64,
+    liters: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn withdraw(&mut self) -> bool {
+        let now = Instant::now();
+        let refilled = now.duration_since(self.updated).as_secs_f64();
+        self.liters = (self.liters + refilled).min(self.capacity);
+        self.updated = now;
+        if self.liters < 1.0 {
+            return false;
+        }
+        self.liters -= 1.0;
+        true
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Units {
+    liters: Option<f32>,
+    gallons: Option<f32>,
+    litres: Option<f32>,
+    pints: Option<f32>,
+}
+
+impl Units {
+    /// The volume in the other unit of its system, if exactly one unit was given
+    fn convert(&self) -> Option<Value> {
+        let converted = match (self.liters, self.gallons, self.litres, self.pints) {
+            (Some(l), None, None, None) => json!({"gallons": l / 3.785_411_8}),
+            (None, Some(g), None, None) => json!({"liters": g * 3.785_411_8}),
+            (None, None, Some(l), None) => json!({"pints": l / 0.568_261_25}),
+            (None, None, None, Some(p)) => json!({"litres": p * 0.568_261_25}),
+            _ => return None,
+        };
+        Some(converted)
+    }
+}
+
+fn day09(wrong: bool) -> Router {
+    let capacity = match wrong {
+        false => 5.0,
+        // one more liter than the bucket should hold
+        true => 6.0,
+    };
+    let bucket = Arc::new(Mutex::new(Bucket {
+        capacity,
+        liters: capacity,
+        updated: Instant::now(),
+    }));
+    let refill = bucket.clone();
+    Router::new()
+        .route(
+            "/9/milk",
+            post(move |headers: HeaderMap, body: String| async move {
+                if !bucket.lock().unwrap().withdraw() {
+                    return (StatusCode::TOO_MANY_REQUESTS, "No milk available\n").into_response();
+                }
+                if headers
+                    .get(header::CONTENT_TYPE)
+                    .and_then(|c| c.to_str().ok())
+                    != Some("application/json")
+                {
+                    return "Milk withdrawn\n".into_response();
+                }
+                match serde_json::from_str::<Units>(&body)
+                    .ok()
+                    .and_then(|u| u.convert())
+                {
+                    Some(converted) => Json(converted).into_response(),
+                    None => StatusCode::BAD_REQUEST.into_response(),
+                }
+            }),
+        )
+        .route(
+            "/9/refill",
+            post(move || async move {
+                let mut bucket = refill.lock().unwrap();
+                bucket.liters = bucket.capacity;
+                bucket.updated = Instant::now();
+            }),
+        )
+}
+
+/// This one takes a while, the validator waits for the bucket to refill between tasks
+#[tokio::test]
+async fn day09_is_solved() {
+    let (outcome, updates) = validate(day09(false), "9", None).await;
+    assert_eq!(outcome, RunOutcome::Completed);
+    assert_eq!(
+        updates,
+        [
+            passed(1, 1, (false, 0)),
+            passed(2, 11, (false, 0)),
+            passed(3, 7, (true, 0)),
+            passed(4, 2, (false, 75)),
+        ]
+        .concat()
+    );
+    let (outcome, _) = validate(day09(true), "9", None).await;
+    assert_eq!(outcome, RunOutcome::Failed { task: 1, test: 1 });
+}
+
+const SECRET: &[u8] = b"reference solution";
+
+fn day16(wrong: bool) -> Router {
+    Router::new()
+        .route(
+            "/16/wrap",
+            post(|Json(payload): Json<Value>| async move {
+                let key = EncodingKey::from_secret(SECRET);
+                match jsonwebtoken::encode(&Header::default(), &payload, &key) {
+                    Ok(jwt) => [(header::SET_COOKIE, format!("gift={jwt}"))].into_response(),
+                    Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+                }
+            }),
+        )
+        .route(
+            "/16/unwrap",
+            get(move |headers: HeaderMap| async move {
+                let gift = headers
+                    .get_all(header::COOKIE)
+                    .iter()
+                    .filter_map(|c| c.to_str().ok())
+                    .flat_map(|c| c.split("; "))
+                    .find_map(|c| c.strip_prefix("gift="));
+                let Some(gift) = gift else {
+                    return match wrong {
+                        false => StatusCode::BAD_REQUEST.into_response(),
+                        // an empty gift instead of none
+                        true => Json(json!({})).into_response(),
+                    };
+                };
+                let mut validation = Validation::default();
+                validation.required_spec_claims.clear();
+                validation.validate_exp = false;
+                let key = DecodingKey::from_secret(SECRET);
+                match jsonwebtoken::decode::<Value>(gift, &key, &validation) {
+                    Ok(token) => Json(token.claims).into_response(),
+                    Err(_) => StatusCode::BAD_REQUEST.into_response(),
+                }
+            }),
+        )
+}
+
+/// Only the first task, decoding the tokens of the second one needs a PEM key
+#[tokio::test]
+async fn day16_is_solved() {
+    let (outcome, updates) = validate(day16(false), "16", Some(1)).await;
+    assert_eq!(outcome, RunOutcome::Completed);
+    assert_eq!(updates, passed(1, 4, (true, 0)));
+    let (outcome, _) = validate(day16(true), "16", Some(1)).await;
+    assert_eq!(outcome, RunOutcome::Failed { task: 1, test: 3 });
+}
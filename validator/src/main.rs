@@ -0,0 +1,124 @@
+//! Validates the challenges of any year, with the validator of each year
+
+use cch23_validator::Cch23;
+use cch24_validator::Cch24;
+use clap::Parser;
+use shuttlings::{
+    client::normalize_url, RunOutcome, SinkClosed, SubmissionOutcome, SubmissionSink,
+    SubmissionUpdate, Validator,
+};
+
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Args {
+    /// The challenges to validate, with the year they are from as a prefix like `cch23:5`,
+    /// or without one if --year is set
+    #[arg(required = true)]
+    challenges: Vec<String>,
+    /// The year of the challenges that have no prefix
+    #[arg(long, value_parser = ["2023", "2024"])]
+    year: Option<String>,
+    /// The base URL to test against
+    #[arg(long, short, default_value = "http://127.0.0.1:8000", value_parser = normalize_url)]
+    url: String,
+}
+
+/// The year and number of a challenge, from a prefix like `cch23:` or from `year`
+fn select<'a>(challenge: &'a str, year: Option<&str>) -> Result<(u16, &'a str), String> {
+    let (year, number) = match challenge.split_once(':') {
+        Some(("cch23", number)) => ("2023", number),
+        Some(("cch24", number)) => ("2024", number),
+        Some((prefix, _)) => return Err(format!("{prefix} is not cch23 or cch24")),
+        None => match year {
+            Some(year) => (year, challenge),
+            None => {
+                return Err(format!(
+                    "{challenge} needs a cch23: or cch24: prefix, or --year"
+                ))
+            }
+        },
+    };
+    Ok((year.parse().map_err(|e| format!("{e}"))?, number))
+}
+
+/// Prints the log lines and completed tasks of a submission
+struct Printer;
+
+impl SubmissionSink for Printer {
+    async fn send(&self, update: SubmissionUpdate) -> Result<(), SinkClosed> {
+        match update {
+            SubmissionUpdate::LogLine { message, .. } => println!("{message}"),
+            SubmissionUpdate::TaskCompleted(last_core, bonus) => {
+                println!("Task completed 🎉");
+                if bonus > 0 {
+                    println!("Bonus points: {bonus} ✨");
+                }
+                if last_core {
+                    println!("Core tasks completed ✅");
+                }
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+}
+
+async fn validate(validator: &impl Validator, url: &str, number: &str) -> SubmissionOutcome {
+    println!();
+    println!("Validating Challenge {number} of {}...", validator.year());
+    println!();
+    let config = Default::default();
+    validator
+        .run(url.to_owned(), number, Printer, &config)
+        .await
+}
+
+/// The same exit codes as the validator of each year
+fn exit_code(outcome: RunOutcome) -> i32 {
+    match outcome {
+        RunOutcome::Completed => 0,
+        RunOutcome::Failed { .. } => 1,
+        RunOutcome::TimedOut
+        | RunOutcome::ConnectionFailed
+        | RunOutcome::Cancelled
+        | RunOutcome::InternalError => 2,
+        RunOutcome::Unsupported => 3,
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+    let selected = args
+        .challenges
+        .iter()
+        .map(|c| select(c, args.year.as_deref()))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap_or_else(|e| {
+            eprintln!("error: {e}");
+            std::process::exit(3);
+        });
+    let mut code = 0;
+    for (year, number) in selected {
+        let ended = match year {
+            2023 => validate(&Cch23, &args.url, number).await,
+            _ => validate(&Cch24, &args.url, number).await,
+        };
+        code = code.max(exit_code(ended.reason));
+    }
+    std::process::exit(code);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn challenges_are_selected_by_prefix_or_year() {
+        assert_eq!(select("cch23:5", None), Ok((2023, "5")));
+        assert_eq!(select("cch24:-1", Some("2023")), Ok((2024, "-1")));
+        assert_eq!(select("12", Some("2024")), Ok((2024, "12")));
+        assert!(select("12", None).is_err());
+        assert!(select("cch22:1", None).is_err());
+    }
+}